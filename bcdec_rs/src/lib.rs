@@ -107,7 +107,7 @@ pub fn bc4(
     destination_pitch: usize,
     is_signed: bool,
 ) {
-    bc4_block(
+    decode_bc4_block(
         compressed_block,
         decompressed_block,
         destination_pitch,
@@ -133,7 +133,7 @@ pub fn bc4_float(
     destination_pitch: usize,
     is_signed: bool,
 ) {
-    bc4_block_float(
+    decode_bc4_block_float(
         compressed_block,
         decompressed_block,
         destination_pitch,
@@ -159,14 +159,14 @@ pub fn bc5(
     destination_pitch: usize,
     is_signed: bool,
 ) {
-    bc4_block(
+    decode_bc4_block(
         compressed_block,
         decompressed_block,
         destination_pitch,
         2,
         is_signed,
     );
-    bc4_block(
+    decode_bc4_block(
         &compressed_block[8..],
         &mut decompressed_block[1..],
         destination_pitch,
@@ -192,14 +192,14 @@ pub fn bc5_float(
     destination_pitch: usize,
     is_signed: bool,
 ) {
-    bc4_block_float(
+    decode_bc4_block_float(
         compressed_block,
         decompressed_block,
         destination_pitch,
         2,
         is_signed,
     );
-    bc4_block_float(
+    decode_bc4_block_float(
         &compressed_block[8..],
         &mut decompressed_block[1..],
         destination_pitch,
@@ -1228,6 +1228,96 @@ pub fn bc7(compressed_block: &[u8], decompressed_block: &mut [u8], destination_p
     }
 }
 
+/// Decode 8 bytes from `compressed_block` to a 4x4 block of RGBA8 pixels in row-major order.
+///
+/// # Examples
+///
+/// ```rust
+/// let decompressed_block = bcdec_rs::bc1_block(&[0u8; 8]);
+/// assert_eq!([[[0, 0, 0, 255]; 4]; 4], decompressed_block);
+/// ```
+pub fn bc1_block(compressed_block: &[u8; 8]) -> [[[u8; 4]; 4]; 4] {
+    let mut decompressed_block = [0u8; 4 * 4 * 4];
+    bc1(compressed_block, &mut decompressed_block, 4 * 4);
+    pixel_rows(&decompressed_block)
+}
+
+/// Decode 16 bytes from `compressed_block` to a 4x4 block of RGBA8 pixels in row-major order.
+///
+/// # Examples
+///
+/// ```rust
+/// let decompressed_block = bcdec_rs::bc2_block(&[0u8; 16]);
+/// assert_eq!([[[0, 0, 0, 0]; 4]; 4], decompressed_block);
+/// ```
+pub fn bc2_block(compressed_block: &[u8; 16]) -> [[[u8; 4]; 4]; 4] {
+    let mut decompressed_block = [0u8; 4 * 4 * 4];
+    bc2(compressed_block, &mut decompressed_block, 4 * 4);
+    pixel_rows(&decompressed_block)
+}
+
+/// Decode 16 bytes from `compressed_block` to a 4x4 block of RGBA8 pixels in row-major order.
+///
+/// # Examples
+///
+/// ```rust
+/// let decompressed_block = bcdec_rs::bc3_block(&[0u8; 16]);
+/// assert_eq!([[[0, 0, 0, 0]; 4]; 4], decompressed_block);
+/// ```
+pub fn bc3_block(compressed_block: &[u8; 16]) -> [[[u8; 4]; 4]; 4] {
+    let mut decompressed_block = [0u8; 4 * 4 * 4];
+    bc3(compressed_block, &mut decompressed_block, 4 * 4);
+    pixel_rows(&decompressed_block)
+}
+
+/// Decode 8 bytes from `compressed_block` to a 4x4 block of R8 pixels in row-major order.
+///
+/// # Examples
+///
+/// ```rust
+/// let decompressed_block = bcdec_rs::bc4_block(&[0u8; 8], false);
+/// assert_eq!([[0; 4]; 4], decompressed_block);
+/// ```
+pub fn bc4_block(compressed_block: &[u8; 8], is_signed: bool) -> [[u8; 4]; 4] {
+    let mut decompressed_block = [0u8; 4 * 4];
+    bc4(compressed_block, &mut decompressed_block, 4, is_signed);
+    pixel_rows::<1>(&decompressed_block).map(|row| row.map(|[r]| r))
+}
+
+/// Decode 16 bytes from `compressed_block` to a 4x4 block of RG8 pixels in row-major order.
+///
+/// # Examples
+///
+/// ```rust
+/// let decompressed_block = bcdec_rs::bc5_block(&[0u8; 16], false);
+/// assert_eq!([[[0, 0]; 4]; 4], decompressed_block);
+/// ```
+pub fn bc5_block(compressed_block: &[u8; 16], is_signed: bool) -> [[[u8; 2]; 4]; 4] {
+    let mut decompressed_block = [0u8; 4 * 4 * 2];
+    bc5(compressed_block, &mut decompressed_block, 4 * 2, is_signed);
+    pixel_rows(&decompressed_block)
+}
+
+/// Decode 16 bytes from `compressed_block` to a 4x4 block of RGBA8 pixels in row-major order.
+///
+/// # Examples
+///
+/// ```rust
+/// let decompressed_block = bcdec_rs::bc7_block(&[0u8; 16]);
+/// assert_eq!([[[0, 0, 0, 0]; 4]; 4], decompressed_block);
+/// ```
+pub fn bc7_block(compressed_block: &[u8; 16]) -> [[[u8; 4]; 4]; 4] {
+    let mut decompressed_block = [0u8; 4 * 4 * 4];
+    bc7(compressed_block, &mut decompressed_block, 4 * 4);
+    pixel_rows(&decompressed_block)
+}
+
+fn pixel_rows<const N: usize>(decompressed_block: &[u8]) -> [[[u8; N]; 4]; 4] {
+    core::array::from_fn(|i| {
+        core::array::from_fn(|j| core::array::from_fn(|c| decompressed_block[(i * 4 + j) * N + c]))
+    })
+}
+
 fn color_block(
     compressed_block: &[u8],
     decompressed_block: &mut [u8],
@@ -1353,7 +1443,7 @@ fn smooth_alpha_block(
     }
 }
 
-fn bc4_block(
+fn decode_bc4_block(
     compressed_block: &[u8],
     decompressed_block: &mut [u8],
     destination_pitch: usize,
@@ -1412,7 +1502,7 @@ fn bc4_block(
     }
 }
 
-fn bc4_block_float(
+fn decode_bc4_block_float(
     compressed_block: &[u8],
     decompressed_block: &mut [f32],
     destination_pitch: usize,
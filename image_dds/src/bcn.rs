@@ -2,7 +2,7 @@ mod decode;
 #[cfg(feature = "encode")]
 mod encode;
 
-pub use decode::decode_bcn;
+pub use decode::{bc7_rotation, decode_bcn};
 #[cfg(feature = "encode")]
 pub use encode::encode_bcn;
 
@@ -20,4 +20,8 @@ pub struct Bc4S;
 pub struct Bc5;
 pub struct Bc5S;
 pub struct Bc6;
+pub struct Bc6S;
 pub struct Bc7;
+// BC7 with opaque alpha for encoding.
+#[cfg(feature = "encode")]
+pub struct Bc7Opaque;
@@ -1,8 +1,13 @@
 use bytemuck::Pod;
 
-use crate::{error::SurfaceError, mip_size, snorm_to_unorm};
+use crate::{
+    error::SurfaceError, float_to_unorm8, mip_size, snorm_to_unorm, DecodeOptions,
+    InvalidBlockHandling,
+};
 
-use super::{Bc1, Bc2, Bc3, Bc4, Bc4S, Bc5, Bc5S, Bc6, Bc7, BLOCK_HEIGHT, BLOCK_WIDTH, CHANNELS};
+use super::{
+    Bc1, Bc2, Bc3, Bc4, Bc4S, Bc5, Bc5S, Bc6, Bc6S, Bc7, BLOCK_HEIGHT, BLOCK_WIDTH, CHANNELS,
+};
 
 pub trait BcnDecode<Pixel> {
     type CompressedBlock;
@@ -10,6 +15,11 @@ pub trait BcnDecode<Pixel> {
     // The decoded 4x4 pixel blocks are in row-major ordering.
     // Fixing the length should reduce the amount of bounds checking.
     fn decompress_block(block: &Self::CompressedBlock) -> [[Pixel; BLOCK_WIDTH]; BLOCK_HEIGHT];
+
+    // Blocks with reserved encodings still decompress but may need to be reported.
+    fn is_valid_block(_block: &Self::CompressedBlock) -> bool {
+        true
+    }
 }
 
 // Allows block types to read and copy buffer data to enforce alignment.
@@ -267,45 +277,68 @@ impl BcnDecode<[f32; 4]> for Bc6 {
     type CompressedBlock = [u8; 16];
 
     fn decompress_block(block: &[u8; 16]) -> [[[f32; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT] {
-        // BC6H uses half precision floating point data.
-        // Convert to single precision since f32 is better supported on CPUs.
-        let mut decompressed_rgb = [[[0.0; 3]; BLOCK_WIDTH]; BLOCK_HEIGHT];
+        decompress_bc6h_block(block, false)
+    }
+}
 
-        // Cast the pointer to a less strictly aligned type.
-        // The pitch is in terms of floats rather than bytes.
-        bcdec_rs::bc6h_float(
-            block,
-            bytemuck::cast_slice_mut(&mut decompressed_rgb),
-            BLOCK_WIDTH * 3,
-            // TODO: signed vs unsigned?
-            false,
-        );
+impl BcnDecode<[u8; 4]> for Bc6 {
+    type CompressedBlock = [u8; 16];
 
-        // Pad to RGBA with alpha set to white.
-        let mut decompressed = [[[0.0; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT];
-        for y in 0..BLOCK_HEIGHT {
-            for x in 0..BLOCK_HEIGHT {
-                let [r, g, b] = decompressed_rgb[y][x];
-                decompressed[y][x] = [r, g, b, 1.0];
-            }
-        }
+    fn decompress_block(block: &[u8; 16]) -> [[[u8; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT] {
+        let decompressed: [[[f32; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT] = Bc6::decompress_block(block);
 
-        decompressed
+        decompressed.map(|row| row.map(|pixel| pixel.map(float_to_unorm8)))
     }
 }
 
-impl BcnDecode<[u8; 4]> for Bc6 {
+impl BcnDecode<[f32; 4]> for Bc6S {
+    type CompressedBlock = [u8; 16];
+
+    fn decompress_block(block: &[u8; 16]) -> [[[f32; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT] {
+        decompress_bc6h_block(block, true)
+    }
+}
+
+impl BcnDecode<[u8; 4]> for Bc6S {
     type CompressedBlock = [u8; 16];
 
     fn decompress_block(block: &[u8; 16]) -> [[[u8; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT] {
-        let decompressed: [[[f32; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT] = Bc6::decompress_block(block);
+        // Negative values are clamped to 0.
+        let decompressed: [[[f32; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT] = Bc6S::decompress_block(block);
 
-        // Truncate to clamp to 0 to 255.
-        let float_to_u8 = |x: f32| (x * 255.0) as u8;
-        decompressed.map(|row| row.map(|pixel| pixel.map(float_to_u8)))
+        decompressed.map(|row| row.map(|pixel| pixel.map(float_to_unorm8)))
     }
 }
 
+fn decompress_bc6h_block(
+    block: &[u8; 16],
+    is_signed: bool,
+) -> [[[f32; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT] {
+    // BC6H uses half precision floating point data.
+    // Convert to single precision since f32 is better supported on CPUs.
+    let mut decompressed_rgb = [[[0.0; 3]; BLOCK_WIDTH]; BLOCK_HEIGHT];
+
+    // Cast the pointer to a less strictly aligned type.
+    // The pitch is in terms of floats rather than bytes.
+    bcdec_rs::bc6h_float(
+        block,
+        bytemuck::cast_slice_mut(&mut decompressed_rgb),
+        BLOCK_WIDTH * 3,
+        is_signed,
+    );
+
+    // Pad to RGBA with alpha set to white.
+    let mut decompressed = [[[0.0; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT];
+    for y in 0..BLOCK_HEIGHT {
+        for x in 0..BLOCK_HEIGHT {
+            let [r, g, b] = decompressed_rgb[y][x];
+            decompressed[y][x] = [r, g, b, 1.0];
+        }
+    }
+
+    decompressed
+}
+
 impl BcnDecode<[u8; 4]> for Bc7 {
     type CompressedBlock = [u8; 16];
 
@@ -320,12 +353,34 @@ impl BcnDecode<[u8; 4]> for Bc7 {
 
         decompressed
     }
+
+    fn is_valid_block(block: &[u8; 16]) -> bool {
+        // The mode is the index of the lowest set bit.
+        // Blocks without any mode bits set use the reserved mode 8.
+        block[0] != 0
+    }
+}
+
+/// The rotation for a BC7 block that swaps alpha with red (1), green (2), or blue (3).
+/// Only modes 4 and 5 support rotation, so all other modes return 0.
+pub fn bc7_rotation(block: &[u8; 16]) -> u8 {
+    // The rotation bits immediately follow the mode bits.
+    match block[0].trailing_zeros() {
+        4 => (block[0] >> 5) & 0b11,
+        5 => (block[0] >> 6) & 0b11,
+        _ => 0,
+    }
 }
 
 /// Decompress the bytes in `data` to the uncompressed RGBA8 format.
-pub fn decode_bcn<F, T>(width: u32, height: u32, data: &[u8]) -> Result<Vec<T>, SurfaceError>
+pub fn decode_bcn<F, T>(
+    width: u32,
+    height: u32,
+    data: &[u8],
+    options: DecodeOptions,
+) -> Result<Vec<T>, SurfaceError>
 where
-    T: Copy + Default + Pod,
+    T: Copy + Default + Pod + Send,
     F: BcnDecode<[T; 4]>,
     F::CompressedBlock: ReadBlock,
 {
@@ -356,33 +411,83 @@ where
     }
 
     let mut rgba = vec![T::default(); width as usize * height as usize * CHANNELS];
+    if rgba.is_empty() {
+        return Ok(rgba);
+    }
+
+    // Each row of blocks updates up to 4 rows of pixels and can be decoded independently.
+    let row_size = width as usize * BLOCK_HEIGHT * CHANNELS;
+
+    // Collect all results to report the first invalid block like the serial path.
+    #[cfg(feature = "rayon")]
+    {
+        use rayon::prelude::*;
+        rgba.par_chunks_mut(row_size)
+            .enumerate()
+            .map(|(i, rgba_row)| {
+                decode_block_row::<F, T>(rgba_row, data, (i * BLOCK_HEIGHT) as u32, width, options)
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .collect::<Result<(), _>>()?;
+    }
+
+    #[cfg(not(feature = "rayon"))]
+    {
+        rgba.chunks_mut(row_size)
+            .enumerate()
+            .try_for_each(|(i, rgba_row)| {
+                decode_block_row::<F, T>(rgba_row, data, (i * BLOCK_HEIGHT) as u32, width, options)
+            })?;
+    }
+
+    Ok(rgba)
+}
+
+fn decode_block_row<F, T>(
+    rgba_row: &mut [T],
+    data: &[u8],
+    y: u32,
+    width: u32,
+    options: DecodeOptions,
+) -> Result<(), SurfaceError>
+where
+    T: Copy + Default + Pod,
+    F: BcnDecode<[T; 4]>,
+    F::CompressedBlock: ReadBlock,
+{
+    // The last row of blocks may cover fewer than 4 rows of pixels.
+    let rows = rgba_row.len() / (width as usize * CHANNELS);
 
     // BCN formats lay out blocks in row-major order.
-    // TODO: calculate x and y using division and mod?
-    let mut block_start = 0;
-    for y in (0..height).step_by(BLOCK_HEIGHT) {
-        for x in (0..width).step_by(BLOCK_WIDTH) {
-            // Use a special type to enforce alignment.
-            let block = F::CompressedBlock::read_block(data, block_start);
-            // TODO: Add rgba8 and rgbaf32 variants for decompress block.
-            let decompressed_block = F::decompress_block(&block);
-
-            // TODO: This can be generic over the pixel type to also support float.
-            // Each block is 4x4, so we need to update multiple rows.
-            put_rgba_block(
-                &mut rgba,
-                decompressed_block,
-                x as usize,
-                y as usize,
-                width as usize,
-                height as usize,
-            );
-
-            block_start += F::CompressedBlock::SIZE_IN_BYTES;
+    let blocks_per_row = (width as usize).div_ceil(BLOCK_WIDTH);
+    let mut block_start =
+        y as usize / BLOCK_HEIGHT * blocks_per_row * F::CompressedBlock::SIZE_IN_BYTES;
+
+    for x in (0..width).step_by(BLOCK_WIDTH) {
+        // Use a special type to enforce alignment.
+        let block = F::CompressedBlock::read_block(data, block_start);
+        if options.on_invalid_block == InvalidBlockHandling::Error && !F::is_valid_block(&block) {
+            return Err(SurfaceError::InvalidCompressedBlock { x, y });
         }
+
+        // TODO: Add rgba8 and rgbaf32 variants for decompress block.
+        let decompressed_block = F::decompress_block(&block);
+
+        // Each block is 4x4, so we need to update multiple rows.
+        put_rgba_block(
+            rgba_row,
+            decompressed_block,
+            x as usize,
+            0,
+            width as usize,
+            rows,
+        );
+
+        block_start += F::CompressedBlock::SIZE_IN_BYTES;
     }
 
-    Ok(rgba)
+    Ok(())
 }
 
 fn put_rgba_block<T: Pod>(
@@ -415,6 +520,68 @@ mod tests {
 
     // TODO: Add decoding tests?
 
+    #[test]
+    fn decode_bc6h_signed_block() {
+        // Mode 11 with both endpoints set to the most negative 10-bit value.
+        let block = [3, 64, 0, 1, 4, 16, 64, 0, 1, 0, 0, 0, 0, 0, 0, 0];
+
+        let signed: [[[f32; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT] = Bc6S::decompress_block(&block);
+        for [r, g, b, a] in signed.into_iter().flatten() {
+            assert!(r < 0.0 && g < 0.0 && b < 0.0);
+            assert_eq!(1.0, a);
+        }
+
+        let unsigned: [[[f32; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT] = Bc6::decompress_block(&block);
+        for [r, g, b, _] in unsigned.into_iter().flatten() {
+            assert!(r >= 0.0 && g >= 0.0 && b >= 0.0);
+        }
+
+        // Negative values clamp to zero for unorm output.
+        let rgba8: [[[u8; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT] = Bc6S::decompress_block(&block);
+        assert_eq!([[[0, 0, 0, 255]; BLOCK_WIDTH]; BLOCK_HEIGHT], rgba8);
+    }
+
+    #[test]
+    fn decode_bc1_multiple_block_rows() {
+        // A white block above a black block with a partial last row.
+        let data = [[0xFF, 0xFF, 0xFF, 0xFF, 0, 0, 0, 0], [0u8; 8]].concat();
+        let rgba = decode_bcn::<Bc1, u8>(4, 6, &data, DecodeOptions::default()).unwrap();
+
+        let expected: Vec<_> = [[255u8; 4]; 16]
+            .into_iter()
+            .chain([[0, 0, 0, 255]; 8])
+            .flatten()
+            .collect();
+        assert_eq!(expected, rgba);
+    }
+
+    #[test]
+    fn decode_bc7_partial_block() {
+        // The data ends in the middle of the second block.
+        let data = [0u8; 31];
+        assert_eq!(
+            Err(SurfaceError::NotEnoughData {
+                expected: 32,
+                actual: 31
+            }),
+            decode_bcn::<Bc7, u8>(8, 4, &data, DecodeOptions::default())
+        );
+    }
+
+    #[test]
+    fn decode_bc7_first_invalid_block_row() {
+        // The second and third rows of blocks are invalid.
+        let data = [[0xFFu8; 16], [0u8; 16], [0u8; 16]].concat();
+        let options = DecodeOptions {
+            on_invalid_block: InvalidBlockHandling::Error,
+            ..Default::default()
+        };
+        assert_eq!(
+            Err(SurfaceError::InvalidCompressedBlock { x: 0, y: 4 }),
+            decode_bcn::<Bc7, u8>(4, 12, &data, options)
+        );
+    }
+
     #[test]
     fn put_rgba_block_4x4() {
         // Write an entire block.
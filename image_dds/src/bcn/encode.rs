@@ -1,8 +1,11 @@
+#[cfg(feature = "rayon")]
+use crate::round_up;
 use crate::{mip_size, Quality, SurfaceError};
 use half::f16;
 
 use super::{
-    Bc1, Bc2, Bc3, Bc4, Bc5, Bc6, Bc7, BLOCK_HEIGHT, BLOCK_WIDTH, CHANNELS, ELEMENTS_PER_BLOCK,
+    Bc1, Bc2, Bc3, Bc4, Bc5, Bc6, Bc7, Bc7Opaque, BLOCK_HEIGHT, BLOCK_WIDTH, CHANNELS,
+    ELEMENTS_PER_BLOCK,
 };
 
 // Quality modes are optimized for a balance of speed and quality.
@@ -21,16 +24,17 @@ impl From<Quality> for intel_tex_2::bc7::EncodeSettings {
     fn from(value: Quality) -> Self {
         // bc7 has almost imperceptible errors even at ultra_fast
         // 4k rgba ultra fast (2s), very fast (7s), fast (12s)
+        // The basic settings noticeably improve smooth gradients over fast.
         match value {
             Quality::Fast => intel_tex_2::bc7::alpha_ultra_fast_settings(),
             Quality::Normal => intel_tex_2::bc7::alpha_very_fast_settings(),
-            Quality::Slow => intel_tex_2::bc7::alpha_fast_settings(),
+            Quality::Slow => intel_tex_2::bc7::alpha_basic_settings(),
         }
     }
 }
 
 pub trait BcnEncode<T> {
-    // TODO: How to handle depth with intel-tex-rs-2?
+    // 3D data uses block aligned depth slices stacked vertically.
     fn compress_surface(
         width: u32,
         height: u32,
@@ -54,6 +58,8 @@ impl BcnEncode<u8> for Bc1 {
             data: rgba8_data,
         };
 
+        // Blocks with c0 <= c1 decode index 3 as transparent black.
+        // Solid opaque colors often quantize to c0 == c1 and must not use index 3.
         Ok(intel_tex_2::bc1::compress_blocks(&surface))
     }
 }
@@ -80,7 +86,6 @@ impl BcnEncode<u8> for Bc2 {
 
         let mut data = Vec::new();
 
-        // TODO: Tests for this.
         // TODO: Test surfaces not divisible by block dimensions.
         let mut block_index = 0;
         for y in (0..height).step_by(BLOCK_HEIGHT) {
@@ -130,8 +135,9 @@ fn sharp_alpha_block(x: u32, y: u32, width: u32, height: u32, rgba8_data: &[u8])
 
             let output_index = j * BLOCK_HEIGHT + i;
 
-            // 4-bit alpha for each pixel.
-            alpha |= ((rgba8_data[input_index] / 17) as u64) << (output_index * 4);
+            // 4-bit alpha for each pixel rounded to the nearest multiple of 17.
+            let alpha4 = (rgba8_data[input_index] as u64 + 8) / 17;
+            alpha |= alpha4 << (output_index * 4);
         }
     }
     alpha
@@ -208,7 +214,11 @@ impl BcnEncode<f32> for Bc6 {
     ) -> Result<Vec<u8>, SurfaceError> {
         // The BC6H encoder expects the data to be in half precision floating point.
         // This differs from the other formats that expect [u8; 4] for each pixel.
-        let f16_data: Vec<f16> = rgba8_data.iter().copied().map(f16::from_f32).collect();
+        // The encoder only creates unsigned blocks, so clamp negative values to zero.
+        let f16_data: Vec<f16> = rgba8_data
+            .iter()
+            .map(|v| f16::from_f32(v.max(0.0)))
+            .collect();
 
         let surface = intel_tex_2::RgbaSurface {
             width,
@@ -271,6 +281,32 @@ impl BcnEncode<u8> for Bc7 {
     }
 }
 
+impl BcnEncode<u8> for Bc7Opaque {
+    fn compress_surface(
+        width: u32,
+        height: u32,
+        rgba8_data: &[u8],
+        quality: Quality,
+    ) -> Result<Vec<u8>, SurfaceError> {
+        // RGBA with 4 bytes per pixel.
+        let surface = intel_tex_2::RgbaSurface {
+            width,
+            height,
+            stride: width * CHANNELS as u32,
+            data: rgba8_data,
+        };
+
+        // The alpha settings don't always preserve fully opaque alpha.
+        // The ultra fast opaque settings also produce an alpha of 254 for some blocks.
+        let settings = match quality {
+            Quality::Fast => intel_tex_2::bc7::opaque_very_fast_settings(),
+            Quality::Normal => intel_tex_2::bc7::opaque_very_fast_settings(),
+            Quality::Slow => intel_tex_2::bc7::opaque_basic_settings(),
+        };
+        Ok(intel_tex_2::bc7::compress_blocks(&settings, &surface))
+    }
+}
+
 pub fn encode_bcn<F, T>(
     width: u32,
     height: u32,
@@ -279,6 +315,7 @@ pub fn encode_bcn<F, T>(
 ) -> Result<Vec<u8>, SurfaceError>
 where
     F: BcnEncode<T>,
+    T: Sync,
 {
     // Surface dimensions are not validated yet and may cause overflow.
     let expected_size = mip_size(
@@ -304,6 +341,23 @@ where
         });
     }
 
+    #[cfg(feature = "rayon")]
+    {
+        // Rows of blocks are encoded independently, so splitting the surface produces the same data.
+        // Surfaces are already padded to the block dimensions when encoding.
+        if round_up(width as usize, BLOCK_WIDTH) == width as usize
+            && round_up(height as usize, BLOCK_HEIGHT) == height as usize
+        {
+            use rayon::prelude::*;
+            let row_size = width as usize * BLOCK_HEIGHT * CHANNELS;
+            let rows = data[..expected_size]
+                .par_chunks(row_size)
+                .map(|row| F::compress_surface(width, BLOCK_HEIGHT as u32, row, quality))
+                .collect::<Result<Vec<_>, _>>()?;
+            return Ok(rows.concat());
+        }
+    }
+
     F::compress_surface(width, height, data, quality)
 }
 
@@ -313,6 +367,8 @@ where
 mod tests {
     use super::*;
 
+    use crate::{bcn::decode_bcn, DecodeOptions};
+
     // TODO: Create tests for data length since we can't know what the compressed blocks should be?
     // TODO: Test edge cases and type conversions?
     // TODO: Add tests for validating the input length.
@@ -337,6 +393,59 @@ mod tests {
         check_compress_bcn::<Bc2>(&rgba, Quality::Slow);
     }
 
+    #[test]
+    fn bc2_alpha_roundtrip() {
+        // Alpha uses 4 bits, so multiples of 17 are preserved exactly.
+        // Other values round to the nearest multiple of 17.
+        let alpha: Vec<_> = (0..12).map(|i| i * 17).chain([8, 9, 25, 26]).collect();
+        let rgba: Vec<_> = alpha.iter().flat_map(|a| [64, 128, 255, *a]).collect();
+
+        let bc2 = encode_bcn::<Bc2, u8>(4, 4, &rgba, Quality::Fast).unwrap();
+        let decoded = decode_bcn::<Bc2, u8>(4, 4, &bc2, DecodeOptions::default()).unwrap();
+
+        let expected: Vec<_> = (0..12).map(|i| i * 17).chain([0, 17, 17, 34]).collect();
+        assert_eq!(
+            expected,
+            decoded.chunks_exact(4).map(|p| p[3]).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn bc7_compress_multiple_block_rows() {
+        // Splitting rows of blocks should not change the encoded data.
+        let rgba: Vec<_> = (0..8 * 12 * 4).map(|i| (i * 7) as u8).collect();
+        assert_eq!(
+            Bc7::compress_surface(8, 12, &rgba, Quality::Fast).unwrap(),
+            encode_bcn::<Bc7, u8>(8, 12, &rgba, Quality::Fast).unwrap()
+        );
+    }
+
+    fn bc7_gradient_error<T: BcnEncode<u8>>(quality: Quality) -> u64 {
+        let rgba: Vec<_> = (0..32 * 32)
+            .flat_map(|i| {
+                let (x, y) = (i % 32, i / 32);
+                [x * 8, y * 8, (x + y) * 4, 255 - x * 4].map(|v| v as u8)
+            })
+            .collect();
+        let bc7 = encode_bcn::<T, u8>(32, 32, &rgba, quality).unwrap();
+        let decoded = decode_bcn::<Bc7, u8>(32, 32, &bc7, DecodeOptions::default()).unwrap();
+        rgba.iter()
+            .zip(&decoded)
+            .map(|(a, b)| (*a as i64 - *b as i64).pow(2) as u64)
+            .sum()
+    }
+
+    #[test]
+    fn bc7_slow_improves_gradients() {
+        assert!(
+            bc7_gradient_error::<Bc7>(Quality::Slow) < bc7_gradient_error::<Bc7>(Quality::Normal)
+        );
+        assert!(
+            bc7_gradient_error::<Bc7Opaque>(Quality::Slow)
+                < bc7_gradient_error::<Bc7Opaque>(Quality::Normal)
+        );
+    }
+
     #[test]
     fn bc3_compress() {
         let rgba = vec![64u8; ELEMENTS_PER_BLOCK];
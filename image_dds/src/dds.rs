@@ -1,6 +1,9 @@
 use std::ops::Range;
 
-use ddsfile::{Caps2, D3DFormat, Dds, DxgiFormat, FourCC};
+use ddsfile::{
+    AlphaMode, Caps, Caps2, D3DFormat, DataFormat, Dds, DxgiFormat, FourCC, HeaderFlags,
+    PixelFormat, PixelFormatFlags,
+};
 use thiserror::Error;
 
 use crate::{
@@ -16,6 +19,34 @@ pub enum CreateDdsError {
 
     #[error("error compressing surface: {0}")]
     CompressSurface(#[from] SurfaceError),
+
+    #[error("surface {field} changed after converting to and from DDS")]
+    RoundtripMismatch { field: &'static str },
+
+    #[error("image format {0:?} has no equivalent DXGI or D3D format")]
+    UnsupportedFormat(ImageFormat),
+
+    #[error("image format {image_format:?} with {layers} layers requires a DX10 header")]
+    RequiresDx10Header {
+        image_format: ImageFormat,
+        layers: u32,
+    },
+
+    #[error("DXGI format {format:?} does not have the same block size as {image_format:?}")]
+    IncompatibleDxgiFormat {
+        format: DxgiFormat,
+        image_format: ImageFormat,
+    },
+
+    #[error(
+        "surface with height {height}, depth {depth}, {layers} layers, and image format {image_format:?} is not a valid 1D texture"
+    )]
+    Invalid1dSurface {
+        height: u32,
+        depth: u32,
+        layers: u32,
+        image_format: ImageFormat,
+    },
 }
 
 #[cfg(feature = "encode")]
@@ -40,6 +71,7 @@ pub fn dds_from_image(
 /// Encode `image` to a 2D DDS file with the given `format`.
 ///
 /// The number of mipmaps generated depends on the `mipmaps` parameter.
+/// Color channels are assumed to be linear and converted to sRGB for sRGB formats.
 pub fn dds_from_imagef32(
     image: &image::Rgba32FImage,
     format: ImageFormat,
@@ -56,79 +88,358 @@ pub fn dds_from_imagef32(
 /// Decode the given mip level from `dds` to an RGBA8 image.
 /// Array layers are arranged vertically from top to bottom.
 pub fn image_from_dds(dds: &Dds, mipmap: u32) -> Result<image::RgbaImage, CreateImageError> {
-    let layers = array_layer_count(dds);
+    let layers = Surface::from_dds(dds)?.layers;
     SurfaceRgba8::decode_layers_mipmaps_dds(dds, 0..layers, mipmap..mipmap + 1)?.into_image()
 }
 
 #[cfg(feature = "image")]
 /// Decode the given mip level from `dds` to an RGBAF32 image.
 /// Array layers are arranged vertically from top to bottom.
+/// Color channels for sRGB formats are converted to linear.
 pub fn imagef32_from_dds(dds: &Dds, mipmap: u32) -> Result<image::Rgba32FImage, CreateImageError> {
-    let layers = array_layer_count(dds);
+    let layers = Surface::from_dds(dds)?.layers;
     SurfaceRgba32Float::decode_layers_mipmaps_dds(dds, 0..layers, mipmap..mipmap + 1)?.into_image()
 }
 
+#[cfg(feature = "image")]
+/// Decode the alpha channel for the given mip level from `dds` to a grayscale image.
+/// Array layers are arranged vertically from top to bottom.
+///
+/// Formats without an alpha channel decode to an image with all values set to `255`.
+pub fn alpha_image_from_dds(dds: &Dds, mipmap: u32) -> Result<image::GrayImage, CreateImageError> {
+    let image = image_from_dds(dds, mipmap)?;
+    Ok(image::GrayImage::from_fn(
+        image.width(),
+        image.height(),
+        |x, y| image::Luma([image.get_pixel(x, y)[3]]),
+    ))
+}
+
+#[cfg(feature = "image")]
+/// Decode the given mip level from `dds` to an image with enough precision for the format.
+/// Array layers are arranged vertically from top to bottom.
+///
+/// Floating point formats like [ImageFormat::BC6hRgbUfloat] decode to [image::DynamicImage::ImageRgba32F].
+/// All other formats decode to [image::DynamicImage::ImageRgba8].
+pub fn dynamic_image_from_dds(
+    dds: &Dds,
+    mipmap: u32,
+) -> Result<image::DynamicImage, CreateImageError> {
+    let image_format = dds_image_format(dds)
+        .map_err(|e| CreateImageError::DecompressSurface(SurfaceError::UnsupportedDdsFormat(e)))?;
+    if image_format.is_float() {
+        imagef32_from_dds(dds, mipmap).map(image::DynamicImage::ImageRgba32F)
+    } else {
+        image_from_dds(dds, mipmap).map(image::DynamicImage::ImageRgba8)
+    }
+}
+
+#[cfg(feature = "image")]
+/// Decode a single `layer`, `depth_level`, and `mipmap` from `dds` to an RGBA8 image.
+///
+/// The image has the dimensions of the mipmap rather than the base level.
+/// This is useful for extracting a single cube map face or array layer.
+pub fn image_from_dds_subresource(
+    dds: &Dds,
+    layer: u32,
+    depth_level: u32,
+    mipmap: u32,
+) -> Result<image::RgbaImage, CreateImageError> {
+    SurfaceRgba8::decode_layers_mipmaps_dds(dds, layer..layer + 1, mipmap..mipmap + 1)?
+        .get_image(0, depth_level, 0)
+        .ok_or(CreateImageError::DecompressSurface(
+            SurfaceError::MipmapDataOutOfBounds { layer, mipmap },
+        ))
+}
+
 impl<T: AsRef<[u8]>> Surface<T> {
     /// Create a DDS file with the same image data and format.
+    ///
+    /// This is equivalent to [Surface::to_dds_with_alpha_mode] with [AlphaMode::Straight].
     pub fn to_dds(&self) -> Result<crate::ddsfile::Dds, CreateDdsError> {
+        self.to_dds_with_alpha_mode(AlphaMode::Straight)
+    }
+
+    /// Create a DDS file with the same image data and format and the given `alpha_mode`.
+    ///
+    /// The alpha mode is only stored in the DX10 header,
+    /// so formats written with a legacy D3D header like [ImageFormat::Bgr8Unorm] ignore it.
+    pub fn to_dds_with_alpha_mode(
+        &self,
+        alpha_mode: AlphaMode,
+    ) -> Result<crate::ddsfile::Dds, CreateDdsError> {
         let mut dds = dxgi_from_image_format(self.image_format)
-            .map(|format| {
-                Dds::new_dxgi(ddsfile::NewDxgiParams {
-                    height: self.height,
-                    width: self.width,
-                    depth: if self.depth > 1 {
-                        Some(self.depth)
-                    } else {
-                        None
-                    },
-                    format,
-                    mipmap_levels: (self.mipmaps > 1).then_some(self.mipmaps),
-                    array_layers: (self.layers > 1 && self.layers != 6).then_some(self.layers),
-                    caps2: (self.layers == 6).then_some(Caps2::CUBEMAP | Caps2::CUBEMAP_ALLFACES),
-                    is_cubemap: self.layers == 6,
-                    resource_dimension: if self.depth > 1 {
-                        ddsfile::D3D10ResourceDimension::Texture3D
-                    } else {
-                        ddsfile::D3D10ResourceDimension::Texture2D
-                    },
-                    alpha_mode: ddsfile::AlphaMode::Straight,
-                })
-            })
+            .map(|format| self.new_dxgi_dds(format, alpha_mode, self.resource_dimension()))
             .or_else(|| {
                 // Not all surface formats are supported by DXGI.
-                d3d_from_image_format(self.image_format).map(|format| {
-                    Dds::new_d3d(ddsfile::NewD3dParams {
-                        height: self.height,
-                        width: self.width,
-                        depth: if self.depth > 1 {
-                            Some(self.depth)
-                        } else {
-                            None
-                        },
-                        format,
-                        mipmap_levels: (self.mipmaps > 1).then_some(self.mipmaps),
-                        caps2: (self.layers == 6)
-                            .then_some(Caps2::CUBEMAP | Caps2::CUBEMAP_ALLFACES),
-                    })
-                })
+                d3d_from_image_format(self.image_format).map(|format| self.new_d3d_dds(format))
             })
-            .unwrap()?;
+            .ok_or(CreateDdsError::UnsupportedFormat(self.image_format))??;
+
+        if self.image_format == ImageFormat::BC3NormalXgxr {
+            // There is no D3D or DXGI format for swizzled BC3 normal maps.
+            dds.header.spf.fourcc = Some(FourCC(RXGB));
+        }
 
         dds.data = self.data.as_ref().to_vec();
 
         Ok(dds)
     }
+
+    /// Create the bytes for a DDS file with only the required header fields and no DX10 header.
+    ///
+    /// This is intended for very old tools that fail to read optional header fields
+    /// like the pitch or linear size.
+    /// Only formats with a legacy D3D format like DXT1 or A8R8G8B8 are supported.
+    /// Array layers other than cube maps also require a DX10 header.
+    pub fn to_dds_minimal(&self) -> Result<Vec<u8>, CreateDdsError> {
+        let format = d3d_from_image_format(self.image_format)
+            .filter(|_| self.layers == 1 || self.layers == 6)
+            .ok_or(CreateDdsError::RequiresDx10Header {
+                image_format: self.image_format,
+                layers: self.layers,
+            })?;
+
+        // Reuse the pixel format and caps from the full legacy header.
+        let mut dds = self.new_d3d_dds(format)?;
+        if self.image_format == ImageFormat::BC3NormalXgxr {
+            dds.header.spf.fourcc = Some(FourCC(RXGB));
+        }
+
+        let mut flags =
+            HeaderFlags::CAPS | HeaderFlags::HEIGHT | HeaderFlags::WIDTH | HeaderFlags::PIXELFORMAT;
+        if self.mipmaps > 1 {
+            flags |= HeaderFlags::MIPMAPCOUNT;
+        }
+        if self.depth > 1 {
+            flags |= HeaderFlags::DEPTH;
+        }
+
+        let mut bytes = b"DDS ".to_vec();
+        // The header size, flags, height, width, pitch, depth, and mipmap count.
+        for value in [
+            124,
+            flags.bits(),
+            self.height,
+            self.width,
+            0,
+            if self.depth > 1 { self.depth } else { 0 },
+            if self.mipmaps > 1 { self.mipmaps } else { 0 },
+        ] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        bytes.extend_from_slice(&[0u8; 11 * 4]);
+        dds.header.spf.write(&mut bytes)?;
+        // The remaining caps and reserved values are unused.
+        for value in [dds.header.caps.bits(), dds.header.caps2.bits(), 0, 0, 0] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        bytes.extend_from_slice(self.data.as_ref());
+
+        Ok(bytes)
+    }
+
+    /// Create a DDS file with the surface data and the DXGI `format` in the header.
+    ///
+    /// This allows using formats without an [ImageFormat] variant like `R8G8B8A8_Typeless`.
+    /// The `format` must have the same block dimensions and block size as the surface format.
+    pub fn to_dds_with_dxgi(&self, format: DxgiFormat) -> Result<Dds, CreateDdsError> {
+        let (block_size_in_bytes, block_dimensions) = match format.get_block_size() {
+            Some(size) => (Some(size as usize), (4, 4, 1)),
+            None => (
+                format.get_bits_per_pixel().map(|bits| bits as usize / 8),
+                (1, 1, 1),
+            ),
+        };
+        if block_size_in_bytes != Some(self.image_format.block_size_in_bytes())
+            || block_dimensions != self.image_format.block_dimensions()
+        {
+            return Err(CreateDdsError::IncompatibleDxgiFormat {
+                format,
+                image_format: self.image_format,
+            });
+        }
+
+        let mut dds = self.new_dxgi_dds(format, AlphaMode::Straight, self.resource_dimension())?;
+        dds.data = self.data.as_ref().to_vec();
+        Ok(dds)
+    }
+
+    /// Create a DDS file with the same image data and format and a `Texture1D` resource dimension.
+    ///
+    /// [Surface::to_dds] always writes surfaces with a height of 1 as `Texture2D`.
+    /// 1D textures like lookup tables require a height and depth of 1,
+    /// no cube maps, and an uncompressed format with a DXGI format.
+    pub fn to_dds_1d(&self) -> Result<Dds, CreateDdsError> {
+        let format = dxgi_from_image_format(self.image_format)
+            .filter(|_| {
+                self.height == 1
+                    && self.depth == 1
+                    && self.layers != 6
+                    && self.image_format.block_dimensions() == (1, 1, 1)
+            })
+            .ok_or(CreateDdsError::Invalid1dSurface {
+                height: self.height,
+                depth: self.depth,
+                layers: self.layers,
+                image_format: self.image_format,
+            })?;
+
+        let mut dds = self.new_dxgi_dds(
+            format,
+            AlphaMode::Straight,
+            ddsfile::D3D10ResourceDimension::Texture1D,
+        )?;
+        dds.data = self.data.as_ref().to_vec();
+        Ok(dds)
+    }
+
+    fn new_dxgi_dds(
+        &self,
+        format: DxgiFormat,
+        alpha_mode: AlphaMode,
+        resource_dimension: ddsfile::D3D10ResourceDimension,
+    ) -> Result<Dds, ddsfile::Error> {
+        let mut dds = Dds::new_dxgi(ddsfile::NewDxgiParams {
+            height: self.height,
+            width: self.width,
+            depth: if self.depth > 1 {
+                Some(self.depth)
+            } else {
+                None
+            },
+            format,
+            mipmap_levels: (self.mipmaps > 1).then_some(self.mipmaps),
+            array_layers: (self.layers > 1 && self.layers != 6).then_some(self.layers),
+            caps2: (self.layers == 6).then_some(Caps2::CUBEMAP | Caps2::CUBEMAP_ALLFACES),
+            is_cubemap: self.layers == 6,
+            resource_dimension,
+            alpha_mode,
+        })?;
+        self.insert_caps(&mut dds);
+        Ok(dds)
+    }
+
+    fn new_d3d_dds(&self, format: D3DFormat) -> Result<Dds, ddsfile::Error> {
+        // The pixel format flags already include DDPF_ALPHAPIXELS for formats with an alpha mask.
+        let mut dds = Dds::new_d3d(ddsfile::NewD3dParams {
+            height: self.height,
+            width: self.width,
+            depth: if self.depth > 1 {
+                Some(self.depth)
+            } else {
+                None
+            },
+            format,
+            mipmap_levels: (self.mipmaps > 1).then_some(self.mipmaps),
+            caps2: (self.layers == 6).then_some(Caps2::CUBEMAP | Caps2::CUBEMAP_ALLFACES),
+        })?;
+        self.insert_caps(&mut dds);
+
+        // ddsfile marks all uncompressed formats as RGB.
+        // Luminance and alpha only formats need different flags to be read correctly.
+        let flags = &mut dds.header.spf.flags;
+        match format {
+            D3DFormat::L8 | D3DFormat::A8L8 => {
+                flags.remove(PixelFormatFlags::RGB);
+                flags.insert(PixelFormatFlags::LUMINANCE);
+            }
+            D3DFormat::A8 => {
+                flags.remove(PixelFormatFlags::RGB | PixelFormatFlags::ALPHA_PIXELS);
+                flags.insert(PixelFormatFlags::ALPHA);
+            }
+            _ => (),
+        }
+        Ok(dds)
+    }
+
+    fn insert_caps(&self, dds: &mut Dds) {
+        // ddsfile only sets DDSCAPS_COMPLEX for mipmaps, volumes, and arrays.
+        // Some readers also require it for cube maps.
+        if self.mipmaps > 1 || self.layers > 1 || self.depth > 1 {
+            dds.header.caps.insert(Caps::COMPLEX);
+        }
+        // ddsfile leaves caps2 to the caller for volumes.
+        if self.depth > 1 {
+            dds.header.caps2.insert(Caps2::VOLUME);
+        }
+    }
+
+    fn resource_dimension(&self) -> ddsfile::D3D10ResourceDimension {
+        // 1D textures are only written with Surface::to_dds_1d.
+        if self.depth > 1 {
+            ddsfile::D3D10ResourceDimension::Texture3D
+        } else {
+            ddsfile::D3D10ResourceDimension::Texture2D
+        }
+    }
+}
+
+impl ImageFormat {
+    /// The name of the DXGI format used for DDS files like `"BC1_UNorm"`
+    /// or [None] if there is no equivalent DXGI format.
+    pub fn dxgi_name(&self) -> Option<String> {
+        dxgi_from_image_format(*self).map(|f| format!("{f:?}"))
+    }
+
+    /// The name of the legacy D3D format used for DDS files like `"DXT1"`
+    /// or [None] if there is no equivalent D3D format.
+    pub fn d3d_name(&self) -> Option<String> {
+        d3d_from_image_format(*self).map(|f| format!("{f:?}"))
+    }
 }
 
 impl<'a> Surface<&'a [u8]> {
     /// Create a view over the data in `dds` without any copies.
+    ///
+    /// The pitch or linear size in the header is ignored since many tools write incorrect values.
+    /// Sizes are always calculated from the format and dimensions instead.
     pub fn from_dds(dds: &'a crate::ddsfile::Dds) -> Result<Self, SurfaceError> {
+        Self::from_dds_with_srgb(dds, false)
+    }
+
+    /// Create a view over the data in `dds` without any copies
+    /// and use sRGB formats for legacy BC1, BC2, and BC3 files if `srgb` is `true`.
+    ///
+    /// DDS files without a DX10 header use D3D or FourCC formats like DXT1 or DXT5
+    /// that have no way to indicate sRGB or linear data.
+    /// These formats are treated as linear by [Surface::from_dds].
+    /// Color textures typically use sRGB, so the caller must decide which interpretation to use.
+    pub fn from_dds_with_srgb(
+        dds: &'a crate::ddsfile::Dds,
+        srgb: bool,
+    ) -> Result<Self, SurfaceError> {
         let width = dds.get_width();
-        let height = dds.get_height();
-        let depth = dds.get_depth();
-        let layers = array_layer_count(dds);
+        let (height, depth) = if is_texture_1d(dds) {
+            // 1D textures should ignore any height or depth values in the header.
+            (1, 1)
+        } else {
+            (dds.get_height(), dds.get_depth())
+        };
+        let layers = array_layer_count(dds)?;
+        let (depth, layers) = match dds_depth_interpretation(dds) {
+            DdsDepthInterpretation::Volume => (depth, layers),
+            DdsDepthInterpretation::ArrayLayers => (
+                1,
+                layers
+                    .checked_mul(depth)
+                    .ok_or(SurfaceError::DdsLayerCountWouldOverflow {
+                        layers,
+                        factor: depth,
+                    })?,
+            ),
+        };
         let mipmaps = dds.get_num_mipmap_levels();
+        // The format is stored in the DX10 header, so report a more helpful error.
+        if dds.header.spf.fourcc == Some(FourCC(FourCC::DX10)) && dds.header10.is_none() {
+            return Err(SurfaceError::MissingDx10Header);
+        }
         let image_format = dds_image_format(dds).map_err(SurfaceError::UnsupportedDdsFormat)?;
+        let image_format = if srgb && dds.header10.is_none() {
+            legacy_srgb_format(image_format)
+        } else {
+            image_format
+        };
 
         Ok(Surface {
             width,
@@ -189,24 +500,144 @@ impl SurfaceRgba32Float<Vec<f32>> {
     }
 }
 
-fn array_layer_count(dds: &Dds) -> u32 {
+fn array_layer_count(dds: &Dds) -> Result<u32, SurfaceError> {
     // Array layers for DDS are calculated differently for cube maps.
+    let layers = dds.get_num_array_layers().max(1);
     if matches!(&dds.header10, Some(header10) if header10.misc_flag == ddsfile::MiscFlag::TEXTURECUBE)
     {
-        dds.get_num_array_layers().max(1) * 6
+        layers
+            .checked_mul(6)
+            .ok_or(SurfaceError::DdsLayerCountWouldOverflow { layers, factor: 6 })
+    } else {
+        Ok(layers)
+    }
+}
+
+fn is_texture_1d(dds: &Dds) -> bool {
+    matches!(&dds.header10, Some(header10) if header10.resource_dimension == ddsfile::D3D10ResourceDimension::Texture1D)
+}
+
+/// How the depth of a DDS file is used by [Surface::from_dds].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DdsDepthInterpretation {
+    /// Depth slices of a 3D volume texture.
+    Volume,
+    /// Additional array layers for 2D textures with an incorrect depth in the header.
+    ArrayLayers,
+}
+
+/// Returns how the depth of `dds` should be interpreted.
+///
+/// Some tools write array layers using the depth field.
+/// A depth greater than 1 is treated as array layers if the DX10 header
+/// uses a 2D resource dimension instead of 3D.
+/// Legacy files without a DX10 header use the `DDSCAPS2_VOLUME` caps flag instead
+/// and treat the depth as array layers if the flag is missing.
+pub fn dds_depth_interpretation(dds: &Dds) -> DdsDepthInterpretation {
+    if dds.get_depth() <= 1 {
+        return DdsDepthInterpretation::Volume;
+    }
+
+    let is_volume = match &dds.header10 {
+        Some(header10) => header10.resource_dimension != ddsfile::D3D10ResourceDimension::Texture2D,
+        None => dds.header.caps2.contains(Caps2::VOLUME),
+    };
+    if is_volume {
+        DdsDepthInterpretation::Volume
     } else {
-        dds.get_num_array_layers().max(1)
+        DdsDepthInterpretation::ArrayLayers
+    }
+}
+
+/// Returns how the alpha channel of `dds` should be interpreted.
+///
+/// DX10 files store the alpha mode in the `misc_flags2` field of the DX10 header.
+/// Legacy files only indicate premultiplied alpha with the DXT2 and DXT4 formats.
+/// Use this to determine if decoded data needs to be unpremultiplied.
+pub fn dds_alpha_mode(dds: &Dds) -> AlphaMode {
+    match &dds.header10 {
+        Some(header10) => header10.alpha_mode,
+        None => match dds.get_d3d_format() {
+            Some(D3DFormat::DXT2 | D3DFormat::DXT4) => AlphaMode::PreMultiplied,
+            _ => AlphaMode::Unknown,
+        },
+    }
+}
+
+/// Check that `dds` can be converted to a [Surface] and back to DDS without losing data.
+///
+/// This compares the dimensions, array layers, mipmaps, format, and data
+/// of the surfaces before and after writing the DDS header.
+/// Header fields not used by [Surface] like the pitch or alpha mode are not compared.
+/// Returns an error if the DDS data is smaller than expected for the header.
+pub fn verify_dds_roundtrip(dds: &Dds) -> Result<(), CreateDdsError> {
+    let surface = Surface::from_dds(dds)?;
+    surface.validate()?;
+
+    // Data past the expected size is ignored by Surface but any missing data would be lost.
+    let expected = surface
+        .expected_data_size()
+        .ok_or(SurfaceError::PixelCountWouldOverflow {
+            width: surface.width,
+            height: surface.height,
+            depth: surface.depth,
+        })?;
+    if surface.data.len() < expected {
+        return Err(SurfaceError::NotEnoughData {
+            expected,
+            actual: surface.data.len(),
+        }
+        .into());
+    }
+
+    let new_dds = surface.to_dds()?;
+    let new_surface = Surface::from_dds(&new_dds)?;
+
+    let fields = [
+        ("width", surface.width == new_surface.width),
+        ("height", surface.height == new_surface.height),
+        ("depth", surface.depth == new_surface.depth),
+        ("layers", surface.layers == new_surface.layers),
+        ("mipmaps", surface.mipmaps == new_surface.mipmaps),
+        (
+            "image_format",
+            surface.image_format == new_surface.image_format,
+        ),
+        ("data", surface.data == new_surface.data),
+    ];
+    match fields.iter().find(|(_, equal)| !equal) {
+        Some((field, _)) => Err(CreateDdsError::RoundtripMismatch { field }),
+        None => Ok(()),
     }
 }
 
 /// Format information for all DDS variants.
-#[derive(Debug, PartialEq)]
+///
+/// This is returned for unsupported formats to show which of the DXGI, D3D,
+/// or FourCC format codes were present in the file.
+#[derive(Debug, PartialEq, Clone)]
 pub struct DdsFormatInfo {
     pub dxgi: Option<DxgiFormat>,
     pub d3d: Option<D3DFormat>,
     pub fourcc: Option<FourCC>,
 }
 
+impl std::fmt::Display for DdsFormatInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "DXGI: {:?}, D3D: {:?}, FourCC: ", self.dxgi, self.d3d)?;
+        match &self.fourcc {
+            // FourCC codes are usually readable ASCII like "DXT1".
+            Some(fourcc) => write!(
+                f,
+                "{:?} ({:#010x})",
+                String::from_utf8_lossy(&fourcc.0.to_le_bytes()),
+                fourcc.0
+            ),
+            None => write!(f, "None"),
+        }
+    }
+}
+
 /// Returns the format of `dds` or `None` if the format is unrecognized.
 pub fn dds_image_format(dds: &Dds) -> Result<ImageFormat, DdsFormatInfo> {
     // The format can be DXGI, D3D, or specified in the FOURCC.
@@ -215,6 +646,7 @@ pub fn dds_image_format(dds: &Dds) -> Result<ImageFormat, DdsFormatInfo> {
     let fourcc = dds.header.spf.fourcc.clone();
 
     d3d.and_then(image_format_from_d3d)
+        .or_else(|| image_format_from_pixel_format(&dds.header.spf))
         .or_else(|| dxgi.and_then(image_format_from_dxgi))
         .or_else(|| fourcc.clone().and_then(image_format_from_fourcc))
         .ok_or(DdsFormatInfo { dxgi, d3d, fourcc })
@@ -229,6 +661,8 @@ fn image_format_from_dxgi(format: DxgiFormat) -> Option<ImageFormat> {
         DxgiFormat::R8G8B8A8_UNorm => Some(ImageFormat::Rgba8Unorm),
         DxgiFormat::R8G8B8A8_UNorm_sRGB => Some(ImageFormat::Rgba8UnormSrgb),
         DxgiFormat::R16G16B16A16_Float => Some(ImageFormat::Rgba16Float),
+        DxgiFormat::R9G9B9E5_SharedExp => Some(ImageFormat::Rgb9e5Ufloat),
+        DxgiFormat::R11G11B10_Float => Some(ImageFormat::Rg11b10Float),
         DxgiFormat::R32G32B32A32_Float => Some(ImageFormat::Rgba32Float),
         DxgiFormat::B8G8R8A8_UNorm => Some(ImageFormat::Bgra8Unorm),
         DxgiFormat::B8G8R8A8_UNorm_sRGB => Some(ImageFormat::Bgra8UnormSrgb),
@@ -247,6 +681,9 @@ fn image_format_from_dxgi(format: DxgiFormat) -> Option<ImageFormat> {
         DxgiFormat::BC7_UNorm => Some(ImageFormat::BC7RgbaUnorm),
         DxgiFormat::BC7_UNorm_sRGB => Some(ImageFormat::BC7RgbaUnormSrgb),
         DxgiFormat::B4G4R4A4_UNorm => Some(ImageFormat::Bgra4Unorm),
+        DxgiFormat::B8G8R8X8_UNorm => Some(ImageFormat::Bgrx8Unorm),
+        DxgiFormat::B5G6R5_UNorm => Some(ImageFormat::B5g6r5Unorm),
+        DxgiFormat::A8_UNorm => Some(ImageFormat::A8Unorm),
         _ => None,
     }
 }
@@ -263,14 +700,34 @@ fn image_format_from_d3d(format: D3DFormat) -> Option<ImageFormat> {
         D3DFormat::A8R8G8B8 => Some(ImageFormat::Bgra8Unorm),
         D3DFormat::R8G8B8 => Some(ImageFormat::Bgr8Unorm),
         D3DFormat::A8B8G8R8 => Some(ImageFormat::Rgba8Unorm),
+        D3DFormat::X8R8G8B8 => Some(ImageFormat::Bgrx8Unorm),
+        D3DFormat::X8B8G8R8 => Some(ImageFormat::Rgbx8Unorm),
+        D3DFormat::R5G6B5 => Some(ImageFormat::B5g6r5Unorm),
+        D3DFormat::L8 => Some(ImageFormat::L8Unorm),
+        D3DFormat::A8 => Some(ImageFormat::A8Unorm),
+        D3DFormat::A8L8 => Some(ImageFormat::La8Unorm),
         D3DFormat::A16B16G16R16F => Some(ImageFormat::Rgba16Float),
         D3DFormat::A32B32G32R32F => Some(ImageFormat::Rgba32Float),
         _ => None,
     }
 }
 
+fn image_format_from_pixel_format(spf: &PixelFormat) -> Option<ImageFormat> {
+    // ddsfile only reads the bit count and red mask for some flags,
+    // so L8, A8L8, and A8 are never detected as D3D formats.
+    let luminance = spf.flags.contains(PixelFormatFlags::LUMINANCE);
+    let rgb = spf.flags.contains(PixelFormatFlags::RGB);
+    match (luminance, rgb, spf.rgb_bit_count, spf.a_bit_mask) {
+        (true, false, Some(8), None) => Some(ImageFormat::L8Unorm),
+        (true, false, Some(16), Some(0xff00)) => Some(ImageFormat::La8Unorm),
+        (false, false, None, Some(0xff)) => Some(ImageFormat::A8Unorm),
+        _ => None,
+    }
+}
+
 const BC5U: u32 = u32::from_le_bytes(*b"BC5U");
 const ATI2: u32 = u32::from_le_bytes(*b"ATI2");
+const RXGB: u32 = u32::from_le_bytes(*b"RXGB");
 
 fn image_format_from_fourcc(fourcc: FourCC) -> Option<ImageFormat> {
     match fourcc.0 {
@@ -283,10 +740,20 @@ fn image_format_from_fourcc(fourcc: FourCC) -> Option<ImageFormat> {
         FourCC::BC4_SNORM => Some(ImageFormat::BC4RSnorm),
         ATI2 | BC5U => Some(ImageFormat::BC5RgUnorm),
         FourCC::BC5_SNORM => Some(ImageFormat::BC5RgSnorm),
+        RXGB => Some(ImageFormat::BC3NormalXgxr),
         _ => None,
     }
 }
 
+fn legacy_srgb_format(format: ImageFormat) -> ImageFormat {
+    match format {
+        ImageFormat::BC1RgbaUnorm => ImageFormat::BC1RgbaUnormSrgb,
+        ImageFormat::BC2RgbaUnorm => ImageFormat::BC2RgbaUnormSrgb,
+        ImageFormat::BC3RgbaUnorm => ImageFormat::BC3RgbaUnormSrgb,
+        _ => format,
+    }
+}
+
 fn d3d_from_image_format(value: ImageFormat) -> Option<D3DFormat> {
     match value {
         ImageFormat::BC1RgbaUnorm => Some(D3DFormat::DXT1),
@@ -295,10 +762,11 @@ fn d3d_from_image_format(value: ImageFormat) -> Option<D3DFormat> {
         ImageFormat::BC2RgbaUnormSrgb => Some(D3DFormat::DXT2),
         ImageFormat::BC3RgbaUnorm => Some(D3DFormat::DXT5),
         ImageFormat::BC3RgbaUnormSrgb => Some(D3DFormat::DXT5),
-        ImageFormat::BC4RUnorm => None,  // fourcc
-        ImageFormat::BC4RSnorm => None,  // fourcc
-        ImageFormat::BC5RgUnorm => None, // fourcc
-        ImageFormat::BC5RgSnorm => None, // fourcc
+        ImageFormat::BC3NormalXgxr => Some(D3DFormat::DXT5), // fourcc
+        ImageFormat::BC4RUnorm => None,                      // fourcc
+        ImageFormat::BC4RSnorm => None,                      // fourcc
+        ImageFormat::BC5RgUnorm => None,                     // fourcc
+        ImageFormat::BC5RgSnorm => None,                     // fourcc
         ImageFormat::BC6hRgbUfloat => None,
         ImageFormat::BC6hRgbSfloat => None,
         ImageFormat::BC7RgbaUnorm => None,
@@ -314,7 +782,17 @@ fn d3d_from_image_format(value: ImageFormat) -> Option<D3DFormat> {
         ImageFormat::Bgra8Unorm => Some(D3DFormat::A8R8G8B8),
         ImageFormat::Bgra8UnormSrgb => Some(D3DFormat::A8R8G8B8),
         ImageFormat::Bgra4Unorm => Some(D3DFormat::A4R4G4B4),
+        ImageFormat::Bgrx8Unorm => Some(D3DFormat::X8R8G8B8),
+        ImageFormat::Rgbx8Unorm => Some(D3DFormat::X8B8G8R8),
+        ImageFormat::B5g6r5Unorm => Some(D3DFormat::R5G6B5),
+        ImageFormat::L8Unorm => Some(D3DFormat::L8),
+        ImageFormat::A8Unorm => Some(D3DFormat::A8),
+        ImageFormat::La8Unorm => Some(D3DFormat::A8L8),
         ImageFormat::Bgr8Unorm => Some(D3DFormat::R8G8B8),
+        ImageFormat::Rgb16Float => None,
+        ImageFormat::Rgb8Unorm => None,
+        ImageFormat::Rgb9e5Ufloat => None,
+        ImageFormat::Rg11b10Float => None,
     }
 }
 
@@ -326,6 +804,7 @@ fn dxgi_from_image_format(value: ImageFormat) -> Option<DxgiFormat> {
         ImageFormat::BC2RgbaUnormSrgb => Some(DxgiFormat::BC2_UNorm_sRGB),
         ImageFormat::BC3RgbaUnorm => Some(DxgiFormat::BC3_UNorm),
         ImageFormat::BC3RgbaUnormSrgb => Some(DxgiFormat::BC3_UNorm_sRGB),
+        ImageFormat::BC3NormalXgxr => None,
         ImageFormat::BC4RUnorm => Some(DxgiFormat::BC4_UNorm),
         ImageFormat::BC4RSnorm => Some(DxgiFormat::BC4_SNorm),
         ImageFormat::BC5RgUnorm => Some(DxgiFormat::BC5_UNorm),
@@ -341,11 +820,21 @@ fn dxgi_from_image_format(value: ImageFormat) -> Option<DxgiFormat> {
         ImageFormat::Rgba8Unorm => Some(DxgiFormat::R8G8B8A8_UNorm),
         ImageFormat::Rgba8UnormSrgb => Some(DxgiFormat::R8G8B8A8_UNorm_sRGB),
         ImageFormat::Rgba16Float => Some(DxgiFormat::R16G16B16A16_Float),
+        ImageFormat::Rgb9e5Ufloat => Some(DxgiFormat::R9G9B9E5_SharedExp),
+        ImageFormat::Rg11b10Float => Some(DxgiFormat::R11G11B10_Float),
         ImageFormat::Rgba32Float => Some(DxgiFormat::R32G32B32A32_Float),
         ImageFormat::Bgra8Unorm => Some(DxgiFormat::B8G8R8A8_UNorm),
         ImageFormat::Bgra8UnormSrgb => Some(DxgiFormat::B8G8R8A8_UNorm_sRGB),
         ImageFormat::Bgra4Unorm => Some(DxgiFormat::B4G4R4A4_UNorm),
+        ImageFormat::Bgrx8Unorm => Some(DxgiFormat::B8G8R8X8_UNorm),
+        ImageFormat::Rgbx8Unorm => None,
+        ImageFormat::B5g6r5Unorm => Some(DxgiFormat::B5G6R5_UNorm),
+        ImageFormat::L8Unorm => None,
+        ImageFormat::A8Unorm => Some(DxgiFormat::A8_UNorm),
+        ImageFormat::La8Unorm => None,
         ImageFormat::Bgr8Unorm => None,
+        ImageFormat::Rgb16Float => None,
+        ImageFormat::Rgb8Unorm => None,
     }
 }
 
@@ -355,9 +844,36 @@ mod tests {
 
     use strum::IntoEnumIterator;
 
+    #[test]
+    fn surface_to_dds_with_alpha_mode() {
+        let surface = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::BC7RgbaUnorm,
+            data: &[0u8; 16][..],
+        };
+        assert_eq!(
+            AlphaMode::Straight,
+            surface.to_dds().unwrap().header10.unwrap().alpha_mode
+        );
+
+        for alpha_mode in [AlphaMode::PreMultiplied, AlphaMode::Opaque] {
+            let dds = surface.to_dds_with_alpha_mode(alpha_mode).unwrap();
+            let mut bytes = Vec::new();
+            dds.write(&mut bytes).unwrap();
+            let dds = Dds::read(bytes.as_slice()).unwrap();
+            assert_eq!(alpha_mode, dds.header10.unwrap().alpha_mode);
+        }
+    }
+
     #[test]
     fn dds_to_from_surface() {
-        for image_format in ImageFormat::iter() {
+        for image_format in ImageFormat::iter()
+            .filter(|f| !matches!(f, ImageFormat::Rgb16Float | ImageFormat::Rgb8Unorm))
+        {
             let data = vec![0u8; 4 * 4 * 6 * image_format.block_size_in_bytes()];
             let surface = Surface {
                 width: 4,
@@ -375,9 +891,41 @@ mod tests {
         }
     }
 
+    #[test]
+    fn bgr8_dds_bytes_roundtrip() {
+        // Bgr8Unorm has no DXGI format and uses the legacy R8G8B8 D3D format.
+        let data: Vec<_> = (0..4 * 4 * 3).map(|i| i as u8).collect();
+        let surface = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::Bgr8Unorm,
+            data: data.as_slice(),
+        };
+        let dds = surface.to_dds().unwrap();
+        assert!(dds.header10.is_none());
+        assert_eq!(Some(24), dds.header.spf.rgb_bit_count);
+
+        let mut bytes = Vec::new();
+        dds.write(&mut bytes).unwrap();
+        let dds = Dds::read(bytes.as_slice()).unwrap();
+        assert_eq!(Some(D3DFormat::R8G8B8), dds.get_d3d_format());
+
+        let new_surface = Surface::from_dds(&dds).unwrap();
+        assert_eq!(surface, new_surface);
+        assert_eq!(
+            &[2, 1, 0, 255],
+            &new_surface.decode_rgba8().unwrap().data[..4]
+        );
+    }
+
     #[test]
     fn dds_to_from_surface_cube() {
-        for image_format in ImageFormat::iter() {
+        for image_format in ImageFormat::iter()
+            .filter(|f| !matches!(f, ImageFormat::Rgb16Float | ImageFormat::Rgb8Unorm))
+        {
             let data = vec![0u8; 4 * 4 * 6 * image_format.block_size_in_bytes()];
             let surface = Surface {
                 width: 4,
@@ -394,4 +942,908 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn verify_dds_roundtrip_fixtures() {
+        for name in [
+            "bc1_r",
+            "bc1_g",
+            "bc1_b",
+            "bc2_r",
+            "bc2_g",
+            "bc2_b",
+            "bc3_r",
+            "bc3_g",
+            "bc3_b",
+            "bc4_r",
+            "bc4_r_signed",
+            "bc5_r",
+            "bc5_g",
+            "bc5_r_signed",
+            "bc5_g_signed",
+        ] {
+            let path = format!("{}/../bcn_test/data/{name}.dds", env!("CARGO_MANIFEST_DIR"));
+            let mut reader = std::io::BufReader::new(std::fs::File::open(path).unwrap());
+            let dds = Dds::read(&mut reader).unwrap();
+            verify_dds_roundtrip(&dds).unwrap();
+        }
+    }
+
+    #[test]
+    fn verify_dds_roundtrip_mipmaps_cube() {
+        for image_format in ImageFormat::iter()
+            .filter(|f| !matches!(f, ImageFormat::Rgb16Float | ImageFormat::Rgb8Unorm))
+        {
+            let data = vec![1u8; 6 * 4 * 4 * 3 * image_format.block_size_in_bytes()];
+            let dds = Surface {
+                width: 4,
+                height: 4,
+                depth: 1,
+                layers: 6,
+                mipmaps: 3,
+                image_format,
+                data: data.as_slice(),
+            }
+            .to_dds()
+            .unwrap();
+            verify_dds_roundtrip(&dds).unwrap();
+        }
+    }
+
+    #[test]
+    fn decode_truncated_bc7_dds() {
+        let dds = Surface {
+            width: 8,
+            height: 8,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::BC7RgbaUnorm,
+            data: &[0u8; 4 * 16][..],
+        }
+        .to_dds()
+        .unwrap();
+        let mut bytes = Vec::new();
+        dds.write(&mut bytes).unwrap();
+
+        // Remove part of the last block like an incomplete download.
+        bytes.truncate(bytes.len() - 5);
+        let dds = Dds::read(bytes.as_slice()).unwrap();
+        let surface = Surface::from_dds(&dds).unwrap();
+        assert!(matches!(
+            surface.decode_rgba8(),
+            Err(SurfaceError::NotEnoughData { .. })
+        ));
+        assert!(matches!(
+            surface.decode_rgbaf32(),
+            Err(SurfaceError::NotEnoughData { .. })
+        ));
+        assert!(matches!(
+            surface.decode_region_rgba8(0, 0, 4, 4, 4, 4),
+            Err(SurfaceError::NotEnoughData { .. })
+        ));
+        assert!(matches!(
+            image_from_dds(&dds, 0),
+            Err(CreateImageError::DecompressSurface(
+                SurfaceError::NotEnoughData { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn verify_dds_roundtrip_too_little_data() {
+        let mut dds = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::Rgba8Unorm,
+            data: &[0u8; 64][..],
+        }
+        .to_dds()
+        .unwrap();
+        // The data only contains the base mip level.
+        dds.header.mip_map_count = Some(3);
+        assert!(matches!(
+            verify_dds_roundtrip(&dds),
+            Err(CreateDdsError::CompressSurface(
+                SurfaceError::NotEnoughData { .. }
+            ))
+        ));
+    }
+
+    fn volume_dds(resource_dimension: ddsfile::D3D10ResourceDimension) -> Dds {
+        let mut dds = Surface {
+            width: 2,
+            height: 2,
+            depth: 4,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::Rgba8Unorm,
+            data: &[0u8; 2 * 2 * 4 * 4][..],
+        }
+        .to_dds()
+        .unwrap();
+        dds.header10.as_mut().unwrap().resource_dimension = resource_dimension;
+        dds
+    }
+
+    #[test]
+    fn dds_depth_interpretation_volume() {
+        let dds = volume_dds(ddsfile::D3D10ResourceDimension::Texture3D);
+        assert_eq!(
+            DdsDepthInterpretation::Volume,
+            dds_depth_interpretation(&dds)
+        );
+
+        let surface = Surface::from_dds(&dds).unwrap();
+        assert_eq!((4, 1), (surface.depth, surface.layers));
+    }
+
+    #[test]
+    fn dds_depth_interpretation_array() {
+        let dds = volume_dds(ddsfile::D3D10ResourceDimension::Texture2D);
+        assert_eq!(
+            DdsDepthInterpretation::ArrayLayers,
+            dds_depth_interpretation(&dds)
+        );
+
+        let surface = Surface::from_dds(&dds).unwrap();
+        assert_eq!((1, 4), (surface.depth, surface.layers));
+    }
+
+    #[test]
+    fn dds_depth_interpretation_legacy() {
+        let mut dds = volume_dds(ddsfile::D3D10ResourceDimension::Texture2D);
+        dds.header10 = None;
+        assert_eq!(
+            DdsDepthInterpretation::Volume,
+            dds_depth_interpretation(&dds)
+        );
+
+        // The caps flags disagree with the depth.
+        dds.header.caps2.remove(Caps2::VOLUME);
+        assert_eq!(
+            DdsDepthInterpretation::ArrayLayers,
+            dds_depth_interpretation(&dds)
+        );
+    }
+
+    #[test]
+    fn dds_depth_interpretation_array_overflow() {
+        let mut dds = volume_dds(ddsfile::D3D10ResourceDimension::Texture2D);
+        dds.header.depth = Some(u32::MAX);
+        dds.header10.as_mut().unwrap().array_size = 2;
+        assert_eq!(
+            Err(SurfaceError::DdsLayerCountWouldOverflow {
+                layers: 2,
+                factor: u32::MAX
+            }),
+            Surface::from_dds(&dds)
+        );
+
+        let mut dds = volume_dds(ddsfile::D3D10ResourceDimension::Texture3D);
+        let header10 = dds.header10.as_mut().unwrap();
+        header10.array_size = u32::MAX;
+        header10.misc_flag = ddsfile::MiscFlag::TEXTURECUBE;
+        assert_eq!(
+            Err(SurfaceError::DdsLayerCountWouldOverflow {
+                layers: u32::MAX,
+                factor: 6
+            }),
+            Surface::from_dds(&dds)
+        );
+    }
+
+    #[test]
+    fn image_format_names_bc1() {
+        let format = ImageFormat::BC1RgbaUnorm;
+        assert_eq!("DXT1", format.dds_label());
+        assert_eq!(Some("BC1_UNorm".to_string()), format.dxgi_name());
+        assert_eq!(Some("DXT1".to_string()), format.d3d_name());
+    }
+
+    #[test]
+    fn image_format_names_no_dds_format() {
+        assert_eq!(None, ImageFormat::Rgb16Float.dxgi_name());
+        assert_eq!(None, ImageFormat::Rgb16Float.d3d_name());
+        assert_eq!(None, ImageFormat::Rgb8Unorm.dxgi_name());
+        assert_eq!(None, ImageFormat::Rgb8Unorm.d3d_name());
+        assert_eq!(None, ImageFormat::Bgr8Unorm.dxgi_name());
+        assert_eq!(
+            Some("R8G8B8".to_string()),
+            ImageFormat::Bgr8Unorm.d3d_name()
+        );
+    }
+
+    #[test]
+    fn to_dds_unsupported_format() {
+        let surface = Surface {
+            width: 1,
+            height: 1,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::Rgb16Float,
+            data: &[0u8; 6][..],
+        };
+        assert!(matches!(
+            surface.to_dds(),
+            Err(CreateDdsError::UnsupportedFormat(ImageFormat::Rgb16Float))
+        ));
+    }
+
+    #[test]
+    fn to_dds_complex_caps_cube_mipmaps() {
+        // Each of the 3 mipmaps is a single 4x4 block.
+        let data = vec![0u8; 6 * 3 * 16];
+        let surface = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 6,
+            mipmaps: 3,
+            image_format: ImageFormat::BC7RgbaUnorm,
+            data: data.as_slice(),
+        };
+        let dds = surface.to_dds().unwrap();
+        assert!(dds.header.caps.contains(Caps::COMPLEX | Caps::MIPMAP));
+    }
+
+    #[test]
+    fn to_dds_complex_caps_cube() {
+        let surface = Surface {
+            width: 1,
+            height: 1,
+            depth: 1,
+            layers: 6,
+            mipmaps: 1,
+            image_format: ImageFormat::Rgba8Unorm,
+            data: &[0u8; 6 * 4][..],
+        };
+        let dds = surface.to_dds().unwrap();
+        assert!(dds.header.caps.contains(Caps::COMPLEX));
+    }
+
+    #[test]
+    fn to_dds_complex_caps_2d() {
+        let surface = Surface {
+            width: 1,
+            height: 1,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::Rgba8Unorm,
+            data: &[0u8; 4][..],
+        };
+        let dds = surface.to_dds().unwrap();
+        assert!(!dds.header.caps.contains(Caps::COMPLEX));
+    }
+
+    #[test]
+    fn dds_to_from_surface_1d() {
+        let data = vec![0u8; 256 * 4];
+        let surface = Surface {
+            width: 256,
+            height: 1,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::Rgba8Unorm,
+            data: data.as_slice(),
+        };
+        let dds = surface.to_dds_1d().unwrap();
+        assert_eq!(
+            Some(ddsfile::D3D10ResourceDimension::Texture1D),
+            dds.header10.as_ref().map(|h| h.resource_dimension)
+        );
+        assert_eq!(surface, Surface::from_dds(&dds).unwrap());
+
+        // Surfaces with a height of 1 are still 2D by default.
+        let dds = surface.to_dds().unwrap();
+        assert_eq!(
+            Some(ddsfile::D3D10ResourceDimension::Texture2D),
+            dds.header10.as_ref().map(|h| h.resource_dimension)
+        );
+        assert_eq!(surface, Surface::from_dds(&dds).unwrap());
+    }
+
+    #[test]
+    fn dds_to_dds_1d_invalid() {
+        let data = vec![0u8; 16];
+        let surface = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::BC7RgbaUnorm,
+            data: data.as_slice(),
+        };
+        assert!(matches!(
+            surface.to_dds_1d(),
+            Err(CreateDdsError::Invalid1dSurface {
+                height: 4,
+                depth: 1,
+                layers: 1,
+                image_format: ImageFormat::BC7RgbaUnorm
+            })
+        ));
+
+        let surface = Surface {
+            width: 4,
+            height: 1,
+            data: &[0u8; 16][..],
+            image_format: ImageFormat::Rgba8Unorm,
+            ..surface
+        };
+        assert!(surface.to_dds_1d().is_ok());
+        assert!(matches!(
+            Surface {
+                depth: 2,
+                ..surface
+            }
+            .to_dds_1d(),
+            Err(CreateDdsError::Invalid1dSurface { depth: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn dds_from_dxt1_srgb() {
+        let mut dds = Dds::new_d3d(ddsfile::NewD3dParams {
+            height: 4,
+            width: 4,
+            depth: None,
+            format: D3DFormat::DXT1,
+            mipmap_levels: None,
+            caps2: None,
+        })
+        .unwrap();
+        dds.data = vec![0u8; 8];
+
+        let linear = Surface::from_dds_with_srgb(&dds, false).unwrap();
+        assert_eq!(ImageFormat::BC1RgbaUnorm, linear.image_format);
+
+        let srgb = Surface::from_dds_with_srgb(&dds, true).unwrap();
+        assert_eq!(ImageFormat::BC1RgbaUnormSrgb, srgb.image_format);
+
+        // The interpretation doesn't change the decoded values.
+        assert_eq!(linear.decode_rgba8(), srgb.decode_rgba8());
+    }
+
+    #[test]
+    fn dds_from_dxgi_srgb() {
+        // DX10 files already specify sRGB or linear.
+        let data = [0u8; 8];
+        let surface = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::BC1RgbaUnorm,
+            data: data.as_slice(),
+        };
+        let dds = surface.to_dds().unwrap();
+        assert_eq!(
+            ImageFormat::BC1RgbaUnorm,
+            Surface::from_dds_with_srgb(&dds, true)
+                .unwrap()
+                .image_format
+        );
+    }
+
+    #[test]
+    fn surface_from_dds_unsupported_fourcc() {
+        let mut dds = Dds::new_d3d(ddsfile::NewD3dParams {
+            height: 4,
+            width: 4,
+            depth: None,
+            format: D3DFormat::DXT1,
+            mipmap_levels: None,
+            caps2: None,
+        })
+        .unwrap();
+        let fourcc = FourCC(u32::from_le_bytes(*b"ABCD"));
+        dds.header.spf.fourcc = Some(fourcc.clone());
+
+        let error = Surface::from_dds(&dds).map(|_| ()).unwrap_err();
+        assert_eq!(
+            SurfaceError::UnsupportedDdsFormat(DdsFormatInfo {
+                dxgi: None,
+                d3d: None,
+                fourcc: Some(fourcc),
+            }),
+            error
+        );
+        assert_eq!(
+            "DDS image format is not supported (DXGI: None, D3D: None, FourCC: \"ABCD\" (0x44434241))",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn surface_from_dds_missing_dx10_header() {
+        let mut dds = Dds::new_dxgi(ddsfile::NewDxgiParams {
+            height: 4,
+            width: 4,
+            depth: None,
+            format: DxgiFormat::BC7_UNorm,
+            mipmap_levels: None,
+            array_layers: None,
+            caps2: None,
+            is_cubemap: false,
+            resource_dimension: ddsfile::D3D10ResourceDimension::Texture2D,
+            alpha_mode: AlphaMode::Straight,
+        })
+        .unwrap();
+        dds.header10 = None;
+
+        assert_eq!(
+            Err(SurfaceError::MissingDx10Header),
+            Surface::from_dds(&dds).map(|_| ())
+        );
+    }
+
+    #[test]
+    fn to_dds_minimal_bc1_roundtrip() {
+        let surface = Surface {
+            width: 8,
+            height: 8,
+            depth: 1,
+            layers: 1,
+            mipmaps: 4,
+            image_format: ImageFormat::BC1RgbaUnorm,
+            data: (0..(4 + 1 + 1 + 1) * 8)
+                .map(|i| i as u8)
+                .collect::<Vec<_>>(),
+        };
+        let bytes = surface.to_dds_minimal().unwrap();
+        assert_eq!(128 + surface.data.len(), bytes.len());
+
+        let dds = Dds::read(bytes.as_slice()).unwrap();
+        assert!(dds.header10.is_none());
+        assert_eq!(None, dds.header.linear_size);
+        assert_eq!(None, dds.header.pitch);
+        assert_eq!(Some(FourCC(FourCC::DXT1)), dds.header.spf.fourcc);
+        let new_surface = Surface::from_dds(&dds).unwrap();
+        assert_eq!(
+            (8, 8, 1, 1, 4),
+            (
+                new_surface.width,
+                new_surface.height,
+                new_surface.depth,
+                new_surface.layers,
+                new_surface.mipmaps
+            )
+        );
+        assert_eq!(ImageFormat::BC1RgbaUnorm, new_surface.image_format);
+        assert_eq!(surface.data, new_surface.data);
+    }
+
+    #[test]
+    fn to_dds_minimal_requires_dx10() {
+        let surface = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::BC7RgbaUnorm,
+            data: &[0u8; 16],
+        };
+        assert!(matches!(
+            surface.to_dds_minimal(),
+            Err(CreateDdsError::RequiresDx10Header {
+                image_format: ImageFormat::BC7RgbaUnorm,
+                layers: 1
+            })
+        ));
+
+        // Array textures require a DX10 header even for legacy formats.
+        let surface = Surface {
+            layers: 2,
+            image_format: ImageFormat::BC1RgbaUnorm,
+            ..surface
+        };
+        assert!(matches!(
+            surface.to_dds_minimal(),
+            Err(CreateDdsError::RequiresDx10Header { layers: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn dds_from_dxt5_invalid_linear_size() {
+        let mut dds = Dds::new_d3d(ddsfile::NewD3dParams {
+            height: 8,
+            width: 8,
+            depth: None,
+            format: D3DFormat::DXT5,
+            mipmap_levels: None,
+            caps2: None,
+        })
+        .unwrap();
+        dds.data = vec![0u8; 64];
+
+        // Write an incorrect linear size that doesn't match the mip 0 size of 64 bytes.
+        let mut bytes = Vec::new();
+        dds.write(&mut bytes).unwrap();
+        bytes[20..24].copy_from_slice(&16u32.to_le_bytes());
+
+        let dds = Dds::read(bytes.as_slice()).unwrap();
+        assert_eq!(Some(16), dds.header.linear_size);
+
+        let rgba8 = SurfaceRgba8::decode_dds(&dds).unwrap();
+        assert_eq!((8, 8), (rgba8.width, rgba8.height));
+        assert_eq!(8 * 8 * 4, rgba8.data.len());
+    }
+
+    #[test]
+    fn dds_alpha_mode_dxt2() {
+        let dds = Dds::new_d3d(ddsfile::NewD3dParams {
+            height: 4,
+            width: 4,
+            depth: None,
+            format: D3DFormat::DXT2,
+            mipmap_levels: None,
+            caps2: None,
+        })
+        .unwrap();
+        assert_eq!(AlphaMode::PreMultiplied, dds_alpha_mode(&dds));
+    }
+
+    #[test]
+    fn dds_alpha_mode_dxt3() {
+        let dds = Dds::new_d3d(ddsfile::NewD3dParams {
+            height: 4,
+            width: 4,
+            depth: None,
+            format: D3DFormat::DXT3,
+            mipmap_levels: None,
+            caps2: None,
+        })
+        .unwrap();
+        assert_eq!(AlphaMode::Unknown, dds_alpha_mode(&dds));
+    }
+
+    #[test]
+    fn dds_alpha_mode_dxgi() {
+        let dds = Dds::new_dxgi(ddsfile::NewDxgiParams {
+            height: 4,
+            width: 4,
+            depth: None,
+            format: DxgiFormat::BC3_UNorm,
+            mipmap_levels: None,
+            array_layers: None,
+            caps2: None,
+            is_cubemap: false,
+            resource_dimension: ddsfile::D3D10ResourceDimension::Texture2D,
+            alpha_mode: AlphaMode::PreMultiplied,
+        })
+        .unwrap();
+        assert_eq!(AlphaMode::PreMultiplied, dds_alpha_mode(&dds));
+    }
+
+    #[test]
+    fn dds_alpha_mode_dxgi_misc_flags2() {
+        let dds = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::BC3RgbaUnorm,
+            data: &[0u8; 16][..],
+        }
+        .to_dds()
+        .unwrap();
+
+        // Set misc_flags2 after the magic, header, and first 4 fields of the DX10 header.
+        let mut bytes = Vec::new();
+        dds.write(&mut bytes).unwrap();
+        bytes[144..148].copy_from_slice(&2u32.to_le_bytes());
+
+        let dds = Dds::read(bytes.as_slice()).unwrap();
+        assert_eq!(AlphaMode::PreMultiplied, dds_alpha_mode(&dds));
+    }
+
+    #[test]
+    fn alpha_image_from_dds_bc3() {
+        // Alpha endpoints 255 and 0 with indices 1, 6, 4, and 0 for each row.
+        let dds = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::BC3RgbaUnorm,
+            data: &[255u8, 0, 73, 98, 219, 36, 9, 0, 0, 0, 0, 0, 0, 0, 0, 0][..],
+        }
+        .to_dds()
+        .unwrap();
+
+        let image = alpha_image_from_dds(&dds, 0).unwrap();
+        assert_eq!((4, 4), image.dimensions());
+        assert_eq!(
+            &[0, 0, 0, 0, 73, 73, 73, 73, 145, 145, 145, 145, 255, 255, 255, 255],
+            image.as_raw().as_slice()
+        );
+    }
+
+    #[test]
+    fn alpha_image_from_dds_bc1_opaque() {
+        // c0 > c1 uses the 4 color mode with no transparency.
+        let dds = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::BC1RgbaUnorm,
+            data: &[255u8, 255, 0, 0, 0xE4, 0xE4, 0xE4, 0xE4][..],
+        }
+        .to_dds()
+        .unwrap();
+
+        let image = alpha_image_from_dds(&dds, 0).unwrap();
+        assert_eq!(&[255u8; 16], image.as_raw().as_slice());
+    }
+
+    #[test]
+    fn dynamic_image_from_dds_rgba32float() {
+        // Values outside the range 0.0 to 1.0 should be preserved.
+        let data: Vec<_> = [-1.0f32, 0.25, 2.0, 1.0]
+            .iter()
+            .flat_map(|f| f.to_le_bytes())
+            .collect();
+        let dds = Surface {
+            width: 1,
+            height: 1,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::Rgba32Float,
+            data: data.as_slice(),
+        }
+        .to_dds()
+        .unwrap();
+
+        match dynamic_image_from_dds(&dds, 0).unwrap() {
+            image::DynamicImage::ImageRgba32F(image) => {
+                assert_eq!(&[-1.0, 0.25, 2.0, 1.0], image.as_raw().as_slice())
+            }
+            _ => panic!("expected an RGBA32F image"),
+        }
+    }
+
+    #[test]
+    fn dynamic_image_from_dds_rgba8() {
+        let dds = Surface {
+            width: 1,
+            height: 1,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::Bgra8Unorm,
+            data: &[1u8, 2, 3, 4][..],
+        }
+        .to_dds()
+        .unwrap();
+
+        match dynamic_image_from_dds(&dds, 0).unwrap() {
+            image::DynamicImage::ImageRgba8(image) => {
+                assert_eq!(&[3, 2, 1, 4], image.as_raw().as_slice())
+            }
+            _ => panic!("expected an RGBA8 image"),
+        }
+    }
+
+    #[test]
+    fn image_from_dds_subresource_cube() {
+        // Use a different value for each face.
+        let data: Vec<_> = (0..6u8)
+            .flat_map(|layer| vec![layer; (16 + 4 + 1) * 4])
+            .collect();
+        let dds = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 6,
+            mipmaps: 3,
+            image_format: ImageFormat::Rgba8Unorm,
+            data: data.as_slice(),
+        }
+        .to_dds()
+        .unwrap();
+
+        let image = image_from_dds_subresource(&dds, 3, 0, 1).unwrap();
+        assert_eq!((2, 2), image.dimensions());
+        assert_eq!(&vec![3u8; 2 * 2 * 4], image.as_raw());
+
+        let image = image_from_dds_subresource(&dds, 5, 0, 2).unwrap();
+        assert_eq!((1, 1), image.dimensions());
+        assert_eq!(&vec![5u8; 4], image.as_raw());
+    }
+
+    #[test]
+    fn image_from_dds_subresource_invalid_layer() {
+        let data = vec![0u8; 4 * 4 * 4];
+        let dds = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::Rgba8Unorm,
+            data: data.as_slice(),
+        }
+        .to_dds()
+        .unwrap();
+
+        assert!(image_from_dds_subresource(&dds, 1, 0, 0).is_err());
+    }
+
+    fn legacy_pixel_format_flags(image_format: ImageFormat) -> PixelFormatFlags {
+        let data = vec![0u8; 4 * 4 * image_format.block_size_in_bytes()];
+        let surface = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format,
+            data: data.as_slice(),
+        };
+        let format = d3d_from_image_format(image_format).unwrap();
+        surface.new_d3d_dds(format).unwrap().header.spf.flags
+    }
+
+    #[test]
+    fn legacy_alpha_pixels_bgra8() {
+        let flags = legacy_pixel_format_flags(ImageFormat::Bgra8Unorm);
+        assert!(flags.contains(PixelFormatFlags::ALPHA_PIXELS | PixelFormatFlags::RGB));
+    }
+
+    #[test]
+    fn legacy_alpha_pixels_rgba8() {
+        let flags = legacy_pixel_format_flags(ImageFormat::Rgba8Unorm);
+        assert!(flags.contains(PixelFormatFlags::ALPHA_PIXELS | PixelFormatFlags::RGB));
+    }
+
+    #[test]
+    fn legacy_alpha_pixels_bgra4() {
+        let flags = legacy_pixel_format_flags(ImageFormat::Bgra4Unorm);
+        assert!(flags.contains(PixelFormatFlags::ALPHA_PIXELS | PixelFormatFlags::RGB));
+    }
+
+    #[test]
+    fn legacy_luminance_l8() {
+        let flags = legacy_pixel_format_flags(ImageFormat::La8Unorm);
+        assert!(flags.contains(PixelFormatFlags::LUMINANCE | PixelFormatFlags::ALPHA_PIXELS));
+        assert!(!flags.contains(PixelFormatFlags::RGB));
+    }
+
+    #[test]
+    fn legacy_d3d_formats_to_from_bytes() {
+        for (image_format, d3d_format) in [
+            (ImageFormat::B5g6r5Unorm, D3DFormat::R5G6B5),
+            (ImageFormat::L8Unorm, D3DFormat::L8),
+            (ImageFormat::A8Unorm, D3DFormat::A8),
+            (ImageFormat::La8Unorm, D3DFormat::A8L8),
+        ] {
+            let data = vec![0u8; 4 * 4 * image_format.block_size_in_bytes()];
+            let surface = Surface {
+                width: 4,
+                height: 4,
+                depth: 1,
+                layers: 1,
+                mipmaps: 1,
+                image_format,
+                data: data.as_slice(),
+            };
+            let mut bytes = Vec::new();
+            surface
+                .new_d3d_dds(d3d_format)
+                .unwrap()
+                .write(&mut bytes)
+                .unwrap();
+
+            let dds = Dds::read(bytes.as_slice()).unwrap();
+            assert_eq!(Ok(image_format), dds_image_format(&dds));
+        }
+    }
+
+    #[test]
+    fn legacy_alpha_pixels_bgr8() {
+        // Bgr8Unorm has no DXGI format and is always written using D3D.
+        let flags = legacy_pixel_format_flags(ImageFormat::Bgr8Unorm);
+        assert!(!flags.contains(PixelFormatFlags::ALPHA_PIXELS));
+        assert!(flags.contains(PixelFormatFlags::RGB));
+    }
+
+    #[test]
+    fn legacy_alpha_pixels_rgbx8() {
+        // Rgbx8Unorm has no DXGI format and is always written using D3D.
+        let flags = legacy_pixel_format_flags(ImageFormat::Rgbx8Unorm);
+        assert!(!flags.contains(PixelFormatFlags::ALPHA_PIXELS));
+        assert!(flags.contains(PixelFormatFlags::RGB));
+    }
+
+    #[test]
+    fn legacy_rgbx8_opaque_alpha() {
+        let surface = Surface {
+            width: 1,
+            height: 1,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::Rgbx8Unorm,
+            data: &[1u8, 2, 3, 4][..],
+        };
+        let dds = surface.to_dds().unwrap();
+        let surface = Surface::from_dds(&dds).unwrap();
+        assert_eq!(ImageFormat::Rgbx8Unorm, surface.image_format);
+        assert_eq!(vec![1, 2, 3, 255], surface.decode_rgba8().unwrap().data);
+    }
+
+    #[test]
+    fn bc3_normal_xgxr_fourcc() {
+        let surface = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::BC3NormalXgxr,
+            data: &[0u8; 16][..],
+        };
+        let dds = surface.to_dds().unwrap();
+        assert_eq!(Some(FourCC(RXGB)), dds.header.spf.fourcc);
+        assert_eq!(Ok(ImageFormat::BC3NormalXgxr), dds_image_format(&dds));
+    }
+
+    #[test]
+    fn to_dds_with_dxgi_typeless() {
+        let data = [0u8; 4 * 8 * 4];
+        let surface = Surface {
+            width: 4,
+            height: 8,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::Rgba8Unorm,
+            data: data.as_slice(),
+        };
+        let dds = surface
+            .to_dds_with_dxgi(DxgiFormat::R8G8B8A8_Typeless)
+            .unwrap();
+
+        let mut bytes = Vec::new();
+        dds.write(&mut bytes).unwrap();
+        let dds = Dds::read(bytes.as_slice()).unwrap();
+        assert_eq!(Some(DxgiFormat::R8G8B8A8_Typeless), dds.get_dxgi_format());
+        assert_eq!((4, 8), (dds.get_width(), dds.get_height()));
+        assert_eq!(data.as_slice(), dds.data);
+    }
+
+    #[test]
+    fn to_dds_with_dxgi_incompatible() {
+        let surface = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::Rgba8Unorm,
+            data: &[0u8; 4 * 4 * 4][..],
+        };
+        assert!(matches!(
+            surface.to_dds_with_dxgi(DxgiFormat::BC1_Typeless),
+            Err(CreateDdsError::IncompatibleDxgiFormat {
+                format: DxgiFormat::BC1_Typeless,
+                image_format: ImageFormat::Rgba8Unorm
+            })
+        ));
+    }
 }
@@ -1,13 +1,28 @@
 use std::ops::Range;
+#[cfg(feature = "timing")]
+use std::time::{Duration, Instant};
 
 use crate::{
     bcn::{self, decode_bcn},
+    div_round_up,
     error::SurfaceError,
     mip_dimension,
-    rgba::{decode_rgba, Bgr8, Bgra4, Bgra8, R8Snorm, Rg8, Rg8Snorm, Rgba8, Rgbaf16, Rgbaf32, R8},
-    ImageFormat, Surface, SurfaceRgba32Float, SurfaceRgba8,
+    rgba::{
+        decode_rgba, B5g6r5, Bgr8, Bgra4, Bgra8, Bgrx8, La8, R8Snorm, Rg11b10, Rg8, Rg8Snorm, Rgb8,
+        Rgb9e5, Rgba8, Rgbaf16, Rgbaf32, Rgbf16, Rgbx8, A8, L8, R8,
+    },
+    srgb_to_linear, DecodeOptions, ImageFormat, RgBlueDefault, Surface, SurfaceRgba32Float,
+    SurfaceRgba8,
 };
-use bcn::{Bc1, Bc2, Bc3, Bc4, Bc4S, Bc5, Bc5S, Bc6, Bc7};
+use bcn::{Bc1, Bc2, Bc3, Bc4, Bc4S, Bc5, Bc5S, Bc6, Bc6S, Bc7};
+
+// The decode time for each subresource in decoding order.
+#[cfg(feature = "timing")]
+type Timings = Vec<(Subresource, Duration)>;
+
+// Decoding is never timed without the "timing" feature.
+#[cfg(not(feature = "timing"))]
+type Timings = ();
 
 impl<T: AsRef<[u8]>> Surface<T> {
     /// Decode all layers and mipmaps from `surface` to RGBA8.
@@ -15,15 +30,185 @@ impl<T: AsRef<[u8]>> Surface<T> {
         self.decode_layers_mipmaps_rgba8(0..self.layers, 0..self.mipmaps)
     }
 
+    /// Decode all layers and mipmaps from `surface` to RGBA8 with additional `options`.
+    pub fn decode_rgba8_with_options(
+        &self,
+        options: DecodeOptions,
+    ) -> Result<SurfaceRgba8<Vec<u8>>, SurfaceError> {
+        self.decode_rgba8_inner(0..self.layers, 0..self.mipmaps, options, None)
+    }
+
+    /// Decode all layers and mipmaps from `surface` to RGBA8 with additional `options`
+    /// and record the time spent decoding each [Subresource].
+    ///
+    /// This is intended for profiling and includes one entry for each decoded subresource
+    /// in the same order as the decoded data.
+    #[cfg(feature = "timing")]
+    pub fn decode_rgba8_with_timing(
+        &self,
+        options: DecodeOptions,
+    ) -> Result<(SurfaceRgba8<Vec<u8>>, Timings), SurfaceError> {
+        let mut timings = Vec::new();
+        let surface =
+            self.decode_rgba8_inner(0..self.layers, 0..self.mipmaps, options, Some(&mut timings))?;
+        Ok((surface, timings))
+    }
+
+    /// Decode only the base mip level for all layers from `surface` to RGBA8.
+    ///
+    /// This is equivalent to [Surface::decode_layers_mipmaps_rgba8] with mipmaps `0..1`.
+    pub fn decode_base_rgba8(&self) -> Result<SurfaceRgba8<Vec<u8>>, SurfaceError> {
+        self.decode_layers_mipmaps_rgba8(0..self.layers, 0..1)
+    }
+
+    /// Decode a single 2D image for `layer` and `mipmap` from `surface` to RGBA8.
+    ///
+    /// Only the data for the requested image is decoded,
+    /// which is faster than [Surface::decode_layers_mipmaps_rgba8] for surfaces with many layers.
+    /// 3D surfaces only decode the first depth slice.
+    pub fn decode_mipmap_rgba8(
+        &self,
+        layer: u32,
+        mipmap: u32,
+    ) -> Result<SurfaceRgba8<Vec<u8>>, SurfaceError> {
+        let options = DecodeOptions::default();
+        let pixels = (mip_dimension(self.width, mipmap) as u64)
+            .checked_mul(mip_dimension(self.height, mipmap) as u64);
+        check_max_pixels(pixels, options)?;
+        self.validate()?;
+
+        let image_format = reinterpret_format(self.image_format, options);
+        let data = decode_subresource(self, layer, 0, mipmap, image_format, options)?;
+
+        Ok(SurfaceRgba8 {
+            width: mip_dimension(self.width, mipmap),
+            height: mip_dimension(self.height, mipmap),
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            data,
+        })
+    }
+
+    /// Decode the `width` x `height` region starting at pixel (`x`, `y`)
+    /// for `layer` and `mipmap` from `surface` to RGBA8.
+    ///
+    /// Only the blocks overlapping the region are decoded,
+    /// which is much faster than decoding the entire mipmap for large surfaces.
+    /// 3D surfaces only decode the first depth slice.
+    pub fn decode_region_rgba8(
+        &self,
+        layer: u32,
+        mipmap: u32,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<SurfaceRgba8<Vec<u8>>, SurfaceError> {
+        let options = DecodeOptions::default();
+        check_max_pixels((width as u64).checked_mul(height as u64), options)?;
+        self.validate()?;
+
+        let mip_width = mip_dimension(self.width, mipmap);
+        let mip_height = mip_dimension(self.height, mipmap);
+        if width == 0 || height == 0 {
+            return Err(SurfaceError::ZeroSizedSurface {
+                width,
+                height,
+                depth: 1,
+            });
+        }
+        if x.checked_add(width).is_none_or(|end| end > mip_width)
+            || y.checked_add(height).is_none_or(|end| end > mip_height)
+        {
+            return Err(SurfaceError::RegionOutOfBounds {
+                x,
+                y,
+                width,
+                height,
+                mip_width,
+                mip_height,
+            });
+        }
+
+        let data = self
+            .get(layer, 0, mipmap)
+            .ok_or(SurfaceError::MipmapDataOutOfBounds { layer, mipmap })?;
+
+        // Copy the rows of blocks overlapping the region.
+        let (block_width, block_height, _) = self.image_format.block_dimensions();
+        let block_size_in_bytes = self.image_format.block_size_in_bytes();
+        let blocks_per_row = div_round_up(mip_width as usize, block_width as usize);
+        let block_x = (x / block_width) as usize;
+        let block_y = (y / block_height) as usize;
+        let region_blocks_x = div_round_up((x + width) as usize, block_width as usize) - block_x;
+        let region_blocks_y = div_round_up((y + height) as usize, block_height as usize) - block_y;
+
+        let mut region_data =
+            Vec::with_capacity(region_blocks_x * region_blocks_y * block_size_in_bytes);
+        for row in block_y..block_y + region_blocks_y {
+            let start = (row * blocks_per_row + block_x) * block_size_in_bytes;
+            let row_data = data
+                .get(start..start + region_blocks_x * block_size_in_bytes)
+                .ok_or(SurfaceError::MipmapDataOutOfBounds { layer, mipmap })?;
+            region_data.extend_from_slice(row_data);
+        }
+
+        let image_format = reinterpret_format(self.image_format, options);
+        let decoded_width = region_blocks_x * block_width as usize;
+        let decoded_height = region_blocks_y * block_height as usize;
+        let offset_x = x as usize - block_x * block_width as usize;
+        let offset_y = y as usize - block_y * block_height as usize;
+        let decoded: Vec<u8> = decode_data(
+            decoded_width as u32,
+            decoded_height as u32,
+            image_format,
+            &region_data,
+            options,
+        )?;
+
+        // Crop the decoded blocks to the requested region.
+        let data = (offset_y..offset_y + height as usize)
+            .flat_map(|row| {
+                let start = (row * decoded_width + offset_x) * 4;
+                &decoded[start..start + width as usize * 4]
+            })
+            .copied()
+            .collect();
+
+        Ok(SurfaceRgba8 {
+            width,
+            height,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            data,
+        })
+    }
+
     /// Decode a specific range of layers and mipmaps from `surface` to RGBA8.
     pub fn decode_layers_mipmaps_rgba8(
         &self,
         layers: Range<u32>,
         mipmaps: Range<u32>,
     ) -> Result<SurfaceRgba8<Vec<u8>>, SurfaceError> {
+        self.decode_rgba8_inner(layers, mipmaps, DecodeOptions::default(), None)
+    }
+
+    fn decode_rgba8_inner(
+        &self,
+        layers: Range<u32>,
+        mipmaps: Range<u32>,
+        options: DecodeOptions,
+        timings: Option<&mut Timings>,
+    ) -> Result<SurfaceRgba8<Vec<u8>>, SurfaceError> {
+        check_max_pixels(self.decoded_pixels(&layers, &mipmaps), options)?;
         self.validate()?;
+        if options.check_layer_count {
+            self.check_layer_count()?;
+        }
 
-        let data = decode_surface(self, layers.clone(), mipmaps.clone())?;
+        let data = decode_surface(self, layers.clone(), mipmaps.clone(), options, timings)?;
 
         Ok(SurfaceRgba8 {
             width: mip_dimension(self.width, mipmaps.start),
@@ -38,21 +223,75 @@ impl<T: AsRef<[u8]>> Surface<T> {
     /// Decode all layers and mipmaps from `surface` to RGBAF32.
     ///
     /// Non floating point formats are normalized to the range `0.0` to `1.0`.
+    /// sRGB formats like [ImageFormat::BC7RgbaUnormSrgb] also convert color to linear.
     pub fn decode_rgbaf32(&self) -> Result<SurfaceRgba32Float<Vec<f32>>, SurfaceError> {
         self.decode_layers_mipmaps_rgbaf32(0..self.layers, 0..self.mipmaps)
     }
 
+    /// Decode all layers and mipmaps from `surface` to RGBAF32 with additional `options`.
+    ///
+    /// Non floating point formats are normalized to the range `0.0` to `1.0`.
+    /// sRGB formats like [ImageFormat::BC7RgbaUnormSrgb] also convert color to linear.
+    pub fn decode_rgbaf32_with_options(
+        &self,
+        options: DecodeOptions,
+    ) -> Result<SurfaceRgba32Float<Vec<f32>>, SurfaceError> {
+        self.decode_rgbaf32_inner(0..self.layers, 0..self.mipmaps, options, None)
+    }
+
+    /// Decode all layers and mipmaps from `surface` to RGBAF32 with additional `options`
+    /// and record the time spent decoding each [Subresource].
+    ///
+    /// This is intended for profiling and includes one entry for each decoded subresource
+    /// in the same order as the decoded data.
+    #[cfg(feature = "timing")]
+    pub fn decode_rgbaf32_with_timing(
+        &self,
+        options: DecodeOptions,
+    ) -> Result<(SurfaceRgba32Float<Vec<f32>>, Timings), SurfaceError> {
+        let mut timings = Vec::new();
+        let surface = self.decode_rgbaf32_inner(
+            0..self.layers,
+            0..self.mipmaps,
+            options,
+            Some(&mut timings),
+        )?;
+        Ok((surface, timings))
+    }
+
+    /// Decode only the base mip level for all layers from `surface` to RGBAF32.
+    ///
+    /// This is equivalent to [Surface::decode_layers_mipmaps_rgbaf32] with mipmaps `0..1`.
+    pub fn decode_base_rgbaf32(&self) -> Result<SurfaceRgba32Float<Vec<f32>>, SurfaceError> {
+        self.decode_layers_mipmaps_rgbaf32(0..self.layers, 0..1)
+    }
+
     /// Decode a specific range of layers and mipmaps from `surface` to RGBAF32.
     ///
     /// Non floating point formats are normalized to the range `0.0` to `1.0`.
+    /// sRGB formats like [ImageFormat::BC7RgbaUnormSrgb] also convert color to linear.
     pub fn decode_layers_mipmaps_rgbaf32(
         &self,
         layers: Range<u32>,
         mipmaps: Range<u32>,
     ) -> Result<SurfaceRgba32Float<Vec<f32>>, SurfaceError> {
+        self.decode_rgbaf32_inner(layers, mipmaps, DecodeOptions::default(), None)
+    }
+
+    fn decode_rgbaf32_inner(
+        &self,
+        layers: Range<u32>,
+        mipmaps: Range<u32>,
+        options: DecodeOptions,
+        timings: Option<&mut Timings>,
+    ) -> Result<SurfaceRgba32Float<Vec<f32>>, SurfaceError> {
+        check_max_pixels(self.decoded_pixels(&layers, &mipmaps), options)?;
         self.validate()?;
+        if options.check_layer_count {
+            self.check_layer_count()?;
+        }
 
-        let data = decode_surface(self, layers.clone(), mipmaps.clone())?;
+        let data = decode_surface(self, layers.clone(), mipmaps.clone(), options, timings)?;
 
         Ok(SurfaceRgba32Float {
             width: mip_dimension(self.width, mipmaps.start),
@@ -63,31 +302,89 @@ impl<T: AsRef<[u8]>> Surface<T> {
             data,
         })
     }
+
+    /// The number of pixels for all depth slices of `layers` and `mipmaps`.
+    fn decoded_pixels(&self, layers: &Range<u32>, mipmaps: &Range<u32>) -> Option<u64> {
+        // Mipmaps after the first 32 are always 1x1x1.
+        // This avoids iterating over very large ranges.
+        let tail_pixels = mipmaps.end.saturating_sub(mipmaps.start.max(32)) as u64;
+        let mip_pixels =
+            (mipmaps.start.min(32)..mipmaps.end.min(32)).try_fold(tail_pixels, |sum, mipmap| {
+                [self.height, self.depth]
+                    .iter()
+                    .try_fold(mip_dimension(self.width, mipmap) as u64, |p, d| {
+                        p.checked_mul(mip_dimension(*d, mipmap) as u64)
+                    })
+                    .and_then(|p| sum.checked_add(p))
+            })?;
+        mip_pixels.checked_mul(layers.end.saturating_sub(layers.start) as u64)
+    }
+
+    fn check_layer_count(&self) -> Result<(), SurfaceError> {
+        let layer_size = Surface {
+            width: self.width,
+            height: self.height,
+            depth: self.depth,
+            layers: 1,
+            mipmaps: self.mipmaps,
+            image_format: self.image_format,
+            data: self.data.as_ref(),
+        }
+        .expected_data_size();
+
+        // Data for more layers than expected likely indicates an incorrect layer count.
+        let actual = self.data.as_ref().len();
+        match layer_size {
+            Some(layer_size) if layer_size > 0 && actual % layer_size == 0 => {
+                let data_layers = actual / layer_size;
+                if data_layers > self.layers as usize {
+                    Err(SurfaceError::UnexpectedLayerCount {
+                        layers: self.layers,
+                        data_layers,
+                    })
+                } else {
+                    Ok(())
+                }
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// A single 2D image within a [Surface].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Subresource {
+    pub layer: u32,
+    pub depth_level: u32,
+    pub mipmap: u32,
 }
 
 fn decode_surface<T, P>(
     surface: &Surface<T>,
     layers: Range<u32>,
     mipmaps: Range<u32>,
+    options: DecodeOptions,
+    mut timings: Option<&mut Timings>,
 ) -> Result<Vec<P>, SurfaceError>
 where
     T: AsRef<[u8]>,
     P: Decode + Copy,
 {
+    let image_format = reinterpret_format(surface.image_format, options);
+
     let mut combined_surface_data = Vec::new();
     for layer in layers {
         for level in 0..surface.depth {
             for mipmap in mipmaps.clone() {
-                let data = surface
-                    .get(layer, level, mipmap)
-                    .ok_or(SurfaceError::MipmapDataOutOfBounds { layer, mipmap })?;
-
-                // The mipmap index is already validated by get above.
-                let width = mip_dimension(surface.width, mipmap);
-                let height = mip_dimension(surface.height, mipmap);
-
                 // TODO: Avoid additional copies?
-                let data = P::decode(width, height, surface.image_format, data)?;
+                let subresource = Subresource {
+                    layer,
+                    depth_level: level,
+                    mipmap,
+                };
+                let data = timed(timings.as_deref_mut(), subresource, || {
+                    decode_subresource(surface, layer, level, mipmap, image_format, options)
+                })?;
 
                 combined_surface_data.extend_from_slice(&data);
             }
@@ -97,6 +394,69 @@ where
     Ok(combined_surface_data)
 }
 
+#[cfg(feature = "timing")]
+fn timed<R>(timings: Option<&mut Timings>, subresource: Subresource, f: impl FnOnce() -> R) -> R {
+    match timings {
+        Some(timings) => {
+            let start = Instant::now();
+            let result = f();
+            timings.push((subresource, start.elapsed()));
+            result
+        }
+        None => f(),
+    }
+}
+
+#[cfg(not(feature = "timing"))]
+fn timed<R>(_: Option<&mut Timings>, _: Subresource, f: impl FnOnce() -> R) -> R {
+    f()
+}
+
+fn decode_subresource<T, P>(
+    surface: &Surface<T>,
+    layer: u32,
+    depth_level: u32,
+    mipmap: u32,
+    image_format: ImageFormat,
+    options: DecodeOptions,
+) -> Result<Vec<P>, SurfaceError>
+where
+    T: AsRef<[u8]>,
+    P: Decode,
+{
+    let data = surface
+        .get(layer, depth_level, mipmap)
+        .ok_or(SurfaceError::MipmapDataOutOfBounds { layer, mipmap })?;
+
+    // The mipmap index is already validated by get above.
+    let width = mip_dimension(surface.width, mipmap);
+    let height = mip_dimension(surface.height, mipmap);
+
+    decode_data(width, height, image_format, data, options)
+}
+
+fn decode_data<P: Decode>(
+    width: u32,
+    height: u32,
+    image_format: ImageFormat,
+    data: &[u8],
+    options: DecodeOptions,
+) -> Result<Vec<P>, SurfaceError> {
+    let mut data = P::decode(width, height, image_format, data, options)?;
+    if options.rg_blue != RgBlueDefault::Zero && is_rg_format(image_format) {
+        P::fill_rg_blue(&mut data, image_format, options.rg_blue);
+    }
+    Ok(data)
+}
+
+fn reinterpret_format(image_format: ImageFormat, options: DecodeOptions) -> ImageFormat {
+    match image_format {
+        ImageFormat::BC4RUnorm if options.interpret_bc4_as_signed => ImageFormat::BC4RSnorm,
+        ImageFormat::BC5RgUnorm if options.interpret_bc5_as_signed => ImageFormat::BC5RgSnorm,
+        _ => image_format,
+    }
+}
+
 // Decoding only works on 2D surfaces.
 trait Decode: Sized {
     fn decode(
@@ -104,7 +464,46 @@ trait Decode: Sized {
         height: u32,
         image_format: ImageFormat,
         data: &[u8],
+        options: DecodeOptions,
     ) -> Result<Vec<Self>, SurfaceError>;
+
+    // Replace the default blue channel of decoded RG data.
+    fn fill_rg_blue(rgba: &mut [Self], image_format: ImageFormat, rg_blue: RgBlueDefault);
+}
+
+fn is_rg_format(image_format: ImageFormat) -> bool {
+    matches!(
+        image_format,
+        ImageFormat::Rg8Unorm
+            | ImageFormat::Rg8Snorm
+            | ImageFormat::BC5RgUnorm
+            | ImageFormat::BC5RgSnorm
+    )
+}
+
+fn check_max_pixels(pixels: Option<u64>, options: DecodeOptions) -> Result<(), SurfaceError> {
+    // Overflow is reported separately when validating the surface.
+    match (pixels, options.max_pixels) {
+        (Some(pixels), Some(max_pixels)) if pixels > max_pixels => {
+            Err(SurfaceError::ExceedsMaxPixels { pixels, max_pixels })
+        }
+        _ => Ok(()),
+    }
+}
+
+fn reconstruct_z(x: f32, y: f32) -> f32 {
+    // Assume a unit length normal vector pointing outward.
+    (1.0 - x * x - y * y).max(0.0).sqrt()
+}
+
+fn unswizzle_xgxr(rgba: &mut [u8]) {
+    for pixel in rgba.chunks_exact_mut(4) {
+        // X is stored in alpha and Y is stored in green.
+        let x = pixel[3];
+        let y = pixel[1];
+        let z = reconstruct_z(x as f32 / 255.0 * 2.0 - 1.0, y as f32 / 255.0 * 2.0 - 1.0);
+        pixel.copy_from_slice(&[x, y, ((z * 0.5 + 0.5) * 255.0).round() as u8, 255u8]);
+    }
 }
 
 impl Decode for u8 {
@@ -113,18 +512,40 @@ impl Decode for u8 {
         height: u32,
         image_format: ImageFormat,
         data: &[u8],
+        options: DecodeOptions,
     ) -> Result<Vec<Self>, SurfaceError> {
         use ImageFormat as F;
         match image_format {
-            F::BC1RgbaUnorm | F::BC1RgbaUnormSrgb => decode_bcn::<Bc1, u8>(width, height, data),
-            F::BC2RgbaUnorm | F::BC2RgbaUnormSrgb => decode_bcn::<Bc2, u8>(width, height, data),
-            F::BC3RgbaUnorm | F::BC3RgbaUnormSrgb => decode_bcn::<Bc3, u8>(width, height, data),
-            F::BC4RUnorm => decode_bcn::<Bc4, u8>(width, height, data),
-            F::BC4RSnorm => decode_bcn::<Bc4S, u8>(width, height, data),
-            F::BC5RgUnorm => decode_bcn::<Bc5, u8>(width, height, data),
-            F::BC5RgSnorm => decode_bcn::<Bc5S, u8>(width, height, data),
-            F::BC6hRgbUfloat | F::BC6hRgbSfloat => decode_bcn::<Bc6, u8>(width, height, data),
-            F::BC7RgbaUnorm | F::BC7RgbaUnormSrgb => decode_bcn::<Bc7, u8>(width, height, data),
+            F::BC1RgbaUnorm | F::BC1RgbaUnormSrgb => {
+                let mut rgba = decode_bcn::<Bc1, u8>(width, height, data, options)?;
+                if !options.bc1_honor_transparency {
+                    // Only transparent black has an alpha other than 255.
+                    for pixel in rgba.chunks_exact_mut(4) {
+                        pixel[3] = 255;
+                    }
+                }
+                Ok(rgba)
+            }
+            F::BC2RgbaUnorm | F::BC2RgbaUnormSrgb => {
+                decode_bcn::<Bc2, u8>(width, height, data, options)
+            }
+            F::BC3RgbaUnorm | F::BC3RgbaUnormSrgb => {
+                decode_bcn::<Bc3, u8>(width, height, data, options)
+            }
+            F::BC3NormalXgxr => {
+                let mut rgba = decode_bcn::<Bc3, u8>(width, height, data, options)?;
+                unswizzle_xgxr(&mut rgba);
+                Ok(rgba)
+            }
+            F::BC4RUnorm => decode_bcn::<Bc4, u8>(width, height, data, options),
+            F::BC4RSnorm => decode_bcn::<Bc4S, u8>(width, height, data, options),
+            F::BC5RgUnorm => decode_bcn::<Bc5, u8>(width, height, data, options),
+            F::BC5RgSnorm => decode_bcn::<Bc5S, u8>(width, height, data, options),
+            F::BC6hRgbUfloat => decode_bcn::<Bc6, u8>(width, height, data, options),
+            F::BC6hRgbSfloat => decode_bcn::<Bc6S, u8>(width, height, data, options),
+            F::BC7RgbaUnorm | F::BC7RgbaUnormSrgb => {
+                decode_bcn::<Bc7, u8>(width, height, data, options)
+            }
             F::R8Unorm => decode_rgba::<R8, u8>(width, height, data),
             F::R8Snorm => decode_rgba::<R8Snorm, u8>(width, height, data),
             F::Rg8Unorm => decode_rgba::<Rg8, u8>(width, height, data),
@@ -132,11 +553,36 @@ impl Decode for u8 {
             F::Rgba8Unorm | F::Rgba8UnormSrgb => decode_rgba::<Rgba8, u8>(width, height, data),
             F::Rgba16Float => decode_rgba::<Rgbaf16, u8>(width, height, data),
             F::Rgba32Float => decode_rgba::<Rgbaf32, u8>(width, height, data),
+            F::Rgb16Float => decode_rgba::<Rgbf16, u8>(width, height, data),
+            F::Rgb9e5Ufloat => decode_rgba::<Rgb9e5, u8>(width, height, data),
+            F::Rg11b10Float => decode_rgba::<Rg11b10, u8>(width, height, data),
             F::Bgra8Unorm | F::Bgra8UnormSrgb => decode_rgba::<Bgra8, u8>(width, height, data),
             F::Bgra4Unorm => decode_rgba::<Bgra4, u8>(width, height, data),
+            F::Bgrx8Unorm => decode_rgba::<Bgrx8, u8>(width, height, data),
+            F::Rgbx8Unorm => decode_rgba::<Rgbx8, u8>(width, height, data),
+            F::B5g6r5Unorm => decode_rgba::<B5g6r5, u8>(width, height, data),
+            F::L8Unorm => decode_rgba::<L8, u8>(width, height, data),
+            F::A8Unorm => decode_rgba::<A8, u8>(width, height, data),
+            F::La8Unorm => decode_rgba::<La8, u8>(width, height, data),
+            F::Rgb8Unorm => decode_rgba::<Rgb8, u8>(width, height, data),
             F::Bgr8Unorm => decode_rgba::<Bgr8, u8>(width, height, data),
         }
     }
+
+    fn fill_rg_blue(rgba: &mut [Self], _image_format: ImageFormat, rg_blue: RgBlueDefault) {
+        for pixel in rgba.chunks_exact_mut(4) {
+            pixel[2] = match rg_blue {
+                RgBlueDefault::Zero => pixel[2],
+                RgBlueDefault::Half => 128,
+                RgBlueDefault::ReconstructZ => {
+                    // Snorm formats are already remapped to unorm for RGBA8.
+                    let x = pixel[0] as f32 / 255.0 * 2.0 - 1.0;
+                    let y = pixel[1] as f32 / 255.0 * 2.0 - 1.0;
+                    ((reconstruct_z(x, y) * 0.5 + 0.5) * 255.0).round() as u8
+                }
+            };
+        }
+    }
 }
 
 impl Decode for f32 {
@@ -145,31 +591,190 @@ impl Decode for f32 {
         height: u32,
         image_format: ImageFormat,
         data: &[u8],
+        options: DecodeOptions,
     ) -> Result<Vec<Self>, SurfaceError> {
         use ImageFormat as F;
         match image_format {
             F::R8Snorm => decode_rgba::<R8Snorm, f32>(width, height, data),
             F::Rg8Snorm => decode_rgba::<Rg8Snorm, f32>(width, height, data),
-            F::BC4RSnorm => decode_bcn::<Bc4S, f32>(width, height, data),
-            F::BC5RgSnorm => decode_bcn::<Bc5S, f32>(width, height, data),
-            F::BC6hRgbUfloat | F::BC6hRgbSfloat => decode_bcn::<Bc6, f32>(width, height, data),
+            F::BC4RSnorm => decode_bcn::<Bc4S, f32>(width, height, data, options),
+            F::BC5RgSnorm => decode_bcn::<Bc5S, f32>(width, height, data, options),
+            F::BC6hRgbUfloat => decode_bcn::<Bc6, f32>(width, height, data, options),
+            F::BC6hRgbSfloat => decode_bcn::<Bc6S, f32>(width, height, data, options),
             F::Rgba16Float => decode_rgba::<Rgbaf16, f32>(width, height, data),
             F::Rgba32Float => decode_rgba::<Rgbaf32, f32>(width, height, data),
-            _ => {
+            F::Rgb16Float => decode_rgba::<Rgbf16, f32>(width, height, data),
+            F::Rgb9e5Ufloat => decode_rgba::<Rgb9e5, f32>(width, height, data),
+            F::Rg11b10Float => decode_rgba::<Rg11b10, f32>(width, height, data),
+            F::Rgba8UnormSrgb
+            | F::Bgra8UnormSrgb
+            | F::BC1RgbaUnormSrgb
+            | F::BC2RgbaUnormSrgb
+            | F::BC3RgbaUnormSrgb
+            | F::BC7RgbaUnormSrgb => {
+                // Convert the color channels to linear instead of only normalizing.
+                // Alpha is always stored as linear.
+                let rgba8 = u8::decode(width, height, image_format, data, options)?;
+                let table: [f32; 256] = std::array::from_fn(|i| srgb_to_linear(i as f32 / 255.0));
+                Ok(rgba8
+                    .chunks_exact(4)
+                    .flat_map(|p| {
+                        [
+                            table[p[0] as usize],
+                            table[p[1] as usize],
+                            table[p[2] as usize],
+                            p[3] as f32 / 255.0,
+                        ]
+                    })
+                    .collect())
+            }
+            // List formats explicitly to catch new formats at compile time.
+            F::R8Unorm
+            | F::Rg8Unorm
+            | F::Rgba8Unorm
+            | F::Rgb8Unorm
+            | F::Bgr8Unorm
+            | F::Bgra8Unorm
+            | F::Bgra4Unorm
+            | F::Bgrx8Unorm
+            | F::Rgbx8Unorm
+            | F::B5g6r5Unorm
+            | F::L8Unorm
+            | F::A8Unorm
+            | F::La8Unorm
+            | F::BC1RgbaUnorm
+            | F::BC2RgbaUnorm
+            | F::BC3RgbaUnorm
+            | F::BC3NormalXgxr
+            | F::BC4RUnorm
+            | F::BC5RgUnorm
+            | F::BC7RgbaUnorm => {
                 // Use existing decoding for formats that don't store floating point data.
-                let rgba8 = u8::decode(width, height, image_format, data)?;
+                let rgba8 = u8::decode(width, height, image_format, data, options)?;
                 Ok(rgba8.into_iter().map(|u| u as f32 / 255.0).collect())
             }
         }
     }
+
+    fn fill_rg_blue(rgba: &mut [Self], image_format: ImageFormat, rg_blue: RgBlueDefault) {
+        // Snorm formats decode to the range -1.0 to 1.0 instead of 0.0 to 1.0.
+        let is_snorm = matches!(
+            image_format,
+            ImageFormat::Rg8Snorm | ImageFormat::BC5RgSnorm
+        );
+        for pixel in rgba.chunks_exact_mut(4) {
+            pixel[2] = match rg_blue {
+                RgBlueDefault::Zero => pixel[2],
+                RgBlueDefault::Half => 0.5,
+                RgBlueDefault::ReconstructZ => {
+                    if is_snorm {
+                        reconstruct_z(pixel[0], pixel[1])
+                    } else {
+                        let z = reconstruct_z(pixel[0] * 2.0 - 1.0, pixel[1] * 2.0 - 1.0);
+                        z * 0.5 + 0.5
+                    }
+                }
+            };
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    use crate::InvalidBlockHandling;
+
     use strum::IntoEnumIterator;
 
+    fn crop_rgba8(
+        image: &SurfaceRgba8<Vec<u8>>,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Vec<u8> {
+        (y..y + height)
+            .flat_map(|row| {
+                let start = ((row * image.width + x) * 4) as usize;
+                image.data[start..start + width as usize * 4].to_vec()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn decode_region_rgba8_bc1_unaligned() {
+        // Use different bytes for each block to catch incorrect offsets.
+        let surface = Surface {
+            width: 12,
+            height: 8,
+            depth: 1,
+            layers: 2,
+            mipmaps: 2,
+            image_format: ImageFormat::BC1RgbaUnorm,
+            data: (0..2 * (6 + 2) * 8)
+                .map(|i| (i * 37 % 256) as u8)
+                .collect::<Vec<_>>(),
+        };
+
+        let region = surface.decode_region_rgba8(1, 0, 3, 2, 6, 5).unwrap();
+        assert_eq!((6, 5, 1), (region.width, region.height, region.layers));
+
+        let mipmap = surface.decode_mipmap_rgba8(1, 0).unwrap();
+        assert_eq!(crop_rgba8(&mipmap, 3, 2, 6, 5), region.data);
+
+        let region = surface.decode_region_rgba8(0, 1, 1, 0, 5, 4).unwrap();
+        let mipmap = surface.decode_mipmap_rgba8(0, 1).unwrap();
+        assert_eq!(crop_rgba8(&mipmap, 1, 0, 5, 4), region.data);
+    }
+
+    #[test]
+    fn decode_region_rgba8_uncompressed() {
+        let surface = Surface {
+            width: 5,
+            height: 3,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::Bgra8Unorm,
+            data: (0..5 * 3 * 4).map(|i| i as u8).collect::<Vec<_>>(),
+        };
+        let region = surface.decode_region_rgba8(0, 0, 4, 1, 1, 2).unwrap();
+        assert_eq!(vec![38, 37, 36, 39, 58, 57, 56, 59], region.data);
+    }
+
+    #[test]
+    fn decode_region_rgba8_out_of_bounds() {
+        let surface = Surface {
+            width: 8,
+            height: 8,
+            depth: 1,
+            layers: 1,
+            mipmaps: 2,
+            image_format: ImageFormat::BC7RgbaUnorm,
+            data: vec![0u8; 4 * 16 + 16],
+        };
+        assert_eq!(
+            Err(SurfaceError::RegionOutOfBounds {
+                x: 2,
+                y: 0,
+                width: 3,
+                height: 4,
+                mip_width: 4,
+                mip_height: 4
+            }),
+            surface.decode_region_rgba8(0, 1, 2, 0, 3, 4)
+        );
+        assert_eq!(
+            Err(SurfaceError::ZeroSizedSurface {
+                width: 0,
+                height: 1,
+                depth: 1
+            }),
+            surface.decode_region_rgba8(0, 0, 0, 0, 0, 1)
+        );
+    }
+
     #[test]
     fn decode_surface_zero_size() {
         let result = Surface {
@@ -216,6 +821,123 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn decode_surface_exceeds_max_pixels() {
+        let surface = Surface {
+            width: 50000,
+            height: 50000,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::BC7RgbaUnorm,
+            data: &[0u8; 0],
+        };
+        let options = DecodeOptions {
+            max_pixels: Some(1_000_000_000),
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            surface.decode_rgba8_with_options(options),
+            Err(SurfaceError::ExceedsMaxPixels {
+                pixels: 2_500_000_000,
+                max_pixels: 1_000_000_000
+            })
+        ));
+        assert!(matches!(
+            surface.decode_rgbaf32_with_options(options),
+            Err(SurfaceError::ExceedsMaxPixels {
+                pixels: 2_500_000_000,
+                max_pixels: 1_000_000_000
+            })
+        ));
+    }
+
+    #[test]
+    fn decode_mipmap_rgba8_exceeds_max_pixels() {
+        let surface = Surface {
+            width: 70000,
+            height: 70000,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::BC7RgbaUnorm,
+            data: &[0u8; 0],
+        };
+
+        assert!(matches!(
+            surface.decode_mipmap_rgba8(0, 0),
+            Err(SurfaceError::ExceedsMaxPixels {
+                pixels: 4_900_000_000,
+                max_pixels: 4_294_967_296
+            })
+        ));
+        assert!(matches!(
+            surface.decode_region_rgba8(0, 0, 0, 0, 70000, 70000),
+            Err(SurfaceError::ExceedsMaxPixels {
+                pixels: 4_900_000_000,
+                max_pixels: 4_294_967_296
+            })
+        ));
+
+        // The limit only applies to the region instead of the entire mipmap.
+        assert!(matches!(
+            surface.decode_region_rgba8(0, 0, 0, 0, 4, 4),
+            Err(SurfaceError::NotEnoughData { .. })
+        ));
+    }
+
+    #[test]
+    fn decode_surface_max_pixels_mipmaps_layers() {
+        // Each layer has 16 + 4 + 1 pixels for all mipmaps.
+        let surface = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 2,
+            mipmaps: 3,
+            image_format: ImageFormat::Rgba8Unorm,
+            data: &[0u8; 2 * 21 * 4],
+        };
+        let options = DecodeOptions {
+            max_pixels: Some(21),
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            surface.decode_rgba8_with_options(options),
+            Err(SurfaceError::ExceedsMaxPixels {
+                pixels: 42,
+                max_pixels: 21
+            })
+        ));
+        assert!(surface
+            .decode_rgba8_inner(1..2, 0..3, options, None)
+            .is_ok());
+        assert!(surface
+            .decode_rgbaf32_inner(0..2, 1..3, options, None)
+            .is_ok());
+    }
+
+    #[test]
+    fn decode_surface_no_max_pixels() {
+        // The data is still validated without a limit.
+        let result = Surface {
+            width: 50000,
+            height: 50000,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::BC7RgbaUnorm,
+            data: &[0u8; 0],
+        }
+        .decode_rgba8_with_options(DecodeOptions {
+            max_pixels: None,
+            ..Default::default()
+        });
+        assert!(matches!(result, Err(SurfaceError::NotEnoughData { .. })));
+    }
+
     #[test]
     fn decode_surface_too_many_mipmaps() {
         let result = Surface {
@@ -381,4 +1103,465 @@ mod tests {
             surface.decode_rgbaf32().unwrap();
         }
     }
+
+    #[test]
+    fn decode_unorm_f32_matches_u8() {
+        // Formats without float or snorm data should decode to RGBA8 first.
+        for image_format in ImageFormat::iter().filter(|f| {
+            !f.is_srgb()
+                && !matches!(
+                    f,
+                    ImageFormat::R8Snorm
+                        | ImageFormat::Rg8Snorm
+                        | ImageFormat::BC4RSnorm
+                        | ImageFormat::BC5RgSnorm
+                        | ImageFormat::BC6hRgbUfloat
+                        | ImageFormat::BC6hRgbSfloat
+                        | ImageFormat::Rgba16Float
+                        | ImageFormat::Rgba32Float
+                        | ImageFormat::Rgb16Float
+                        | ImageFormat::Rgb9e5Ufloat
+                        | ImageFormat::Rg11b10Float
+                )
+        }) {
+            let data: Vec<_> = (0..4 * 4 * image_format.block_size_in_bytes())
+                .map(|i| (i * 37 + 1) as u8)
+                .collect();
+            let surface = Surface {
+                width: 4,
+                height: 4,
+                depth: 1,
+                layers: 1,
+                mipmaps: 1,
+                image_format,
+                data: data.as_slice(),
+            };
+            let rgba8 = surface.decode_rgba8().unwrap();
+            let rgbaf32 = surface.decode_rgbaf32().unwrap();
+            assert_eq!(
+                rgba8
+                    .data
+                    .iter()
+                    .map(|u| *u as f32 / 255.0)
+                    .collect::<Vec<_>>(),
+                rgbaf32.data,
+                "{image_format:?}"
+            );
+        }
+    }
+
+    fn decode_bc1_transparent(bc1_honor_transparency: bool) -> Vec<u8> {
+        // c0 <= c1 uses the 3 color mode, and index 3 is transparent black.
+        Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::BC1RgbaUnorm,
+            data: &[0u8, 0, 255, 255, 255, 255, 255, 255],
+        }
+        .decode_rgba8_with_options(DecodeOptions {
+            bc1_honor_transparency,
+            ..Default::default()
+        })
+        .unwrap()
+        .data
+    }
+
+    #[test]
+    fn decode_bc1_honor_transparency() {
+        assert_eq!([0, 0, 0, 0].repeat(16), decode_bc1_transparent(true));
+    }
+
+    #[test]
+    fn decode_bc1_ignore_transparency() {
+        assert_eq!([0, 0, 0, 255].repeat(16), decode_bc1_transparent(false));
+    }
+
+    fn decode_bc4_red(options: DecodeOptions) -> f32 {
+        // Both endpoints use the byte 127 for every pixel.
+        Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::BC4RUnorm,
+            data: &[127u8, 127, 0, 0, 0, 0, 0, 0],
+        }
+        .decode_rgbaf32_with_options(options)
+        .unwrap()
+        .data[0]
+    }
+
+    #[test]
+    fn decode_bc4_interpret_as_signed() {
+        assert_eq!(127.0 / 255.0, decode_bc4_red(DecodeOptions::default()));
+        assert_eq!(
+            1.0,
+            decode_bc4_red(DecodeOptions {
+                interpret_bc4_as_signed: true,
+                ..Default::default()
+            })
+        );
+        // BC5 options don't affect BC4.
+        assert_eq!(
+            127.0 / 255.0,
+            decode_bc4_red(DecodeOptions {
+                interpret_bc5_as_signed: true,
+                ..Default::default()
+            })
+        );
+    }
+
+    #[test]
+    fn decode_bc5_interpret_as_signed() {
+        // The red and green endpoints use the bytes 255 and 127 for every pixel.
+        let surface = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::BC5RgUnorm,
+            data: &[255u8, 255, 0, 0, 0, 0, 0, 0, 127, 127, 0, 0, 0, 0, 0, 0],
+        };
+        let unorm = surface.decode_rgbaf32().unwrap();
+        assert_eq!(&[1.0, 127.0 / 255.0], &unorm.data[..2]);
+
+        let snorm = surface
+            .decode_rgbaf32_with_options(DecodeOptions {
+                interpret_bc5_as_signed: true,
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(&[-1.0 / 127.0, 1.0], &snorm.data[..2]);
+    }
+
+    #[test]
+    fn decode_bc7_invalid_block_zero_fill() {
+        // The BC7 mode is the lowest set bit, so a block of zeros uses the reserved mode 8.
+        let surface = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::BC7RgbaUnorm,
+            data: &[0u8; 16],
+        };
+        let rgba8 = surface
+            .decode_rgba8_with_options(DecodeOptions {
+                on_invalid_block: InvalidBlockHandling::ZeroFill,
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(vec![0u8; 4 * 4 * 4], rgba8.data);
+        assert_eq!(Ok(rgba8), surface.decode_rgba8());
+    }
+
+    #[test]
+    fn decode_bc7_invalid_block_error() {
+        // Only the second block is invalid.
+        let data = [[0xFFu8; 16], [0u8; 16]].concat();
+        let surface = Surface {
+            width: 8,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::BC7RgbaUnorm,
+            data: data.as_slice(),
+        };
+        let options = DecodeOptions {
+            on_invalid_block: InvalidBlockHandling::Error,
+            ..Default::default()
+        };
+        assert_eq!(
+            Err(SurfaceError::InvalidCompressedBlock { x: 4, y: 0 }),
+            surface.decode_rgba8_with_options(options)
+        );
+        assert_eq!(
+            Err(SurfaceError::InvalidCompressedBlock { x: 4, y: 0 }),
+            surface.decode_rgbaf32_with_options(options)
+        );
+    }
+
+    fn decode_rg8_blue(rg_blue: RgBlueDefault) -> Vec<u8> {
+        // Use a normal pointing straight out and a normal pointing along X.
+        Surface {
+            width: 2,
+            height: 1,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::Rg8Unorm,
+            data: &[128u8, 128, 255, 128],
+        }
+        .decode_rgba8_with_options(DecodeOptions {
+            rg_blue,
+            ..Default::default()
+        })
+        .unwrap()
+        .data
+    }
+
+    #[test]
+    fn decode_bc3_normal_xgxr() {
+        // Alpha stores X and the 565 color stores Y in green.
+        let rgba8 = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::BC3NormalXgxr,
+            data: &[128u8, 128, 0, 0, 0, 0, 0, 0, 0, 4, 0, 4, 0, 0, 0, 0],
+        }
+        .decode_rgba8()
+        .unwrap();
+        assert_eq!([128, 130, 255, 255].repeat(16), rgba8.data);
+    }
+
+    #[test]
+    fn decode_rg8_blue_zero() {
+        assert_eq!(
+            vec![128, 128, 0, 255, 255, 128, 0, 255],
+            decode_rg8_blue(RgBlueDefault::Zero)
+        );
+    }
+
+    #[test]
+    fn decode_rg8_blue_half() {
+        assert_eq!(
+            vec![128, 128, 128, 255, 255, 128, 128, 255],
+            decode_rg8_blue(RgBlueDefault::Half)
+        );
+    }
+
+    #[test]
+    fn decode_rg8_blue_reconstruct_z() {
+        assert_eq!(
+            vec![128, 128, 255, 255, 255, 128, 128, 255],
+            decode_rg8_blue(RgBlueDefault::ReconstructZ)
+        );
+    }
+
+    #[test]
+    fn decode_bc5_blue_reconstruct_z_clamped() {
+        // X and Y of 1.0 are outside the unit circle, so Z is clamped to 0.0.
+        let surface = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::BC5RgUnorm,
+            data: &[255u8, 255, 0, 0, 0, 0, 0, 0, 255, 255, 0, 0, 0, 0, 0, 0],
+        };
+        let options = DecodeOptions {
+            rg_blue: RgBlueDefault::ReconstructZ,
+            ..Default::default()
+        };
+        assert_eq!(
+            [255u8, 255, 128, 255].repeat(4 * 4),
+            surface.decode_rgba8_with_options(options).unwrap().data
+        );
+        assert_eq!(
+            [1.0f32, 1.0, 0.5, 1.0].repeat(4 * 4),
+            surface.decode_rgbaf32_with_options(options).unwrap().data
+        );
+    }
+
+    #[test]
+    fn decode_rg8_snorm_blue_reconstruct_z_f32() {
+        // Snorm values are already in the range -1.0 to 1.0.
+        let rgbaf32 = Surface {
+            width: 1,
+            height: 1,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::Rg8Snorm,
+            data: &[0u8, 0],
+        }
+        .decode_rgbaf32_with_options(DecodeOptions {
+            rg_blue: RgBlueDefault::ReconstructZ,
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(vec![0.0, 0.0, 1.0, 1.0], rgbaf32.data);
+    }
+
+    #[test]
+    fn decode_rgba8_ignores_rg_blue() {
+        let rgba8 = Surface {
+            width: 1,
+            height: 1,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::Rgba8Unorm,
+            data: &[1u8, 2, 3, 4],
+        }
+        .decode_rgba8_with_options(DecodeOptions {
+            rg_blue: RgBlueDefault::Half,
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(vec![1, 2, 3, 4], rgba8.data);
+    }
+
+    #[cfg(feature = "timing")]
+    #[test]
+    fn decode_rgba8_with_timing_subresources() {
+        let (rgba8, timings) = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 2,
+            mipmaps: 3,
+            image_format: ImageFormat::BC1RgbaUnorm,
+            data: &[0u8; 8 * 3 * 2],
+        }
+        .decode_rgba8_with_timing(DecodeOptions::default())
+        .unwrap();
+
+        assert_eq!((4 * 4 + 2 * 2 + 1) * 4 * 2, rgba8.data.len());
+        // Durations depend on the machine, so only check the subresources.
+        assert_eq!(
+            vec![(0, 0), (0, 1), (0, 2), (1, 0), (1, 1), (1, 2)],
+            timings
+                .iter()
+                .map(|(s, _)| {
+                    assert_eq!(0, s.depth_level);
+                    (s.layer, s.mipmap)
+                })
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn decode_surface_check_layer_count() {
+        // Cube maps have 6 layers of data.
+        let surface = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::BC7RgbaUnorm,
+            data: &[1u8; 16 * 6],
+        };
+        let options = DecodeOptions {
+            check_layer_count: true,
+            ..Default::default()
+        };
+        assert!(matches!(
+            surface.decode_rgba8_with_options(options),
+            Err(SurfaceError::UnexpectedLayerCount {
+                layers: 1,
+                data_layers: 6
+            })
+        ));
+        assert!(matches!(
+            surface.decode_rgbaf32_with_options(options),
+            Err(SurfaceError::UnexpectedLayerCount {
+                layers: 1,
+                data_layers: 6
+            })
+        ));
+
+        // The check is opt-in.
+        assert!(surface.decode_rgba8().is_ok());
+        assert!(Surface {
+            layers: 6,
+            ..surface
+        }
+        .decode_rgba8_with_options(options)
+        .is_ok());
+    }
+
+    #[test]
+    fn decode_bc1_srgb_f32_linear() {
+        // A solid gray block with color 0x8410 decodes to RGB8 (132, 130, 132).
+        let surface = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::BC1RgbaUnormSrgb,
+            data: &[0x10, 0x84, 0x10, 0x84, 0, 0, 0, 0],
+        };
+        let rgbaf32 = surface.decode_rgbaf32().unwrap();
+        let expected = [0.23074, 0.22323, 0.23074, 1.0];
+        for (actual, expected) in rgbaf32.data[..4].iter().zip(expected) {
+            assert!((actual - expected).abs() < 1e-5, "{actual} != {expected}");
+        }
+
+        // The unorm format is only normalized.
+        let rgbaf32 = Surface {
+            image_format: ImageFormat::BC1RgbaUnorm,
+            ..surface
+        }
+        .decode_rgbaf32()
+        .unwrap();
+        assert_eq!(
+            &[132.0 / 255.0, 130.0 / 255.0, 132.0 / 255.0, 1.0],
+            &rgbaf32.data[..4]
+        );
+    }
+
+    #[test]
+    fn decode_base_matches_mipmap_range() {
+        let data: Vec<_> = (0..(16 + 4 + 1) * 4 * 2).map(|i| i as u8).collect();
+        let surface = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 2,
+            mipmaps: 3,
+            image_format: ImageFormat::Rgba8Unorm,
+            data: data.as_slice(),
+        };
+
+        let rgba8 = surface.decode_base_rgba8().unwrap();
+        assert_eq!((2, 1), (rgba8.layers, rgba8.mipmaps));
+        assert_eq!(
+            surface.decode_layers_mipmaps_rgba8(0..2, 0..1).unwrap(),
+            rgba8
+        );
+        assert_eq!(
+            surface.decode_layers_mipmaps_rgbaf32(0..2, 0..1).unwrap(),
+            surface.decode_base_rgbaf32().unwrap()
+        );
+    }
+
+    #[test]
+    fn decode_mipmap_rgba8_layer() {
+        let data: Vec<_> = (0..(16 + 4 + 1) * 4 * 3).map(|i| i as u8).collect();
+        let surface = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 3,
+            mipmaps: 3,
+            image_format: ImageFormat::Rgba8Unorm,
+            data: data.as_slice(),
+        };
+
+        assert_eq!(
+            surface.decode_layers_mipmaps_rgba8(1..2, 1..2).unwrap(),
+            surface.decode_mipmap_rgba8(1, 1).unwrap()
+        );
+        assert_eq!(
+            Err(SurfaceError::MipmapDataOutOfBounds {
+                layer: 3,
+                mipmap: 0
+            }),
+            surface.decode_mipmap_rgba8(3, 0)
+        );
+    }
 }
@@ -1,14 +1,21 @@
 use std::borrow::Cow;
+use std::io::Write;
 
-use crate::bcn::{encode_bcn, Bc1, Bc2, Bc3, Bc4, Bc5, Bc6, Bc7};
+use thiserror::Error;
+
+use crate::bcn::{encode_bcn, Bc1, Bc2, Bc3, Bc4, Bc5, Bc6, Bc7, Bc7Opaque};
 use crate::rgba::{
-    encode_rgba, Bgr8, Bgra4, Bgra8, R8Snorm, Rg8, Rg8Snorm, Rgba8, Rgbaf16, Rgbaf32, R8,
+    dither_rgba8, encode_rgba, B5g6r5, Bgr8, Bgra4, Bgra8, Bgrx8, La8, R8Snorm, Rg11b10, Rg8,
+    Rg8Snorm, Rgb8, Rgb9e5, Rgbaf16, Rgbaf32, Rgbf16, Rgbx8, A8, L8, R8,
+};
+use crate::{
+    downsample_rgba, error::SurfaceError, max_mipmap_count, mip_dimension, resample_rgba, round_up,
+    DitherMode, ImageFormat, MipmapFilter, Mipmaps, Quality, Surface, SurfaceRgba8,
 };
 use crate::{
-    downsample_rgba, error::SurfaceError, max_mipmap_count, mip_dimension, round_up, ImageFormat,
-    Mipmaps, Quality, Surface, SurfaceRgba8,
+    float_to_snorm, float_to_unorm8, linear_to_srgb, EncodeOptions, Pixel, SurfaceRgba32Float,
 };
-use crate::{float_to_snorm, Pixel, SurfaceRgba32Float};
+use half::f16;
 
 impl<T: AsRef<[u8]>> SurfaceRgba8<T> {
     /// Encode an RGBA8 surface to the given `format`.
@@ -19,9 +26,160 @@ impl<T: AsRef<[u8]>> SurfaceRgba8<T> {
         format: ImageFormat,
         quality: Quality,
         mipmaps: Mipmaps,
+    ) -> Result<Surface<Vec<u8>>, SurfaceError> {
+        self.encode_with_options(format, quality, mipmaps, EncodeOptions::default())
+    }
+
+    /// Encode an RGBA8 surface to the given `format` with additional `options`.
+    ///
+    /// The number of mipmaps generated depends on the `mipmaps` parameter.
+    pub fn encode_with_options(
+        &self,
+        format: ImageFormat,
+        quality: Quality,
+        mipmaps: Mipmaps,
+        options: EncodeOptions,
     ) -> Result<Surface<Vec<u8>>, SurfaceError> {
         self.validate()?;
-        encode_surface(self, format, quality, mipmaps)
+
+        let remapped = remap_channels(self.data.as_ref(), options, 255u8)?;
+        let surface = SurfaceRgba8 {
+            width: self.width,
+            height: self.height,
+            depth: self.depth,
+            layers: self.layers,
+            mipmaps: self.mipmaps,
+            data: remapped.as_deref().unwrap_or(self.data.as_ref()),
+        };
+
+        let num_mipmaps = encoded_mipmap_count(&surface, mipmaps);
+        if matches!(
+            format,
+            ImageFormat::Rgba8Unorm | ImageFormat::Rgba8UnormSrgb
+        ) && surface.mipmaps == 1
+            && num_mipmaps == 1
+        {
+            // The data is already in the correct layout.
+            return surface.copy_rgba8(format);
+        }
+
+        encode_surface(&surface, format, quality, mipmaps, options)
+    }
+
+    fn copy_rgba8(&self, format: ImageFormat) -> Result<Surface<Vec<u8>>, SurfaceError> {
+        let expected = (self.width as usize)
+            .checked_mul(self.height as usize)
+            .and_then(|s| s.checked_mul(self.depth as usize))
+            .and_then(|s| s.checked_mul(self.layers as usize))
+            .and_then(|s| s.checked_mul(4))
+            .ok_or(SurfaceError::PixelCountWouldOverflow {
+                width: self.width,
+                height: self.height,
+                depth: self.depth,
+            })?;
+        let data = self
+            .data
+            .as_ref()
+            .get(..expected)
+            .ok_or(SurfaceError::NotEnoughData {
+                expected,
+                actual: self.data.as_ref().len(),
+            })?;
+
+        Ok(Surface {
+            width: self.width,
+            height: self.height,
+            depth: self.depth,
+            layers: self.layers,
+            mipmaps: 1,
+            image_format: format,
+            data: data.to_vec(),
+        })
+    }
+}
+
+impl<T: AsRef<[u8]>> Surface<T> {
+    /// Decode the surface and encode the result to the given `format`.
+    ///
+    /// [Mipmaps::FromSurface] decodes and re-encodes each existing mipmap
+    /// to preserve the number of mipmaps instead of generating new mipmaps.
+    /// Data is decoded to RGBAF32 if either format stores floating point data
+    /// and RGBA8 otherwise.
+    pub fn transcode(
+        &self,
+        format: ImageFormat,
+        quality: Quality,
+        mipmaps: Mipmaps,
+    ) -> Result<Surface<Vec<u8>>, SurfaceError> {
+        if self.image_format.is_float() || format.is_float() {
+            self.decode_rgbaf32()?.encode(format, quality, mipmaps)
+        } else {
+            self.decode_rgba8()?.encode(format, quality, mipmaps)
+        }
+    }
+
+    /// Decode the base mip level and encode it to the same format
+    /// with a full chain of [Mipmaps::GeneratedAutomatic] mipmaps.
+    ///
+    /// Any existing mipmaps are replaced by the generated mipmaps.
+    pub fn with_generated_mipmaps(
+        &self,
+        quality: Quality,
+    ) -> Result<Surface<Vec<u8>>, SurfaceError> {
+        let mipmaps = Mipmaps::GeneratedAutomatic;
+        if self.image_format.is_float() {
+            self.decode_base_rgbaf32()?
+                .encode(self.image_format, quality, mipmaps)
+        } else {
+            self.decode_base_rgba8()?
+                .encode(self.image_format, quality, mipmaps)
+        }
+    }
+}
+
+/// Errors that can occur while encoding a surface to a writer.
+#[derive(Debug, Error)]
+pub enum EncodeStreamingError {
+    #[error("error writing encoded data: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("error compressing surface: {0}")]
+    CompressSurface(#[from] SurfaceError),
+}
+
+impl<T: AsRef<[u8]>> SurfaceRgba8<T> {
+    /// Encode an RGBA8 surface to the given `format` and write the encoded data to `writer`.
+    ///
+    /// This writes the same bytes as the data for [SurfaceRgba8::encode]
+    /// and returns the number of mipmaps for each layer.
+    /// Each mipmap is written as soon as it is encoded instead of storing the combined encoded data.
+    /// Only the RGBA8 data for the current mipmap is kept in memory for generating the next mipmap.
+    pub fn encode_streaming<W: Write>(
+        &self,
+        writer: &mut W,
+        format: ImageFormat,
+        quality: Quality,
+        mipmaps: Mipmaps,
+    ) -> Result<u32, EncodeStreamingError> {
+        self.validate()?;
+
+        let num_mipmaps = encoded_mipmap_count(self, mipmaps);
+
+        for layer in 0..self.layers {
+            encode_mipmaps_rgba(
+                &mut |encoded: Vec<u8>| {
+                    writer.write_all(&encoded).map_err(EncodeStreamingError::Io)
+                },
+                self,
+                format,
+                quality,
+                EncodeOptions::default(),
+                mipmaps,
+                layer,
+            )?;
+        }
+
+        Ok(num_mipmaps)
     }
 }
 
@@ -30,14 +188,101 @@ impl<T: AsRef<[f32]>> SurfaceRgba32Float<T> {
     /// Encode an RGBAF32 surface to the given `format`.
     ///
     /// The number of mipmaps generated depends on the `mipmaps` parameter.
+    /// Color channels are assumed to be linear and converted to sRGB for sRGB formats
+    /// like [ImageFormat::BC7RgbaUnormSrgb].
     pub fn encode(
         &self,
         format: ImageFormat,
         quality: Quality,
         mipmaps: Mipmaps,
+    ) -> Result<Surface<Vec<u8>>, SurfaceError> {
+        self.encode_with_options(format, quality, mipmaps, EncodeOptions::default())
+    }
+
+    /// Encode an RGBAF32 surface to the given `format` with additional `options`.
+    ///
+    /// The number of mipmaps generated depends on the `mipmaps` parameter.
+    /// Color channels are assumed to be linear and converted to sRGB for sRGB formats
+    /// like [ImageFormat::BC7RgbaUnormSrgb].
+    pub fn encode_with_options(
+        &self,
+        format: ImageFormat,
+        quality: Quality,
+        mipmaps: Mipmaps,
+        options: EncodeOptions,
     ) -> Result<Surface<Vec<u8>>, SurfaceError> {
         self.validate()?;
-        encode_surface(self, format, quality, mipmaps)
+
+        let remapped = remap_channels(self.data.as_ref(), options, 1.0f32)?;
+        let surface = SurfaceRgba32Float {
+            width: self.width,
+            height: self.height,
+            depth: self.depth,
+            layers: self.layers,
+            mipmaps: self.mipmaps,
+            data: remapped.as_deref().unwrap_or(self.data.as_ref()),
+        };
+
+        encode_surface(&surface, format, quality, mipmaps, options)
+    }
+}
+
+// Returns None if the data doesn't need any changes.
+fn remap_channels<P: Copy>(
+    data: &[P],
+    options: EncodeOptions,
+    opaque: P,
+) -> Result<Option<Vec<P>>, SurfaceError> {
+    let channels = options.channels;
+    if let Some(index) = channels.iter().copied().find(|c| *c >= 4) {
+        return Err(SurfaceError::InvalidChannelIndex { index });
+    }
+    if channels == EncodeOptions::default().channels && !options.force_opaque {
+        return Ok(None);
+    }
+
+    Ok(Some(
+        data.chunks_exact(4)
+            .flat_map(|pixel| {
+                let mut pixel = channels.map(|c| pixel[c]);
+                if options.force_opaque {
+                    pixel[3] = opaque;
+                }
+                pixel
+            })
+            .collect(),
+    ))
+}
+
+/// Encode multiple RGBA8 surfaces to the same `format` with shared settings.
+///
+/// Surfaces are encoded in parallel if the `"rayon"` feature is enabled.
+/// This is often more efficient than parallelizing within each surface for many small surfaces.
+/// The results are in the same order as `surfaces`.
+pub fn encode_batch<T>(
+    surfaces: &[SurfaceRgba8<T>],
+    format: ImageFormat,
+    quality: Quality,
+    mipmaps: Mipmaps,
+) -> Vec<Result<Surface<Vec<u8>>, SurfaceError>>
+where
+    T: AsRef<[u8]> + Sync,
+{
+    #[cfg(feature = "rayon")]
+    {
+        use rayon::prelude::*;
+        surfaces
+            .par_iter()
+            .map(|s| s.encode(format, quality, mipmaps))
+            .collect()
+    }
+
+    #[cfg(not(feature = "rayon"))]
+    {
+        surfaces
+            .iter()
+            .map(|s| s.encode(format, quality, mipmaps))
+            .collect()
     }
 }
 
@@ -46,40 +291,34 @@ fn encode_surface<S, P>(
     format: ImageFormat,
     quality: Quality,
     mipmaps: Mipmaps,
+    options: EncodeOptions,
 ) -> Result<Surface<Vec<u8>>, SurfaceError>
 where
     S: GetMipmap<P>,
     P: Default + Copy + Encode + Pixel,
 {
     // TODO: Encode the correct number of array layers.
-    let num_mipmaps = match mipmaps {
-        Mipmaps::Disabled => 1,
-        Mipmaps::FromSurface => surface.mipmaps(),
-        Mipmaps::GeneratedExact(count) => count,
-        Mipmaps::GeneratedAutomatic => {
-            max_mipmap_count(surface.width().max(surface.height()).max(surface.depth()))
-        }
-    };
-
-    let use_surface = mipmaps == Mipmaps::FromSurface;
+    let num_mipmaps = encoded_mipmap_count(surface, mipmaps);
 
-    // TODO: Does this work if the base mip level is smaller than 4x4?
     let mut surface_data = Vec::new();
 
     for layer in 0..surface.layers() {
         // Encode 2D or 3D data for this layer.
         encode_mipmaps_rgba(
-            &mut surface_data,
+            &mut |encoded: Vec<u8>| {
+                surface_data.extend_from_slice(&encoded);
+                Ok::<_, SurfaceError>(())
+            },
             surface,
             format,
             quality,
-            num_mipmaps,
-            use_surface,
+            options,
+            mipmaps,
             layer,
         )?;
     }
 
-    Ok(Surface {
+    let surface = Surface {
         width: surface.width(),
         height: surface.height(),
         depth: surface.depth(),
@@ -87,31 +326,51 @@ where
         mipmaps: num_mipmaps,
         image_format: format,
         data: surface_data,
-    })
+    };
+    debug_assert_eq!(Ok(()), surface.assert_data_size());
+    Ok(surface)
+}
+
+fn encoded_mipmap_count<S, P>(surface: &S, mipmaps: Mipmaps) -> u32
+where
+    S: GetMipmap<P>,
+{
+    match mipmaps {
+        Mipmaps::Disabled => 1,
+        Mipmaps::FromSurface => surface.mipmaps(),
+        Mipmaps::GeneratedExact(count) => count,
+        Mipmaps::GeneratedAutomatic => {
+            max_mipmap_count(surface.width().max(surface.height()).max(surface.depth()))
+        }
+    }
 }
 
 // TODO: Find a way to simplify this.
-fn encode_mipmaps_rgba<S, P>(
-    surface_data: &mut Vec<u8>,
+fn encode_mipmaps_rgba<S, P, F, E>(
+    write_mipmap: &mut F,
     surface: &S,
     format: ImageFormat,
     quality: Quality,
-    num_mipmaps: u32,
-    use_surface: bool,
+    options: EncodeOptions,
+    mipmaps: Mipmaps,
     layer: u32,
-) -> Result<(), SurfaceError>
+) -> Result<(), E>
 where
     S: GetMipmap<P>,
     P: Default + Copy + Encode + Pixel,
+    F: FnMut(Vec<u8>) -> Result<(), E>,
+    E: From<SurfaceError>,
 {
+    let num_mipmaps = encoded_mipmap_count(surface, mipmaps);
+    let use_surface = mipmaps == Mipmaps::FromSurface;
+
     let block_dimensions = format.block_dimensions();
 
     // Track the previous image data and dimensions.
     // This enables generating mipmaps from a single base layer.
     let mut mip_data = get_mipmap_data(surface, layer, 0, block_dimensions)?;
 
-    let encoded = mip_data.encode(format, quality)?;
-    surface_data.extend_from_slice(&encoded);
+    write_mipmap(mip_data.encode(format, quality, options)?)?;
 
     for mipmap in 1..num_mipmaps {
         mip_data = if use_surface {
@@ -119,16 +378,15 @@ where
             get_mipmap_data(surface, layer, mipmap, block_dimensions)?
         } else {
             mip_data.downsample(
-                surface.width(),
-                surface.height(),
-                surface.depth(),
+                (surface.width(), surface.height(), surface.depth()),
                 block_dimensions,
                 mipmap,
+                options.mipmap_filter,
+                format.is_srgb() && !P::LINEAR,
             )
         };
 
-        let encoded = mip_data.encode(format, quality)?;
-        surface_data.extend_from_slice(&encoded);
+        write_mipmap(mip_data.encode(format, quality, options)?)?;
     }
 
     Ok(())
@@ -144,31 +402,64 @@ struct MipData<T> {
 impl<T: Pixel> MipData<T> {
     fn downsample(
         &self,
-        base_width: u32,
-        base_height: u32,
-        base_depth: u32,
+        base_dimensions: (u32, u32, u32),
         block_dimensions: (u32, u32, u32),
         mipmap: u32,
+        filter: MipmapFilter,
+        srgb: bool,
     ) -> MipData<T> {
+        let (base_width, base_height, base_depth) = base_dimensions;
+        let virtual_dimensions = |mipmap| {
+            [
+                mip_dimension(base_width, mipmap) as usize,
+                mip_dimension(base_height, mipmap) as usize,
+                mip_dimension(base_depth, mipmap) as usize,
+            ]
+        };
+
         // Mip dimensions are the padded virtual size of the mipmap.
         // Padding the physical size of the previous mip produces incorrect results.
+        let [new_width, new_height, new_depth] = virtual_dimensions(mipmap);
         let (width, height, depth) = physical_dimensions(
-            mip_dimension(base_width, mipmap),
-            mip_dimension(base_height, mipmap),
-            mip_dimension(base_depth, mipmap),
+            new_width as u32,
+            new_height as u32,
+            new_depth as u32,
             block_dimensions,
         );
 
-        // Assume the data is already padded.
-        let data = downsample_rgba(
-            width,
-            height,
-            depth,
-            self.width,
-            self.height,
-            self.depth,
-            &self.data,
-        );
+        let data = match filter {
+            // Assume the data is already padded.
+            MipmapFilter::Box => downsample_rgba(
+                [width, height, depth],
+                [self.width, self.height, self.depth],
+                &self.data,
+                srgb,
+            ),
+            MipmapFilter::Triangle | MipmapFilter::Lanczos3 => {
+                // Resample only the virtual size to avoid blending in the padding.
+                let [previous_width, previous_height, previous_depth] =
+                    virtual_dimensions(mipmap - 1);
+                let mut previous = Vec::new();
+                for z in 0..previous_depth {
+                    for y in 0..previous_height {
+                        let start = ((z * self.height + y) * self.width) * 4;
+                        previous.extend_from_slice(&self.data[start..start + previous_width * 4]);
+                    }
+                }
+
+                let resampled = resample_rgba(
+                    [new_width, new_height, new_depth],
+                    [previous_width, previous_height, previous_depth],
+                    &previous,
+                    filter,
+                    srgb,
+                );
+                pad_mipmap_rgba(
+                    new_width, new_height, new_depth, width, height, depth, &resampled,
+                )
+                .into_owned()
+            }
+        };
 
         MipData {
             width,
@@ -183,13 +474,19 @@ impl<T> MipData<T>
 where
     T: Encode,
 {
-    fn encode(&self, format: ImageFormat, quality: Quality) -> Result<Vec<u8>, SurfaceError> {
+    fn encode(
+        &self,
+        format: ImageFormat,
+        quality: Quality,
+        options: EncodeOptions,
+    ) -> Result<Vec<u8>, SurfaceError> {
         T::encode(
             self.width as u32,
             self.height as u32 * self.depth as u32,
             &self.data,
             format,
             quality,
+            options,
         )
     }
 }
@@ -275,17 +572,20 @@ where
     let mip_height = mip_dimension(surface.height(), mipmap);
     let mip_depth = mip_dimension(surface.depth(), mipmap);
 
-    // TODO: This should be for all depth levels.
     // TODO: This can be optimized to avoid copies?
     let mut data = Vec::new();
-    for level in 0..surface.depth() {
-        let new_data = surface.get(layer, level, mipmap).unwrap();
+    for level in 0..mip_depth {
+        let new_data = surface
+            .get(layer, level, mipmap)
+            .ok_or(SurfaceError::MipmapDataOutOfBounds { layer, mipmap })?;
         data.extend_from_slice(new_data);
     }
 
     let (width, height, depth) =
         physical_dimensions(mip_width, mip_height, mip_depth, block_dimensions);
 
+    // Pad each depth slice separately so blocks never span multiple slices.
+    // This allows encoding all slices as a single 2D image with height * depth rows.
     let data = pad_mipmap_rgba(
         mip_width as usize,
         mip_height as usize,
@@ -366,6 +666,7 @@ trait Encode: Sized {
         data: &[Self],
         format: ImageFormat,
         quality: Quality,
+        options: EncodeOptions,
     ) -> Result<Vec<u8>, SurfaceError>;
 }
 
@@ -376,6 +677,7 @@ impl Encode for u8 {
         data: &[Self],
         format: ImageFormat,
         quality: Quality,
+        options: EncodeOptions,
     ) -> Result<Vec<u8>, SurfaceError> {
         // Unorm and srgb only affect how the data is read.
         // Use the same conversion code for both.
@@ -390,23 +692,59 @@ impl Encode for u8 {
             F::BC3RgbaUnorm | F::BC3RgbaUnormSrgb => {
                 encode_bcn::<Bc3, u8>(width, height, data, quality)
             }
+            F::BC3NormalXgxr => {
+                // Store X in alpha and Y in green like DXT5nm.
+                let xgxr: Vec<_> = data
+                    .chunks_exact(4)
+                    .flat_map(|p| [255u8, p[1], 0u8, p[0]])
+                    .collect();
+                encode_bcn::<Bc3, u8>(width, height, &xgxr, quality)
+            }
             F::BC4RUnorm | F::BC4RSnorm => encode_bcn::<Bc4, u8>(width, height, data, quality),
             F::BC5RgUnorm | F::BC5RgSnorm => encode_bcn::<Bc5, u8>(width, height, data, quality),
-            F::BC6hRgbUfloat | F::BC6hRgbSfloat => {
-                encode_bcn::<Bc6, u8>(width, height, data, quality)
-            }
+            F::BC6hRgbUfloat => encode_bcn::<Bc6, u8>(width, height, data, quality),
+            F::BC6hRgbSfloat => Err(SurfaceError::UnsupportedEncodeFormat { format }),
             F::BC7RgbaUnorm | F::BC7RgbaUnormSrgb => {
-                encode_bcn::<Bc7, u8>(width, height, data, quality)
+                if options.force_opaque {
+                    encode_bcn::<Bc7Opaque, u8>(width, height, data, quality)
+                } else {
+                    encode_bcn::<Bc7, u8>(width, height, data, quality)
+                }
             }
             F::R8Unorm => encode_rgba::<R8, u8>(width, height, data),
             F::R8Snorm => encode_rgba::<R8Snorm, u8>(width, height, data),
             F::Rg8Unorm => encode_rgba::<Rg8, u8>(width, height, data),
             F::Rg8Snorm => encode_rgba::<Rg8Snorm, u8>(width, height, data),
-            F::Rgba8Unorm | F::Rgba8UnormSrgb => encode_rgba::<Rgba8, u8>(width, height, data),
+            F::Rgba8Unorm | F::Rgba8UnormSrgb => {
+                // The data is already RGBA8, so skip converting each pixel.
+                let expected = width as usize * height as usize * 4;
+                data.get(..expected)
+                    .map(|d| d.to_vec())
+                    .ok_or(SurfaceError::NotEnoughData {
+                        expected,
+                        actual: data.len(),
+                    })
+            }
             F::Rgba16Float => encode_rgba::<Rgbaf16, u8>(width, height, data),
             F::Rgba32Float => encode_rgba::<Rgbaf32, u8>(width, height, data),
+            F::Rgb16Float => encode_rgba::<Rgbf16, u8>(width, height, data),
+            F::Rgb9e5Ufloat => encode_rgba::<Rgb9e5, u8>(width, height, data),
+            F::Rg11b10Float => encode_rgba::<Rg11b10, u8>(width, height, data),
             F::Bgra8Unorm | F::Bgra8UnormSrgb => encode_rgba::<Bgra8, u8>(width, height, data),
-            F::Bgra4Unorm => encode_rgba::<Bgra4, u8>(width, height, data),
+            F::Bgra4Unorm => match options.dither {
+                DitherMode::None => encode_rgba::<Bgra4, u8>(width, height, data),
+                mode => {
+                    let dithered = dither_rgba8(width, height, data, 4, mode);
+                    encode_rgba::<Bgra4, u8>(width, height, &dithered)
+                }
+            },
+            F::Bgrx8Unorm => encode_rgba::<Bgrx8, u8>(width, height, data),
+            F::Rgbx8Unorm => encode_rgba::<Rgbx8, u8>(width, height, data),
+            F::B5g6r5Unorm => encode_rgba::<B5g6r5, u8>(width, height, data),
+            F::L8Unorm => encode_rgba::<L8, u8>(width, height, data),
+            F::A8Unorm => encode_rgba::<A8, u8>(width, height, data),
+            F::La8Unorm => encode_rgba::<La8, u8>(width, height, data),
+            F::Rgb8Unorm => encode_rgba::<Rgb8, u8>(width, height, data),
             F::Bgr8Unorm => encode_rgba::<Bgr8, u8>(width, height, data),
         }
     }
@@ -419,6 +757,7 @@ impl Encode for f32 {
         data: &[Self],
         format: ImageFormat,
         quality: Quality,
+        options: EncodeOptions,
     ) -> Result<Vec<u8>, SurfaceError> {
         // Unorm and srgb only affect how the data is read.
         // Use the same conversion code for both.
@@ -429,25 +768,57 @@ impl Encode for f32 {
             F::BC4RSnorm | F::BC5RgSnorm => {
                 // intel_tex doesn't have a dedicated encoder for snorm formats.
                 let rgba8: Vec<_> = data.iter().map(|f| float_to_snorm(*f) as u8).collect();
-                u8::encode(width, height, &rgba8, format, quality)
+                u8::encode(width, height, &rgba8, format, quality, options)
             }
-            F::BC6hRgbUfloat | F::BC6hRgbSfloat => {
-                encode_bcn::<Bc6, f32>(width, height, data, quality)
+            F::BC6hRgbSfloat => Err(SurfaceError::UnsupportedEncodeFormat { format }),
+            F::BC6hRgbUfloat => {
+                if options.sanitize_hdr {
+                    let sanitized: Vec<_> = data.iter().copied().map(sanitize_hdr).collect();
+                    encode_bcn::<Bc6, f32>(width, height, &sanitized, quality)
+                } else {
+                    encode_bcn::<Bc6, f32>(width, height, data, quality)
+                }
             }
             F::Rgba16Float => encode_rgba::<Rgbaf16, f32>(width, height, data),
             F::Rgba32Float => encode_rgba::<Rgbaf32, f32>(width, height, data),
+            F::Rgb16Float => encode_rgba::<Rgbf16, f32>(width, height, data),
+            F::Rgb9e5Ufloat => encode_rgba::<Rgb9e5, f32>(width, height, data),
+            F::Rg11b10Float => encode_rgba::<Rg11b10, f32>(width, height, data),
             _ => {
-                let rgba8: Vec<_> = data.iter().map(|f| (f * 255.0) as u8).collect();
-                u8::encode(width, height, &rgba8, format, quality)
+                // Convert linear color channels back to sRGB to match f32 decoding.
+                // Alpha is always stored as linear.
+                let rgba8: Vec<_> = data
+                    .iter()
+                    .enumerate()
+                    .map(|(i, f)| {
+                        if format.is_srgb() && i % 4 < 3 {
+                            float_to_unorm8(linear_to_srgb(f.clamp(0.0, 1.0)))
+                        } else {
+                            float_to_unorm8(*f)
+                        }
+                    })
+                    .collect();
+                u8::encode(width, height, &rgba8, format, quality, options)
             }
         }
     }
 }
 
+fn sanitize_hdr(f: f32) -> f32 {
+    // Use the largest finite half float since BC6H stores half float endpoints.
+    if f.is_nan() {
+        0.0
+    } else {
+        f.clamp(-f16::MAX.to_f32(), f16::MAX.to_f32())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    use crate::rgba::Rgba8;
+
     use strum::IntoEnumIterator;
 
     #[test]
@@ -558,6 +929,57 @@ mod tests {
         assert_eq!(16 * 2, surface.data.len());
     }
 
+    #[test]
+    fn encode_surface_3d_mipmaps_from_surface() {
+        // 4x4x4, 2x2x2, and 1x1x1 mipmaps.
+        let data: Vec<_> = (0..(64 + 8 + 1) * 4).map(|i| i as u8).collect();
+        let surface = SurfaceRgba8 {
+            width: 4,
+            height: 4,
+            depth: 4,
+            layers: 1,
+            mipmaps: 3,
+            data: data.as_slice(),
+        };
+
+        let rgba8 = surface
+            .encode(ImageFormat::Rgba8Unorm, Quality::Fast, Mipmaps::FromSurface)
+            .unwrap();
+        assert_eq!(3, rgba8.mipmaps);
+        assert_eq!(data, rgba8.data);
+
+        // Each depth slice of each mipmap is padded to its own block.
+        let bc7 = surface
+            .encode(
+                ImageFormat::BC7RgbaUnorm,
+                Quality::Fast,
+                Mipmaps::FromSurface,
+            )
+            .unwrap();
+        assert_eq!(3, bc7.mipmaps);
+        assert_eq!((4 + 2 + 1) * 16, bc7.data.len());
+    }
+
+    #[test]
+    fn encode_surface_3d_mipmaps_from_surface_not_enough_data() {
+        let result = SurfaceRgba8 {
+            width: 4,
+            height: 4,
+            depth: 4,
+            layers: 1,
+            mipmaps: 3,
+            data: &[0u8; (64 + 8) * 4][..],
+        }
+        .encode(ImageFormat::Rgba8Unorm, Quality::Fast, Mipmaps::FromSurface);
+        assert_eq!(
+            Err(SurfaceError::MipmapDataOutOfBounds {
+                layer: 0,
+                mipmap: 2
+            }),
+            result
+        );
+    }
+
     #[test]
     fn encode_surface_non_integral_dimensions() {
         // This should succeed with appropriate padding.
@@ -1030,9 +1452,17 @@ mod tests {
                 mipmaps: 1,
                 data: vec![0u8; 4 * 4 * 4],
             };
-            surface
-                .encode(image_format, Quality::Normal, Mipmaps::GeneratedAutomatic)
-                .unwrap();
+            let result = surface.encode(image_format, Quality::Normal, Mipmaps::GeneratedAutomatic);
+            if image_format.can_encode() {
+                result.unwrap();
+            } else {
+                assert_eq!(
+                    Err(SurfaceError::UnsupportedEncodeFormat {
+                        format: image_format
+                    }),
+                    result.map(|_| ())
+                );
+            }
         }
     }
 
@@ -1047,9 +1477,840 @@ mod tests {
                 mipmaps: 1,
                 data: vec![0.0; 4 * 4 * 4],
             };
+            let result = surface.encode(image_format, Quality::Normal, Mipmaps::GeneratedAutomatic);
+            if image_format.can_encode() {
+                result.unwrap();
+            } else {
+                assert_eq!(
+                    Err(SurfaceError::UnsupportedEncodeFormat {
+                        format: image_format
+                    }),
+                    result.map(|_| ())
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn encode_batch_matches_encode() {
+        let surfaces: Vec<_> = (0..10u8)
+            .map(|i| SurfaceRgba8 {
+                width: 8,
+                height: 8,
+                depth: 1,
+                layers: 1,
+                mipmaps: 1,
+                data: vec![i * 20; 8 * 8 * 4],
+            })
+            .collect();
+
+        let results = encode_batch(
+            &surfaces,
+            ImageFormat::BC1RgbaUnorm,
+            Quality::Fast,
+            Mipmaps::GeneratedAutomatic,
+        );
+        assert_eq!(10, results.len());
+
+        for (surface, result) in surfaces.iter().zip(results) {
+            assert_eq!(
+                surface.encode(
+                    ImageFormat::BC1RgbaUnorm,
+                    Quality::Fast,
+                    Mipmaps::GeneratedAutomatic
+                ),
+                result
+            );
+        }
+    }
+
+    fn rgba8_roundtrip(image_format: ImageFormat, data: &[u8]) -> Vec<u8> {
+        SurfaceRgba8 {
+            width: 16,
+            height: 16,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            data,
+        }
+        .encode(image_format, Quality::Fast, Mipmaps::Disabled)
+        .unwrap()
+        .decode_rgba8()
+        .unwrap()
+        .data
+    }
+
+    #[test]
+    fn encode_bc1_solid_color_opaque() {
+        // Solid colors can produce blocks with equal endpoints using the 3 color mode.
+        for color in [
+            [0, 0, 0],
+            [255, 255, 255],
+            [255, 0, 0],
+            [8, 4, 8],
+            [128, 64, 32],
+        ] {
+            let data: Vec<_> = [color[0], color[1], color[2], 255].repeat(16 * 16);
+            let rgba8 = rgba8_roundtrip(ImageFormat::BC1RgbaUnorm, &data);
+            assert!(rgba8.chunks_exact(4).all(|p| p[3] == 255), "{color:?}");
+        }
+    }
+
+    #[test]
+    fn with_generated_mipmaps_bc7() {
+        // 8x8, 4x4, 2x2, and 1x1 mipmaps.
+        let surface = Surface {
+            width: 8,
+            height: 8,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::BC7RgbaUnorm,
+            data: vec![0u8; 4 * 16],
+        };
+
+        let bc7 = surface.with_generated_mipmaps(Quality::Fast).unwrap();
+        assert_eq!(ImageFormat::BC7RgbaUnorm, bc7.image_format);
+        assert_eq!(4, bc7.mipmaps);
+        assert_eq!((4 + 1 + 1 + 1) * 16, bc7.data.len());
+    }
+
+    #[test]
+    fn transcode_bc1_to_bc7_mipmaps() {
+        // 8x8, 4x4, 2x2, and 1x1 mipmaps.
+        let surface = Surface {
+            width: 8,
+            height: 8,
+            depth: 1,
+            layers: 1,
+            mipmaps: 4,
+            image_format: ImageFormat::BC1RgbaUnorm,
+            data: vec![0u8; (4 + 1 + 1 + 1) * 8],
+        };
+
+        let bc7 = surface
+            .transcode(
+                ImageFormat::BC7RgbaUnorm,
+                Quality::Fast,
+                Mipmaps::FromSurface,
+            )
+            .unwrap();
+        assert_eq!(ImageFormat::BC7RgbaUnorm, bc7.image_format);
+        assert_eq!(4, bc7.mipmaps);
+        assert_eq!((4 + 1 + 1 + 1) * 16, bc7.data.len());
+
+        let bc7 = surface
+            .transcode(ImageFormat::BC7RgbaUnorm, Quality::Fast, Mipmaps::Disabled)
+            .unwrap();
+        assert_eq!(1, bc7.mipmaps);
+        assert_eq!(4 * 16, bc7.data.len());
+    }
+
+    #[test]
+    fn encode_bc7_3d_independent_slices() {
+        // Slices with 2 rows would share blocks if encoded as a single 4x4 image.
+        let data: Vec<_> = [[0u8; 4 * 2 * 4], [255u8; 4 * 2 * 4]].concat();
+        let surface = SurfaceRgba8 {
+            width: 4,
+            height: 2,
+            depth: 2,
+            layers: 1,
+            mipmaps: 1,
+            data: data.as_slice(),
+        }
+        .encode(ImageFormat::BC7RgbaUnorm, Quality::Fast, Mipmaps::Disabled)
+        .unwrap();
+
+        // Each slice is padded to its own block.
+        assert_eq!(2 * 16, surface.data.len());
+        let rgba8 = surface.decode_rgba8().unwrap();
+        assert_eq!(data, rgba8.data);
+    }
+
+    #[test]
+    fn encode_bc6h_roundtrip_values() {
+        let data = [0.25f32, 0.5, 2.0, 1.0].repeat(4 * 4);
+        let surface = SurfaceRgba32Float {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            data: data.as_slice(),
+        };
+
+        let rgbaf32 = surface
+            .encode(ImageFormat::BC6hRgbUfloat, Quality::Fast, Mipmaps::Disabled)
+            .unwrap()
+            .decode_rgbaf32()
+            .unwrap();
+        for (actual, expected) in rgbaf32.data.iter().zip(&data) {
+            assert!((actual - expected).abs() < 0.01, "{actual} != {expected}");
+        }
+
+        // The encoder only produces unsigned blocks that would decode incorrectly as signed.
+        let encoded = surface.encode(ImageFormat::BC6hRgbSfloat, Quality::Fast, Mipmaps::Disabled);
+        assert_eq!(
+            Err(SurfaceError::UnsupportedEncodeFormat {
+                format: ImageFormat::BC6hRgbSfloat
+            }),
+            encoded.map(|_| ())
+        );
+        let transcoded = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::BC6hRgbSfloat,
+            data: &[0u8; 16][..],
+        }
+        .with_generated_mipmaps(Quality::Fast);
+        assert_eq!(
+            Err(SurfaceError::UnsupportedEncodeFormat {
+                format: ImageFormat::BC6hRgbSfloat
+            }),
+            transcoded.map(|_| ())
+        );
+    }
+
+    #[test]
+    fn encode_bc6h_negative_clamped() {
+        // The unsigned format can't represent negative values.
+        let data = [-1.0f32, 0.5, -0.25, 1.0].repeat(4 * 4);
+        let rgbaf32 = SurfaceRgba32Float {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            data: data.as_slice(),
+        }
+        .encode(ImageFormat::BC6hRgbUfloat, Quality::Fast, Mipmaps::Disabled)
+        .unwrap()
+        .decode_rgbaf32()
+        .unwrap();
+        for pixel in rgbaf32.data.chunks_exact(4) {
+            assert_eq!(0.0, pixel[0]);
+            assert!((pixel[1] - 0.5).abs() < 0.01);
+            assert_eq!(0.0, pixel[2]);
+        }
+    }
+
+    #[test]
+    fn encode_bc6h_sanitize_hdr() {
+        let data: Vec<_> = [f32::NAN, f32::INFINITY, f32::NEG_INFINITY, 1.0]
+            .repeat(16)
+            .into_iter()
+            .collect();
+        let rgbaf32 = SurfaceRgba32Float {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            data: data.as_slice(),
+        }
+        .encode(ImageFormat::BC6hRgbUfloat, Quality::Fast, Mipmaps::Disabled)
+        .unwrap()
+        .decode_rgbaf32()
+        .unwrap();
+        assert!(rgbaf32.data.iter().all(|f| f.is_finite()));
+    }
+
+    #[test]
+    fn sanitize_hdr_values() {
+        assert_eq!(0.0, sanitize_hdr(f32::NAN));
+        assert_eq!(65504.0, sanitize_hdr(f32::INFINITY));
+        assert_eq!(-65504.0, sanitize_hdr(f32::NEG_INFINITY));
+        assert_eq!(1.5, sanitize_hdr(1.5));
+    }
+
+    #[test]
+    fn encode_streaming_matches_encode() {
+        let data = checkerboard_rgba8(16, 32);
+        let surface = SurfaceRgba8 {
+            width: 16,
+            height: 16,
+            depth: 1,
+            layers: 2,
+            mipmaps: 1,
+            data: data.as_slice(),
+        };
+
+        let mut writer = Vec::new();
+        let mipmaps = surface
+            .encode_streaming(
+                &mut writer,
+                ImageFormat::BC7RgbaUnorm,
+                Quality::Fast,
+                Mipmaps::GeneratedAutomatic,
+            )
+            .unwrap();
+
+        let encoded = surface
+            .encode(
+                ImageFormat::BC7RgbaUnorm,
+                Quality::Fast,
+                Mipmaps::GeneratedAutomatic,
+            )
+            .unwrap();
+        assert_eq!(encoded.mipmaps, mipmaps);
+        assert_eq!(encoded.data, writer);
+    }
+
+    #[test]
+    fn encode_rgba8_matches_encode_rgba() {
+        let data = checkerboard_rgba8(4, 8);
+        let surface = SurfaceRgba8 {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 2,
+            mipmaps: 1,
+            data: data.as_slice(),
+        };
+        let encoded = surface
+            .encode(ImageFormat::Rgba8Unorm, Quality::Fast, Mipmaps::Disabled)
+            .unwrap();
+        assert_eq!(2, encoded.layers);
+        assert_eq!(1, encoded.mipmaps);
+        assert_eq!(encode_rgba::<Rgba8, u8>(4, 8, &data).unwrap(), encoded.data);
+    }
+
+    #[test]
+    fn encode_rgba8_mipmaps_matches_bgra8() {
+        // Mipmaps for RGBA8 skip the per pixel conversion and should match other formats.
+        let data = checkerboard_rgba8(8, 8);
+        let surface = SurfaceRgba8 {
+            width: 8,
+            height: 8,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            data: data.as_slice(),
+        };
+        let rgba8 = surface
+            .encode(
+                ImageFormat::Rgba8UnormSrgb,
+                Quality::Fast,
+                Mipmaps::GeneratedAutomatic,
+            )
+            .unwrap();
+        let bgra8 = surface
+            .encode(
+                ImageFormat::Bgra8UnormSrgb,
+                Quality::Fast,
+                Mipmaps::GeneratedAutomatic,
+            )
+            .unwrap();
+        assert_eq!(4, rgba8.mipmaps);
+        assert_eq!(Some(data.as_slice()), rgba8.get(0, 0, 0));
+        assert_eq!(
+            bgra8.decode_rgba8().unwrap().data,
+            rgba8.decode_rgba8().unwrap().data
+        );
+    }
+
+    fn checkerboard_rgba8(width: usize, height: usize) -> Vec<u8> {
+        (0..width * height)
+            .flat_map(|i| {
+                if (i % width + i / width) & 1 == 0 {
+                    [255, 128, 64, 255]
+                } else {
+                    [0, 32, 255, 128]
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn encode_surface_smaller_than_block() {
+        // The base mip level should be padded to a full 4x4 block.
+        for size in [1, 2, 3] {
+            let data = checkerboard_rgba8(size, size);
+
+            // Manually pad with zeros to compare with the encoder's padding.
+            let mut padded = vec![0u8; 4 * 4 * 4];
+            for y in 0..size {
+                padded[y * 4 * 4..y * 4 * 4 + size * 4]
+                    .copy_from_slice(&data[y * size * 4..(y + 1) * size * 4]);
+            }
+
+            for image_format in
+                ImageFormat::iter().filter(|f| f.block_dimensions() == (4, 4, 1) && f.can_encode())
+            {
+                let surface = SurfaceRgba8 {
+                    width: size as u32,
+                    height: size as u32,
+                    depth: 1,
+                    layers: 1,
+                    mipmaps: 1,
+                    data: data.as_slice(),
+                }
+                .encode(image_format, Quality::Fast, Mipmaps::Disabled)
+                .unwrap();
+
+                assert_eq!(size as u32, surface.width);
+                assert_eq!(size as u32, surface.height);
+                assert_eq!(image_format.block_size_in_bytes(), surface.data.len());
+
+                let expected = SurfaceRgba8 {
+                    width: 4,
+                    height: 4,
+                    depth: 1,
+                    layers: 1,
+                    mipmaps: 1,
+                    data: padded.as_slice(),
+                }
+                .encode(image_format, Quality::Fast, Mipmaps::Disabled)
+                .unwrap()
+                .decode_rgba8()
+                .unwrap();
+
+                // The decoded data should match the top left region of the padded data.
+                let decoded = surface.decode_rgba8().unwrap();
+                for y in 0..size {
+                    assert_eq!(
+                        &expected.data[y * 4 * 4..y * 4 * 4 + size * 4],
+                        &decoded.data[y * size * 4..(y + 1) * size * 4],
+                        "{image_format:?} {size}x{size}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn encode_surface_smaller_than_block_mipmaps() {
+        // Each mipmap still requires a full block.
+        let surface = SurfaceRgba8 {
+            width: 3,
+            height: 3,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            data: checkerboard_rgba8(3, 3),
+        }
+        .encode(
+            ImageFormat::BC7RgbaUnorm,
+            Quality::Fast,
+            Mipmaps::GeneratedAutomatic,
+        )
+        .unwrap();
+
+        assert_eq!(2, surface.mipmaps);
+        assert_eq!(2 * 16, surface.data.len());
+        assert_eq!(3 * 3 * 4 + 4, surface.decode_rgba8().unwrap().data.len());
+    }
+
+    #[test]
+    fn encode_rgba32_float_lanczos3_non_power_of_two() {
+        // 5x3, 2x1, and 1x1 mipmaps.
+        let data: Vec<_> = [4.0f32, 2.0, 0.5, 1.0].repeat(5 * 3);
+        let surface = SurfaceRgba32Float {
+            width: 5,
+            height: 3,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            data: data.as_slice(),
+        }
+        .encode_with_options(
+            ImageFormat::Rgba32Float,
+            Quality::Fast,
+            Mipmaps::GeneratedAutomatic,
+            EncodeOptions {
+                mipmap_filter: MipmapFilter::Lanczos3,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        // Constant HDR colors should not be clamped or blended with padding.
+        assert_eq!(3, surface.mipmaps);
+        let expected: Vec<_> = [4.0f32, 2.0, 0.5, 1.0].repeat(5 * 3 + 2 + 1);
+        let decoded = surface.decode_rgbaf32().unwrap().data;
+        assert_eq!(expected.len(), decoded.len());
+        for (expected, actual) in expected.iter().zip(&decoded) {
+            assert!((expected - actual).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn encode_rgba8_srgb_mipmaps_linear_average() {
+        // A 2x2 checkerboard averages to middle gray in linear space.
+        let surface = SurfaceRgba8 {
+            width: 2,
+            height: 2,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            data: &[
+                0u8, 0, 0, 255, 255, 255, 255, 255, 255, 255, 255, 255, 0, 0, 0, 255,
+            ],
+        };
+
+        let srgb = surface
+            .encode(
+                ImageFormat::Rgba8UnormSrgb,
+                Quality::Fast,
+                Mipmaps::GeneratedAutomatic,
+            )
+            .unwrap();
+        assert_eq!(&[188, 188, 188, 255], &srgb.data[2 * 2 * 4..]);
+
+        let linear = surface
+            .encode(
+                ImageFormat::Rgba8Unorm,
+                Quality::Fast,
+                Mipmaps::GeneratedAutomatic,
+            )
+            .unwrap();
+        assert_eq!(&[128, 128, 128, 255], &linear.data[2 * 2 * 4..]);
+    }
+
+    #[test]
+    fn encode_rgbaf32_srgb_round_trip() {
+        // Decoding to linear f32 and encoding back to sRGB should preserve all values.
+        let data: Vec<_> = (0..=255u8).flat_map(|i| [i, i, i, i]).collect();
+        for image_format in [ImageFormat::Rgba8UnormSrgb, ImageFormat::Bgra8UnormSrgb] {
+            let surface = Surface {
+                width: 16,
+                height: 16,
+                depth: 1,
+                layers: 1,
+                mipmaps: 1,
+                image_format,
+                data: data.as_slice(),
+            };
+            let encoded = surface
+                .decode_rgbaf32()
+                .unwrap()
+                .encode(image_format, Quality::Fast, Mipmaps::Disabled)
+                .unwrap();
+            assert_eq!(data, encoded.data);
+        }
+    }
+
+    #[test]
+    fn encode_rgbaf32_srgb_mipmaps_linear_average() {
+        // The f32 data is already linear, so average without converting from sRGB.
+        let surface = SurfaceRgba32Float {
+            width: 2,
+            height: 2,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            data: &[
+                0.0f32, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 0.0, 0.0, 0.0, 1.0,
+            ],
+        };
+
+        let srgb = surface
+            .encode(
+                ImageFormat::Rgba8UnormSrgb,
+                Quality::Fast,
+                Mipmaps::GeneratedAutomatic,
+            )
+            .unwrap();
+        assert_eq!(&[188, 188, 188, 255], &srgb.data[2 * 2 * 4..]);
+    }
+
+    fn encode_bgra4_gradient(dither: DitherMode) -> Vec<u8> {
+        // A smooth gradient with many values between the 4-bit levels.
+        let data: Vec<_> = (0..16 * 4u32).flat_map(|i| [(i / 4) as u8; 4]).collect();
+        SurfaceRgba8 {
+            width: 16,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            data: data.as_slice(),
+        }
+        .encode_with_options(
+            ImageFormat::Bgra4Unorm,
+            Quality::Fast,
+            Mipmaps::Disabled,
+            EncodeOptions {
+                dither,
+                ..Default::default()
+            },
+        )
+        .unwrap()
+        .decode_rgba8()
+        .unwrap()
+        .data
+    }
+
+    #[test]
+    fn encode_bgra4_dither() {
+        // Values below 17 truncate to 0 without dithering.
+        assert!(encode_bgra4_gradient(DitherMode::None)
+            .iter()
+            .all(|v| *v == 0));
+        for dither in [DitherMode::Ordered, DitherMode::FloydSteinberg] {
+            let decoded = encode_bgra4_gradient(dither);
+            assert!(decoded.contains(&17), "{dither:?}");
+            assert!(decoded.iter().all(|v| *v == 0 || *v == 17), "{dither:?}");
+        }
+    }
+
+    #[test]
+    fn encode_bc7_triangle_padding() {
+        // 6x6 and 3x3 mipmaps are padded to 8x8 and 4x4.
+        let surface = SurfaceRgba8 {
+            width: 6,
+            height: 6,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            data: &[255u8; 6 * 6 * 4],
+        }
+        .encode_with_options(
+            ImageFormat::BC7RgbaUnorm,
+            Quality::Fast,
+            Mipmaps::GeneratedExact(2),
+            EncodeOptions {
+                mipmap_filter: MipmapFilter::Triangle,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        // The zero padding should not darken the edges of the second mipmap.
+        assert_eq!(2, surface.mipmaps);
+        assert_eq!((4 + 1) * 16, surface.data.len());
+        assert_eq!(
+            vec![255u8; (6 * 6 + 3 * 3) * 4],
+            surface.decode_rgba8().unwrap().data
+        );
+    }
+
+    #[test]
+    fn encode_bgr8_custom_channels() {
+        let surface = SurfaceRgba8 {
+            width: 2,
+            height: 1,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            data: &[10u8, 20, 30, 40, 50, 60, 70, 80],
+        }
+        .encode_with_options(
+            ImageFormat::Bgr8Unorm,
+            Quality::Fast,
+            Mipmaps::Disabled,
+            EncodeOptions {
+                channels: [3, 1, 1, 0],
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        // The output RGB is alpha, green, green stored as BGR.
+        assert_eq!(vec![20, 20, 40, 60, 60, 80], surface.data);
+    }
+
+    #[test]
+    fn encode_bc4_bc5_custom_channels() {
+        // Red and blue are black, and green and alpha are white.
+        let data = [0u8, 255, 0, 255].repeat(4 * 4);
+        let surface = SurfaceRgba8 {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            data: data.as_slice(),
+        };
+        let encode = |format, channels| {
             surface
-                .encode(image_format, Quality::Normal, Mipmaps::GeneratedAutomatic)
+                .encode_with_options(
+                    format,
+                    Quality::Fast,
+                    Mipmaps::Disabled,
+                    EncodeOptions {
+                        channels,
+                        ..Default::default()
+                    },
+                )
+                .unwrap()
+                .decode_rgba8()
+                .unwrap()
+                .data
+        };
+
+        // BC4 from green instead of red.
+        let bc4 = encode(ImageFormat::BC4RUnorm, [1, 1, 2, 3]);
+        assert!(bc4.chunks_exact(4).all(|p| p[0] == 255));
+
+        // BC5 from alpha and blue instead of red and green.
+        let bc5 = encode(ImageFormat::BC5RgUnorm, [3, 2, 2, 3]);
+        assert!(bc5.chunks_exact(4).all(|p| p[0] == 255 && p[1] == 0));
+
+        // The default channels use red and green.
+        let bc5 = encode(ImageFormat::BC5RgUnorm, [0, 1, 2, 3]);
+        assert!(bc5.chunks_exact(4).all(|p| p[0] == 0 && p[1] == 255));
+    }
+
+    #[test]
+    fn encode_rgba32_float_custom_channels() {
+        let surface = SurfaceRgba32Float {
+            width: 1,
+            height: 1,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            data: &[0.1f32, 0.2, 0.3, 0.4],
+        }
+        .encode_with_options(
+            ImageFormat::Rgba32Float,
+            Quality::Fast,
+            Mipmaps::Disabled,
+            EncodeOptions {
+                channels: [2, 1, 0, 3],
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            bytemuck::cast_slice::<f32, u8>(&[0.3, 0.2, 0.1, 0.4]),
+            surface.data
+        );
+    }
+
+    #[test]
+    fn encode_force_opaque() {
+        // Use alpha values that would use punch through alpha for BC1.
+        let data: Vec<_> = (0..8 * 8)
+            .flat_map(|i| {
+                [
+                    (i * 4) as u8,
+                    128,
+                    255 - i as u8,
+                    if i % 2 == 0 { 0 } else { 128 },
+                ]
+            })
+            .collect();
+        let surface = SurfaceRgba8 {
+            width: 8,
+            height: 8,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            data: data.as_slice(),
+        };
+
+        for (format, quality) in [
+            ImageFormat::BC1RgbaUnorm,
+            ImageFormat::BC3RgbaUnorm,
+            ImageFormat::BC7RgbaUnorm,
+            ImageFormat::Rgba8Unorm,
+            ImageFormat::Bgra4Unorm,
+        ]
+        .into_iter()
+        .flat_map(|f| [Quality::Fast, Quality::Normal, Quality::Slow].map(|q| (f, q)))
+        {
+            let decoded = surface
+                .encode_with_options(
+                    format,
+                    quality,
+                    Mipmaps::GeneratedAutomatic,
+                    EncodeOptions {
+                        force_opaque: true,
+                        ..Default::default()
+                    },
+                )
+                .unwrap()
+                .decode_rgba8()
                 .unwrap();
+            assert!(
+                decoded.data.chunks_exact(4).all(|p| p[3] == 255),
+                "{format:?} {quality:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn encode_rgba32_float_force_opaque() {
+        let surface = SurfaceRgba32Float {
+            width: 1,
+            height: 1,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            data: &[0.5f32, 0.25, 2.0, 0.0],
+        }
+        .encode_with_options(
+            ImageFormat::Rgba32Float,
+            Quality::Fast,
+            Mipmaps::Disabled,
+            EncodeOptions {
+                force_opaque: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            bytemuck::cast_slice::<f32, u8>(&[0.5, 0.25, 2.0, 1.0]),
+            surface.data
+        );
+    }
+
+    #[test]
+    fn encode_invalid_channel_index() {
+        let result = SurfaceRgba8 {
+            width: 1,
+            height: 1,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            data: &[0u8; 4],
+        }
+        .encode_with_options(
+            ImageFormat::Rgba8Unorm,
+            Quality::Fast,
+            Mipmaps::Disabled,
+            EncodeOptions {
+                channels: [0, 1, 2, 4],
+                ..Default::default()
+            },
+        );
+        assert_eq!(Err(SurfaceError::InvalidChannelIndex { index: 4 }), result);
+    }
+
+    fn all_values_rgba8() -> Vec<u8> {
+        // Use every possible value for each channel.
+        (0..16 * 16)
+            .flat_map(|i| [0, 64, 128, 192].map(|o| (i + o) as u8))
+            .collect()
+    }
+
+    fn assert_lossless_roundtrip(image_format: ImageFormat) {
+        let data = all_values_rgba8();
+        assert_eq!(
+            data,
+            rgba8_roundtrip(image_format, &data),
+            "{image_format:?}"
+        );
+    }
+
+    #[test]
+    fn is_lossless_for_rgba8_all() {
+        let data = all_values_rgba8();
+        for image_format in ImageFormat::iter().filter(|f| f.can_encode()) {
+            if image_format.is_lossless_for_rgba8() {
+                assert_lossless_roundtrip(image_format);
+            } else {
+                assert_ne!(
+                    data,
+                    rgba8_roundtrip(image_format, &data),
+                    "{image_format:?}"
+                );
+            }
         }
     }
 }
@@ -1,5 +1,6 @@
 use thiserror::Error;
 
+use crate::BlockPadding;
 #[cfg(feature = "ddsfile")]
 use crate::DdsFormatInfo;
 use crate::ImageFormat;
@@ -19,6 +20,21 @@ pub enum CreateImageError {
 
     #[error("{mipmaps} mipmaps exceeds the maximum expected mipmap count of {max_mipmaps}")]
     UnexpectedMipmapCount { mipmaps: u32, max_mipmaps: u32 },
+
+    #[error("image dimensions {width}x{height} do not match the expected dimensions {expected_width}x{expected_height}")]
+    UnexpectedImageDimensions {
+        width: u32,
+        height: u32,
+        expected_width: u32,
+        expected_height: u32,
+    },
+}
+
+/// The error when parsing an [ImageFormat] from a string.
+#[derive(Debug, Error, PartialEq)]
+#[error("{name:?} is not a valid image format name")]
+pub struct ParseImageFormatError {
+    pub name: String,
 }
 
 /// Errors that can occur while encoding or decoding a surface.
@@ -30,6 +46,13 @@ pub enum SurfaceError {
     #[error("surface pixel count {width} x {height} x {depth} would overflow")]
     PixelCountWouldOverflow { width: u32, height: u32, depth: u32 },
 
+    #[error("surface dimensions {width} x {height} padded with {padding:?} would overflow")]
+    PaddedDimensionsWouldOverflow {
+        width: u32,
+        height: u32,
+        padding: BlockPadding,
+    },
+
     #[error("surface dimensions {width} x {height} x {depth} are not divisibly by the block dimensions {block_width} x {block_height}")]
     NonIntegralDimensionsInBlocks {
         width: u32,
@@ -56,9 +79,86 @@ pub enum SurfaceError {
     MipmapDataOutOfBounds { layer: u32, mipmap: u32 },
 
     #[cfg(feature = "ddsfile")]
-    #[error("DDS image format {0:?} is not supported")]
+    #[error("DDS image format is not supported ({0})")]
     UnsupportedDdsFormat(DdsFormatInfo),
 
+    #[cfg(feature = "ddsfile")]
+    #[error("DDS file uses the DX10 FourCC but is missing the DX10 header, which may indicate a truncated or corrupted file")]
+    MissingDx10Header,
+
+    #[cfg(feature = "ddsfile")]
+    #[error("DDS array layer count {layers} multiplied by {factor} would overflow")]
+    DdsLayerCountWouldOverflow { layers: u32, factor: u32 },
+
+    #[cfg(feature = "ktx2")]
+    #[error("KTX2 image format {0:?} is not supported")]
+    UnsupportedKtx2Format(Option<ktx2::Format>),
+
+    #[cfg(feature = "ktx2")]
+    #[error("KTX2 supercompression scheme {0:?} is not supported")]
+    UnsupportedKtx2Supercompression(ktx2::SupercompressionScheme),
+
+    #[cfg(feature = "ktx2")]
+    #[error("KTX2 layer count {layer_count} with face count {face_count} is not valid")]
+    InvalidKtx2LayerCount { layer_count: u32, face_count: u32 },
+
     #[error("{mipmaps} mipmaps exceeds the maximum expected mipmap count of {max_mipmaps}")]
     UnexpectedMipmapCount { mipmaps: u32, max_mipmaps: u32 },
+
+    #[error("surface dimensions {width} x {height} x {depth} exceed the maximum dimension of {max_dimension}")]
+    ExceedsMaxDimension {
+        width: u32,
+        height: u32,
+        depth: u32,
+        max_dimension: u32,
+    },
+
+    #[error("{layers} array layers exceeds the maximum of {max_array_layers}")]
+    ExceedsMaxArrayLayers { layers: u32, max_array_layers: u32 },
+
+    #[error("surface dimensions {width} x {height} x {depth} are not powers of two")]
+    NonPowerOfTwoDimensions { width: u32, height: u32, depth: u32 },
+
+    #[error("mipmap {mipmap} does not match the format, array layers, or expected dimensions")]
+    IncompatibleMipmap { mipmap: u32 },
+
+    #[error("data length {actual} does not match the expected length {expected} with a difference of {}", *actual as i64 - *expected as i64)]
+    UnexpectedDataSize { expected: usize, actual: usize },
+
+    #[error("compressed block at pixel ({x}, {y}) uses a reserved or invalid encoding")]
+    InvalidCompressedBlock { x: u32, y: u32 },
+
+    #[error("surface with {pixels} pixels exceeds the maximum of {max_pixels} pixels")]
+    ExceedsMaxPixels { pixels: u64, max_pixels: u64 },
+
+    #[error("surface has {layers} layers but the data contains exactly {data_layers} layers")]
+    UnexpectedLayerCount { layers: u32, data_layers: usize },
+
+    #[error("surface dimensions {width} x {height} x {depth} with {layers} layers are not compatible with {other_width} x {other_height} x {other_depth} with {other_layers} layers")]
+    IncompatibleSurfaceDimensions {
+        width: u32,
+        height: u32,
+        depth: u32,
+        layers: u32,
+        other_width: u32,
+        other_height: u32,
+        other_depth: u32,
+        other_layers: u32,
+    },
+
+    #[error("surface with {mipmaps} mipmaps is not compatible with {other_mipmaps} mipmaps")]
+    IncompatibleMipmapCount { mipmaps: u32, other_mipmaps: u32 },
+
+    #[error("region at ({x}, {y}) with size {width} x {height} is outside the {mip_width} x {mip_height} mipmap")]
+    RegionOutOfBounds {
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        mip_width: u32,
+        mip_height: u32,
+    },
+
+    #[error("channel index {index} is out of range for RGBA data")]
+    InvalidChannelIndex { index: usize },
 }
@@ -0,0 +1,764 @@
+use ktx2::{
+    ChannelTypeQualifiers, ColorModel, ColorPrimaries, DataFormatFlags, DfdBlockHeaderBasic,
+    DfdHeader, Format, Header, Index, LevelIndex, SampleInformation, TransferFunction,
+};
+use std::num::NonZeroU8;
+use thiserror::Error;
+
+use crate::{mip_dimension, mip_size, ImageFormat, Surface, SurfaceError};
+
+/// Errors that can occur when converting to KTX2.
+#[derive(Debug, Error, PartialEq)]
+pub enum CreateKtx2Error {
+    #[error("error reading surface: {0}")]
+    Surface(#[from] SurfaceError),
+
+    #[error("image format {0:?} has no equivalent Vulkan format")]
+    UnsupportedFormat(ImageFormat),
+
+    #[error("KTX2 file size would overflow")]
+    SizeWouldOverflow,
+
+    #[error(
+        "surface with height {height}, depth {depth}, {layers} layers, and image format {image_format:?} is not a valid 1D texture"
+    )]
+    Invalid1dSurface {
+        height: u32,
+        depth: u32,
+        layers: u32,
+        image_format: ImageFormat,
+    },
+}
+
+impl<T: AsRef<[u8]>> Surface<T> {
+    /// Create the bytes for a KTX2 file with the same image data and format.
+    ///
+    /// Surfaces with 6 layers are treated as cube maps like [Surface::to_dds].
+    /// KTX2 stores mipmaps from smallest to largest with all layers of each mipmap together,
+    /// so the data is reordered from the layer major order used by [Surface].
+    pub fn to_ktx2(&self) -> Result<Vec<u8>, CreateKtx2Error> {
+        self.create_ktx2(false)
+    }
+
+    /// Create the bytes for a 1D KTX2 file with the same image data and format.
+    ///
+    /// [Surface::to_ktx2] always writes surfaces with a height of 1 as 2D.
+    /// 1D textures like lookup tables require a height and depth of 1,
+    /// no cube maps, and an uncompressed format.
+    pub fn to_ktx2_1d(&self) -> Result<Vec<u8>, CreateKtx2Error> {
+        if self.height != 1
+            || self.depth != 1
+            || self.layers == 6
+            || self.image_format.block_dimensions() != (1, 1, 1)
+        {
+            return Err(CreateKtx2Error::Invalid1dSurface {
+                height: self.height,
+                depth: self.depth,
+                layers: self.layers,
+                image_format: self.image_format,
+            });
+        }
+        self.create_ktx2(true)
+    }
+
+    fn create_ktx2(&self, is_1d: bool) -> Result<Vec<u8>, CreateKtx2Error> {
+        let format = vk_format_from_image_format(self.image_format)
+            .ok_or(CreateKtx2Error::UnsupportedFormat(self.image_format))?;
+
+        // KTX2 stores all layers and faces for each mipmap together.
+        let mut levels = Vec::new();
+        for mipmap in 0..self.mipmaps {
+            let mut level = Vec::new();
+            for layer in 0..self.layers {
+                let range = self
+                    .mip_range(layer, mipmap)
+                    .ok_or(SurfaceError::MipmapDataOutOfBounds { layer, mipmap })?;
+                level.extend_from_slice(&self.data.as_ref()[range]);
+            }
+            levels.push(level);
+        }
+
+        let dfd = data_format_descriptor(self.image_format);
+
+        let level_index_offset = Header::LENGTH;
+        let dfd_offset = level_index_offset + levels.len() * LevelIndex::LENGTH;
+        let dfd_end = dfd_offset + dfd.len();
+
+        // Levels are stored from smallest to largest and aligned to the texel block size.
+        let alignment = level_alignment(self.image_format.block_size_in_bytes());
+        let mut level_offsets = vec![0; levels.len()];
+        let mut offset = dfd_end;
+        for (i, level) in levels.iter().enumerate().rev() {
+            offset = crate::round_up(offset, alignment);
+            level_offsets[i] = offset;
+            offset += level.len();
+        }
+
+        let is_cube = self.layers == 6;
+        let header = Header {
+            format: Some(format),
+            type_size: type_size(self.image_format),
+            pixel_width: self.width,
+            // 1D textures have a height of 0.
+            pixel_height: if is_1d { 0 } else { self.height },
+            pixel_depth: if self.depth > 1 { self.depth } else { 0 },
+            layer_count: if !is_cube && self.layers > 1 {
+                self.layers
+            } else {
+                0
+            },
+            face_count: if is_cube { 6 } else { 1 },
+            level_count: self.mipmaps,
+            supercompression_scheme: None,
+            index: Index {
+                dfd_byte_offset: dfd_offset
+                    .try_into()
+                    .map_err(|_| CreateKtx2Error::SizeWouldOverflow)?,
+                dfd_byte_length: dfd
+                    .len()
+                    .try_into()
+                    .map_err(|_| CreateKtx2Error::SizeWouldOverflow)?,
+                kvd_byte_offset: 0,
+                kvd_byte_length: 0,
+                sgd_byte_offset: 0,
+                sgd_byte_length: 0,
+            },
+        };
+
+        let mut bytes = Vec::with_capacity(offset);
+        bytes.extend_from_slice(&header.as_bytes());
+        for (level, level_offset) in levels.iter().zip(&level_offsets) {
+            let index = LevelIndex {
+                byte_offset: *level_offset as u64,
+                byte_length: level.len() as u64,
+                uncompressed_byte_length: level.len() as u64,
+            };
+            bytes.extend_from_slice(&index.as_bytes());
+        }
+        bytes.extend_from_slice(&dfd);
+        for (level, level_offset) in levels.iter().zip(&level_offsets).rev() {
+            bytes.resize(*level_offset, 0);
+            bytes.extend_from_slice(level);
+        }
+
+        Ok(bytes)
+    }
+}
+
+impl Surface<Vec<u8>> {
+    /// Create a surface from the image data in `reader`.
+    ///
+    /// KTX2 cube maps use 6 layers for each cube like DDS.
+    /// The data is reordered from the mipmap major order used by KTX2,
+    /// so this always copies the data.
+    /// Files using supercompression are not supported.
+    pub fn from_ktx2<T: AsRef<[u8]>>(reader: &ktx2::Reader<T>) -> Result<Self, SurfaceError> {
+        let header = reader.header();
+        if let Some(scheme) = header.supercompression_scheme {
+            return Err(SurfaceError::UnsupportedKtx2Supercompression(scheme));
+        }
+        let image_format = header
+            .format
+            .and_then(image_format_from_vk_format)
+            .ok_or(SurfaceError::UnsupportedKtx2Format(header.format))?;
+
+        let width = header.pixel_width;
+        let height = header.pixel_height.max(1);
+        let depth = header.pixel_depth.max(1);
+        // Faces are only used for cube maps.
+        let layers = Some(header.face_count)
+            .filter(|f| *f == 1 || *f == 6)
+            .and_then(|f| header.layer_count.max(1).checked_mul(f))
+            .ok_or(SurfaceError::InvalidKtx2LayerCount {
+                layer_count: header.layer_count,
+                face_count: header.face_count,
+            })?;
+        // A level count of 0 requests generating mipmaps at load time.
+        let mipmaps = header.level_count.max(1);
+
+        let (block_width, block_height, block_depth) = image_format.block_dimensions();
+        let levels: Vec<_> = reader.levels().collect();
+
+        let mut data = Vec::new();
+        for layer in 0..layers {
+            for mipmap in 0..mipmaps {
+                let size = mip_size(
+                    mip_dimension(width, mipmap) as usize,
+                    mip_dimension(height, mipmap) as usize,
+                    mip_dimension(depth, mipmap) as usize,
+                    block_width as usize,
+                    block_height as usize,
+                    block_depth as usize,
+                    image_format.block_size_in_bytes(),
+                )
+                .ok_or(SurfaceError::PixelCountWouldOverflow {
+                    width,
+                    height,
+                    depth,
+                })?;
+                let start = layer as usize * size;
+                let layer_data = levels
+                    .get(mipmap as usize)
+                    .and_then(|level| level.data.get(start..start + size))
+                    .ok_or(SurfaceError::MipmapDataOutOfBounds { layer, mipmap })?;
+                data.extend_from_slice(layer_data);
+            }
+        }
+
+        Ok(Surface {
+            width,
+            height,
+            depth,
+            layers,
+            mipmaps,
+            image_format,
+            data,
+        })
+    }
+}
+
+impl ImageFormat {
+    /// The name of the Vulkan format used for KTX2 files like `"BC1_RGBA_UNORM_BLOCK"`
+    /// or [None] if there is no equivalent Vulkan format.
+    pub fn vk_name(&self) -> Option<String> {
+        vk_format_from_image_format(*self).map(|f| format!("{f:?}"))
+    }
+}
+
+fn vk_format_from_image_format(format: ImageFormat) -> Option<Format> {
+    match format {
+        ImageFormat::R8Unorm => Some(Format::R8_UNORM),
+        ImageFormat::R8Snorm => Some(Format::R8_SNORM),
+        ImageFormat::Rg8Unorm => Some(Format::R8G8_UNORM),
+        ImageFormat::Rg8Snorm => Some(Format::R8G8_SNORM),
+        ImageFormat::Rgba8Unorm => Some(Format::R8G8B8A8_UNORM),
+        ImageFormat::Rgba8UnormSrgb => Some(Format::R8G8B8A8_SRGB),
+        ImageFormat::Rgba16Float => Some(Format::R16G16B16A16_SFLOAT),
+        ImageFormat::Rgba32Float => Some(Format::R32G32B32A32_SFLOAT),
+        ImageFormat::Rgb16Float => Some(Format::R16G16B16_SFLOAT),
+        ImageFormat::Rgb9e5Ufloat => Some(Format::E5B9G9R9_UFLOAT_PACK32),
+        ImageFormat::Rg11b10Float => Some(Format::B10G11R11_UFLOAT_PACK32),
+        ImageFormat::Rgb8Unorm => Some(Format::R8G8B8_UNORM),
+        ImageFormat::Bgr8Unorm => Some(Format::B8G8R8_UNORM),
+        ImageFormat::B5g6r5Unorm => Some(Format::R5G6B5_UNORM_PACK16),
+        ImageFormat::Bgra8Unorm => Some(Format::B8G8R8A8_UNORM),
+        ImageFormat::Bgra8UnormSrgb => Some(Format::B8G8R8A8_SRGB),
+        ImageFormat::BC1RgbaUnorm => Some(Format::BC1_RGBA_UNORM_BLOCK),
+        ImageFormat::BC1RgbaUnormSrgb => Some(Format::BC1_RGBA_SRGB_BLOCK),
+        ImageFormat::BC2RgbaUnorm => Some(Format::BC2_UNORM_BLOCK),
+        ImageFormat::BC2RgbaUnormSrgb => Some(Format::BC2_SRGB_BLOCK),
+        ImageFormat::BC3RgbaUnorm => Some(Format::BC3_UNORM_BLOCK),
+        ImageFormat::BC3RgbaUnormSrgb => Some(Format::BC3_SRGB_BLOCK),
+        ImageFormat::BC4RUnorm => Some(Format::BC4_UNORM_BLOCK),
+        ImageFormat::BC4RSnorm => Some(Format::BC4_SNORM_BLOCK),
+        ImageFormat::BC5RgUnorm => Some(Format::BC5_UNORM_BLOCK),
+        ImageFormat::BC5RgSnorm => Some(Format::BC5_SNORM_BLOCK),
+        ImageFormat::BC6hRgbUfloat => Some(Format::BC6H_UFLOAT_BLOCK),
+        ImageFormat::BC6hRgbSfloat => Some(Format::BC6H_SFLOAT_BLOCK),
+        ImageFormat::BC7RgbaUnorm => Some(Format::BC7_UNORM_BLOCK),
+        ImageFormat::BC7RgbaUnormSrgb => Some(Format::BC7_SRGB_BLOCK),
+        // The unused channel, swizzled, luminance, and alpha only formats
+        // have no Vulkan equivalent.
+        ImageFormat::Bgra4Unorm => None,
+        ImageFormat::Bgrx8Unorm => None,
+        ImageFormat::Rgbx8Unorm => None,
+        ImageFormat::L8Unorm => None,
+        ImageFormat::A8Unorm => None,
+        ImageFormat::La8Unorm => None,
+        ImageFormat::BC3NormalXgxr => None,
+    }
+}
+
+fn image_format_from_vk_format(format: Format) -> Option<ImageFormat> {
+    match format {
+        Format::R8_UNORM => Some(ImageFormat::R8Unorm),
+        Format::R8_SNORM => Some(ImageFormat::R8Snorm),
+        Format::R8G8_UNORM => Some(ImageFormat::Rg8Unorm),
+        Format::R8G8_SNORM => Some(ImageFormat::Rg8Snorm),
+        Format::R8G8B8A8_UNORM => Some(ImageFormat::Rgba8Unorm),
+        Format::R8G8B8A8_SRGB => Some(ImageFormat::Rgba8UnormSrgb),
+        Format::R16G16B16A16_SFLOAT => Some(ImageFormat::Rgba16Float),
+        Format::R32G32B32A32_SFLOAT => Some(ImageFormat::Rgba32Float),
+        Format::R16G16B16_SFLOAT => Some(ImageFormat::Rgb16Float),
+        Format::E5B9G9R9_UFLOAT_PACK32 => Some(ImageFormat::Rgb9e5Ufloat),
+        Format::B10G11R11_UFLOAT_PACK32 => Some(ImageFormat::Rg11b10Float),
+        Format::R8G8B8_UNORM => Some(ImageFormat::Rgb8Unorm),
+        Format::B8G8R8_UNORM => Some(ImageFormat::Bgr8Unorm),
+        Format::R5G6B5_UNORM_PACK16 => Some(ImageFormat::B5g6r5Unorm),
+        Format::B8G8R8A8_UNORM => Some(ImageFormat::Bgra8Unorm),
+        Format::B8G8R8A8_SRGB => Some(ImageFormat::Bgra8UnormSrgb),
+        // BC1 blocks decode the same with or without alpha.
+        Format::BC1_RGB_UNORM_BLOCK | Format::BC1_RGBA_UNORM_BLOCK => {
+            Some(ImageFormat::BC1RgbaUnorm)
+        }
+        Format::BC1_RGB_SRGB_BLOCK | Format::BC1_RGBA_SRGB_BLOCK => {
+            Some(ImageFormat::BC1RgbaUnormSrgb)
+        }
+        Format::BC2_UNORM_BLOCK => Some(ImageFormat::BC2RgbaUnorm),
+        Format::BC2_SRGB_BLOCK => Some(ImageFormat::BC2RgbaUnormSrgb),
+        Format::BC3_UNORM_BLOCK => Some(ImageFormat::BC3RgbaUnorm),
+        Format::BC3_SRGB_BLOCK => Some(ImageFormat::BC3RgbaUnormSrgb),
+        Format::BC4_UNORM_BLOCK => Some(ImageFormat::BC4RUnorm),
+        Format::BC4_SNORM_BLOCK => Some(ImageFormat::BC4RSnorm),
+        Format::BC5_UNORM_BLOCK => Some(ImageFormat::BC5RgUnorm),
+        Format::BC5_SNORM_BLOCK => Some(ImageFormat::BC5RgSnorm),
+        Format::BC6H_UFLOAT_BLOCK => Some(ImageFormat::BC6hRgbUfloat),
+        Format::BC6H_SFLOAT_BLOCK => Some(ImageFormat::BC6hRgbSfloat),
+        Format::BC7_UNORM_BLOCK => Some(ImageFormat::BC7RgbaUnorm),
+        Format::BC7_SRGB_BLOCK => Some(ImageFormat::BC7RgbaUnormSrgb),
+        _ => None,
+    }
+}
+
+fn type_size(format: ImageFormat) -> u32 {
+    // The size of the data type used for endianness conversion.
+    match format {
+        ImageFormat::Rgba16Float | ImageFormat::Rgb16Float | ImageFormat::B5g6r5Unorm => 2,
+        ImageFormat::Rgba32Float | ImageFormat::Rgb9e5Ufloat | ImageFormat::Rg11b10Float => 4,
+        _ => 1,
+    }
+}
+
+fn level_alignment(block_size_in_bytes: usize) -> usize {
+    // The least common multiple of the texel block size and 4.
+    match block_size_in_bytes % 4 {
+        0 => block_size_in_bytes,
+        2 => block_size_in_bytes * 2,
+        _ => block_size_in_bytes * 4,
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum SampleType {
+    Unorm,
+    Snorm,
+    Ufloat,
+    Sfloat,
+}
+
+fn data_format_descriptor(format: ImageFormat) -> Vec<u8> {
+    const R: u8 = 0;
+    const G: u8 = 1;
+    const B: u8 = 2;
+    const A: u8 = 15;
+
+    // The channel, bit offset, and bit length for each sample.
+    let (color_model, channels, sample_type): (_, &[(u8, u16, u8)], _) = match format {
+        ImageFormat::R8Unorm => (ColorModel::RGBSDA, &[(R, 0, 8)], SampleType::Unorm),
+        ImageFormat::R8Snorm => (ColorModel::RGBSDA, &[(R, 0, 8)], SampleType::Snorm),
+        ImageFormat::Rg8Unorm => (
+            ColorModel::RGBSDA,
+            &[(R, 0, 8), (G, 8, 8)],
+            SampleType::Unorm,
+        ),
+        ImageFormat::Rg8Snorm => (
+            ColorModel::RGBSDA,
+            &[(R, 0, 8), (G, 8, 8)],
+            SampleType::Snorm,
+        ),
+        ImageFormat::Rgba8Unorm | ImageFormat::Rgba8UnormSrgb | ImageFormat::Rgbx8Unorm => (
+            ColorModel::RGBSDA,
+            &[(R, 0, 8), (G, 8, 8), (B, 16, 8), (A, 24, 8)],
+            SampleType::Unorm,
+        ),
+        ImageFormat::Rgba16Float => (
+            ColorModel::RGBSDA,
+            &[(R, 0, 16), (G, 16, 16), (B, 32, 16), (A, 48, 16)],
+            SampleType::Sfloat,
+        ),
+        ImageFormat::Rgba32Float => (
+            ColorModel::RGBSDA,
+            &[(R, 0, 32), (G, 32, 32), (B, 64, 32), (A, 96, 32)],
+            SampleType::Sfloat,
+        ),
+        ImageFormat::Rgb16Float => (
+            ColorModel::RGBSDA,
+            &[(R, 0, 16), (G, 16, 16), (B, 32, 16)],
+            SampleType::Sfloat,
+        ),
+        ImageFormat::Rgb9e5Ufloat => return rgb9e5_data_format_descriptor(),
+        ImageFormat::Rg11b10Float => (
+            ColorModel::RGBSDA,
+            &[(R, 0, 11), (G, 11, 11), (B, 22, 10)],
+            SampleType::Ufloat,
+        ),
+        ImageFormat::Rgb8Unorm => (
+            ColorModel::RGBSDA,
+            &[(R, 0, 8), (G, 8, 8), (B, 16, 8)],
+            SampleType::Unorm,
+        ),
+        ImageFormat::Bgr8Unorm => (
+            ColorModel::RGBSDA,
+            &[(B, 0, 8), (G, 8, 8), (R, 16, 8)],
+            SampleType::Unorm,
+        ),
+        ImageFormat::Bgra8Unorm | ImageFormat::Bgra8UnormSrgb | ImageFormat::Bgrx8Unorm => (
+            ColorModel::RGBSDA,
+            &[(B, 0, 8), (G, 8, 8), (R, 16, 8), (A, 24, 8)],
+            SampleType::Unorm,
+        ),
+        ImageFormat::Bgra4Unorm => (
+            ColorModel::RGBSDA,
+            &[(B, 0, 4), (G, 4, 4), (R, 8, 4), (A, 12, 4)],
+            SampleType::Unorm,
+        ),
+        ImageFormat::B5g6r5Unorm => (
+            ColorModel::RGBSDA,
+            &[(B, 0, 5), (G, 5, 6), (R, 11, 5)],
+            SampleType::Unorm,
+        ),
+        ImageFormat::L8Unorm => (ColorModel::RGBSDA, &[(R, 0, 8)], SampleType::Unorm),
+        ImageFormat::A8Unorm => (ColorModel::RGBSDA, &[(A, 0, 8)], SampleType::Unorm),
+        ImageFormat::La8Unorm => (
+            ColorModel::RGBSDA,
+            &[(R, 0, 8), (A, 8, 8)],
+            SampleType::Unorm,
+        ),
+        // The BC1 channel 1 indicates that alpha is present.
+        ImageFormat::BC1RgbaUnorm | ImageFormat::BC1RgbaUnormSrgb => {
+            (ColorModel::BC1A, &[(1, 0, 64)], SampleType::Unorm)
+        }
+        ImageFormat::BC2RgbaUnorm | ImageFormat::BC2RgbaUnormSrgb => (
+            ColorModel::BC2,
+            &[(A, 0, 64), (R, 64, 64)],
+            SampleType::Unorm,
+        ),
+        ImageFormat::BC3RgbaUnorm | ImageFormat::BC3RgbaUnormSrgb | ImageFormat::BC3NormalXgxr => (
+            ColorModel::BC3,
+            &[(A, 0, 64), (R, 64, 64)],
+            SampleType::Unorm,
+        ),
+        ImageFormat::BC4RUnorm => (ColorModel::BC4, &[(R, 0, 64)], SampleType::Unorm),
+        ImageFormat::BC4RSnorm => (ColorModel::BC4, &[(R, 0, 64)], SampleType::Snorm),
+        ImageFormat::BC5RgUnorm => (
+            ColorModel::BC5,
+            &[(R, 0, 64), (G, 64, 64)],
+            SampleType::Unorm,
+        ),
+        ImageFormat::BC5RgSnorm => (
+            ColorModel::BC5,
+            &[(R, 0, 64), (G, 64, 64)],
+            SampleType::Snorm,
+        ),
+        ImageFormat::BC6hRgbUfloat => (ColorModel::BC6H, &[(R, 0, 128)], SampleType::Ufloat),
+        ImageFormat::BC6hRgbSfloat => (ColorModel::BC6H, &[(R, 0, 128)], SampleType::Sfloat),
+        ImageFormat::BC7RgbaUnorm | ImageFormat::BC7RgbaUnormSrgb => {
+            (ColorModel::BC7, &[(R, 0, 128)], SampleType::Unorm)
+        }
+    };
+
+    let is_compressed = format.block_dimensions() != (1, 1, 1);
+    let samples: Vec<_> = channels
+        .iter()
+        .map(|(channel, bit_offset, bit_length)| {
+            let (mut qualifiers, lower, upper) = match sample_type {
+                SampleType::Unorm if is_compressed => (ChannelTypeQualifiers::empty(), 0, u32::MAX),
+                SampleType::Unorm => (ChannelTypeQualifiers::empty(), 0, (1 << bit_length) - 1),
+                SampleType::Snorm if is_compressed => {
+                    (ChannelTypeQualifiers::SIGNED, 0x80000001, 0x7FFFFFFF)
+                }
+                SampleType::Snorm => (
+                    ChannelTypeQualifiers::SIGNED,
+                    (1 - (1i32 << (bit_length - 1))) as u32,
+                    (1 << (bit_length - 1)) - 1,
+                ),
+                SampleType::Ufloat => (ChannelTypeQualifiers::FLOAT, 0, 1.0f32.to_bits()),
+                SampleType::Sfloat => (
+                    ChannelTypeQualifiers::FLOAT | ChannelTypeQualifiers::SIGNED,
+                    (-1.0f32).to_bits(),
+                    1.0f32.to_bits(),
+                ),
+            };
+            // Alpha is always linear even for sRGB formats.
+            if *channel == A && format.is_srgb() {
+                qualifiers |= ChannelTypeQualifiers::LINEAR;
+            }
+            sample(*channel, *bit_offset, *bit_length, qualifiers, lower, upper)
+        })
+        .collect();
+
+    dfd_bytes(format, color_model, &samples)
+}
+
+fn rgb9e5_data_format_descriptor() -> Vec<u8> {
+    // Each channel has a 9 bit mantissa and shares the 5 bit exponent.
+    let samples: Vec<_> = (0..3u8)
+        .flat_map(|channel| {
+            [
+                sample(
+                    channel,
+                    channel as u16 * 9,
+                    9,
+                    ChannelTypeQualifiers::empty(),
+                    0,
+                    8448,
+                ),
+                sample(channel, 27, 5, ChannelTypeQualifiers::EXPONENT, 15, 31),
+            ]
+        })
+        .collect();
+    dfd_bytes(ImageFormat::Rgb9e5Ufloat, ColorModel::RGBSDA, &samples)
+}
+
+fn sample(
+    channel: u8,
+    bit_offset: u16,
+    bit_length: u8,
+    qualifiers: ChannelTypeQualifiers,
+    lower: u32,
+    upper: u32,
+) -> SampleInformation {
+    SampleInformation {
+        bit_offset,
+        bit_length: NonZeroU8::new(bit_length).unwrap(),
+        channel_type: channel,
+        channel_type_qualifiers: qualifiers,
+        sample_positions: [0; 4],
+        lower,
+        upper,
+    }
+}
+
+fn dfd_bytes(
+    format: ImageFormat,
+    color_model: ColorModel,
+    samples: &[SampleInformation],
+) -> Vec<u8> {
+    let (block_width, block_height, block_depth) = format.block_dimensions();
+    let mut bytes_planes = [0; 8];
+    bytes_planes[0] = format.block_size_in_bytes() as u8;
+
+    let header = DfdBlockHeaderBasic {
+        color_model: Some(color_model),
+        color_primaries: Some(ColorPrimaries::BT709),
+        transfer_function: Some(if format.is_srgb() {
+            TransferFunction::SRGB
+        } else {
+            TransferFunction::Linear
+        }),
+        flags: DataFormatFlags::STRAIGHT_ALPHA,
+        texel_block_dimensions: [block_width, block_height, block_depth, 1]
+            .map(|d| NonZeroU8::new(d as u8).unwrap()),
+        bytes_planes,
+    };
+
+    let block_size =
+        DfdHeader::LENGTH + DfdBlockHeaderBasic::LENGTH + samples.len() * SampleInformation::LENGTH;
+
+    // The total size includes the 4 bytes for the size itself.
+    let mut bytes = ((block_size + 4) as u32).to_le_bytes().to_vec();
+    bytes.extend_from_slice(&DfdHeader::BASIC.as_bytes(block_size as u16));
+    bytes.extend_from_slice(&header.as_bytes());
+    for sample in samples {
+        bytes.extend_from_slice(&sample.as_bytes());
+    }
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "strum")]
+    use strum::IntoEnumIterator;
+
+    fn surface(
+        dimensions: (u32, u32, u32),
+        layers: u32,
+        mipmaps: u32,
+        image_format: ImageFormat,
+    ) -> Surface<Vec<u8>> {
+        let size = crate::layout::surface_size(
+            dimensions.0,
+            dimensions.1,
+            dimensions.2,
+            layers,
+            mipmaps,
+            image_format,
+        )
+        .unwrap();
+        Surface {
+            width: dimensions.0,
+            height: dimensions.1,
+            depth: dimensions.2,
+            layers,
+            mipmaps,
+            image_format,
+            data: (0..size).map(|i| (i % 251) as u8).collect(),
+        }
+    }
+
+    fn roundtrip(surface: &Surface<Vec<u8>>) -> Surface<Vec<u8>> {
+        let bytes = surface.to_ktx2().unwrap();
+        let reader = ktx2::Reader::new(&bytes[..]).unwrap();
+        Surface::from_ktx2(&reader).unwrap()
+    }
+
+    #[test]
+    fn ktx2_level_order() {
+        let surface = surface((8, 8, 1), 2, 4, ImageFormat::Rgba8Unorm);
+        let bytes = surface.to_ktx2().unwrap();
+        let reader = ktx2::Reader::new(&bytes[..]).unwrap();
+
+        let header = reader.header();
+        assert_eq!(Some(Format::R8G8B8A8_UNORM), header.format);
+        assert_eq!(
+            (8, 8, 0),
+            (header.pixel_width, header.pixel_height, header.pixel_depth)
+        );
+        assert_eq!(
+            (2, 1, 4),
+            (header.layer_count, header.face_count, header.level_count)
+        );
+
+        // The largest mipmap is stored last in the file.
+        let levels: Vec<_> = reader.levels().collect();
+        assert_eq!(&bytes[bytes.len() - 512..], levels[0].data);
+
+        // Each level contains the mipmap for all layers.
+        for (mipmap, level) in levels.iter().enumerate() {
+            let layer0 = surface.mip_range(0, mipmap as u32).unwrap();
+            let layer1 = surface.mip_range(1, mipmap as u32).unwrap();
+            assert_eq!(
+                [&surface.data[layer0], &surface.data[layer1]].concat(),
+                level.data
+            );
+        }
+    }
+
+    #[test]
+    fn ktx2_roundtrip_2d_mipmaps() {
+        let surface = surface((7, 5, 1), 1, 3, ImageFormat::BC7RgbaUnormSrgb);
+        assert_eq!(surface, roundtrip(&surface));
+    }
+
+    #[test]
+    fn ktx2_roundtrip_array() {
+        let surface = surface((4, 4, 1), 3, 3, ImageFormat::BC1RgbaUnorm);
+        assert_eq!(surface, roundtrip(&surface));
+    }
+
+    #[test]
+    fn ktx2_roundtrip_cube() {
+        let surface = surface((16, 16, 1), 6, 5, ImageFormat::Rgba16Float);
+        let bytes = surface.to_ktx2().unwrap();
+        let header = ktx2::Reader::new(&bytes[..]).unwrap().header();
+        assert_eq!((0, 6), (header.layer_count, header.face_count));
+        assert_eq!(surface, roundtrip(&surface));
+    }
+
+    #[test]
+    fn ktx2_roundtrip_3d() {
+        let surface = surface((4, 4, 8), 1, 4, ImageFormat::R8Unorm);
+        let bytes = surface.to_ktx2().unwrap();
+        let header = ktx2::Reader::new(&bytes[..]).unwrap().header();
+        assert_eq!(8, header.pixel_depth);
+        assert_eq!(surface, roundtrip(&surface));
+    }
+
+    #[test]
+    fn ktx2_roundtrip_1d() {
+        let surface = surface((16, 1, 1), 1, 1, ImageFormat::Rgb16Float);
+        let bytes = surface.to_ktx2_1d().unwrap();
+        let header = ktx2::Reader::new(&bytes[..]).unwrap().header();
+        assert_eq!(0, header.pixel_height);
+        let reader = ktx2::Reader::new(&bytes[..]).unwrap();
+        assert_eq!(surface, Surface::from_ktx2(&reader).unwrap());
+
+        // Surfaces with a height of 1 are still 2D by default.
+        let bytes = surface.to_ktx2().unwrap();
+        let header = ktx2::Reader::new(&bytes[..]).unwrap().header();
+        assert_eq!(1, header.pixel_height);
+        assert_eq!(surface, roundtrip(&surface));
+    }
+
+    #[test]
+    fn ktx2_invalid_layer_count() {
+        let surface = surface((4, 4, 1), 1, 1, ImageFormat::Rgba8Unorm);
+        let mut bytes = surface.to_ktx2().unwrap();
+        for (layer_count, face_count) in [(0x8000_0000u32, 2u32), (0x8000_0000, 6), (1, 3)] {
+            bytes[32..36].copy_from_slice(&layer_count.to_le_bytes());
+            bytes[36..40].copy_from_slice(&face_count.to_le_bytes());
+            let reader = ktx2::Reader::new(&bytes[..]).unwrap();
+            assert_eq!(
+                Err(SurfaceError::InvalidKtx2LayerCount {
+                    layer_count,
+                    face_count
+                }),
+                Surface::from_ktx2(&reader)
+            );
+        }
+    }
+
+    #[test]
+    fn ktx2_type_size_packed() {
+        // Packed formats use the size of the packed data type.
+        for (image_format, expected) in [
+            (ImageFormat::B5g6r5Unorm, 2),
+            (ImageFormat::Rg11b10Float, 4),
+            (ImageFormat::Rgba8Unorm, 1),
+        ] {
+            let bytes = surface((4, 4, 1), 1, 1, image_format).to_ktx2().unwrap();
+            let header = ktx2::Reader::new(&bytes[..]).unwrap().header();
+            assert_eq!(expected, header.type_size);
+        }
+    }
+
+    #[test]
+    fn ktx2_1d_invalid() {
+        let surface = surface((4, 4, 1), 1, 1, ImageFormat::Rgba8Unorm);
+        assert!(matches!(
+            surface.to_ktx2_1d(),
+            Err(CreateKtx2Error::Invalid1dSurface { height: 4, .. })
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "strum")]
+    fn ktx2_roundtrip_all_formats() {
+        for image_format in ImageFormat::iter() {
+            let surface = surface((8, 8, 1), 1, 2, image_format);
+            match vk_format_from_image_format(image_format) {
+                Some(_) => {
+                    let bytes = surface.to_ktx2().unwrap();
+                    let reader = ktx2::Reader::new(&bytes[..]).unwrap();
+                    assert_eq!(1, reader.dfd_blocks().count(), "{image_format:?}");
+                    assert_eq!(surface, Surface::from_ktx2(&reader).unwrap());
+                }
+                None => assert_eq!(
+                    Err(CreateKtx2Error::UnsupportedFormat(image_format)),
+                    surface.to_ktx2()
+                ),
+            }
+        }
+    }
+
+    #[test]
+    fn ktx2_not_enough_data() {
+        let mut surface = surface((4, 4, 1), 2, 1, ImageFormat::Rgba8Unorm);
+        surface.data.truncate(64);
+        assert_eq!(
+            Err(CreateKtx2Error::Surface(
+                SurfaceError::MipmapDataOutOfBounds {
+                    layer: 1,
+                    mipmap: 0
+                }
+            )),
+            surface.to_ktx2()
+        );
+    }
+
+    #[test]
+    fn ktx2_unsupported_format() {
+        let mut bytes = surface((4, 4, 1), 1, 1, ImageFormat::Rgba8Unorm)
+            .to_ktx2()
+            .unwrap();
+        // Change the format to R8G8B8A8_UINT.
+        bytes[12..16].copy_from_slice(&41u32.to_le_bytes());
+        let reader = ktx2::Reader::new(&bytes[..]).unwrap();
+        assert_eq!(
+            Err(SurfaceError::UnsupportedKtx2Format(Format::new(41))),
+            Surface::from_ktx2(&reader)
+        );
+    }
+}
@@ -0,0 +1,264 @@
+//! Functions for calculating the size and offset of surface data.
+//!
+//! These match the layout used by [Surface](crate::Surface) and DDS files
+//! and can be used for other containers with the same layout.
+//! Surfaces use a row-major memory layout like `surface[layer][mipmap][z][y][x]`
+//! with tightly packed mipmaps.
+use crate::{mip_dimension, ImageFormat};
+
+/// The offset in bytes of the 2D data for `layer`, `depth_level`, and `mipmap`
+/// or [None] if the values would overflow, `mipmap` is out of range,
+/// or any of the `block_dimensions` are zero.
+///
+/// The `dimensions` are the width, height, and depth of the base mip level in pixels.
+/// The `block_dimensions` are the width, height, and depth of each block in pixels
+/// like `(4, 4, 1)` for BCN formats or `(1, 1, 1)` for uncompressed formats.
+/// The `block_size_in_bytes` is the size of each block for compressed formats
+/// or the size of each pixel for uncompressed formats.
+/// Each of the array layers has `mipmaps_per_layer` mipmaps.
+///
+/// # Examples
+/// Cube maps store each face as a separate array layer.
+/// ```rust
+/// use image_dds::layout::calculate_offset;
+///
+/// // A 4x4 BC7 cube map with 3 mipmaps uses a single 16 byte block for each mipmap.
+/// // The third face (layer 2) starts after the 3 mipmaps of the first two faces.
+/// let offset = calculate_offset(2, 0, 1, (4, 4, 1), (4, 4, 1), 16, 3);
+/// assert_eq!(Some(16 * 3 * 2 + 16), offset);
+/// ```
+pub fn calculate_offset(
+    layer: u32,
+    depth_level: u32,
+    mipmap: u32,
+    dimensions: (u32, u32, u32),
+    block_dimensions: (u32, u32, u32),
+    block_size_in_bytes: usize,
+    mipmaps_per_layer: u32,
+) -> Option<usize> {
+    // Surfaces typically use a row-major memory layout like surface[layer][mipmap][z][y][x].
+    // Not all mipmaps are the same size, so the offset calculation is slightly more complex.
+    let (width, height, depth) = dimensions;
+    let (block_width, block_height, block_depth) = block_dimensions;
+
+    let mip_sizes = (0..mipmaps_per_layer)
+        .map(|i| {
+            let mip_width = mip_dimension(width, i) as usize;
+            let mip_height = mip_dimension(height, i) as usize;
+            let mip_depth = mip_dimension(depth, i) as usize;
+
+            mip_size(
+                mip_width,
+                mip_height,
+                mip_depth,
+                block_width as usize,
+                block_height as usize,
+                block_depth as usize,
+                block_size_in_bytes,
+            )
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    // Each depth level adds another rounded 2D slice.
+    let mip_width = mip_dimension(width, mipmap) as usize;
+    let mip_height = mip_dimension(height, mipmap) as usize;
+    let mip_size2d = mip_size(
+        mip_width,
+        mip_height,
+        1,
+        block_width as usize,
+        block_height as usize,
+        block_depth as usize,
+        block_size_in_bytes,
+    )?;
+
+    // Assume mipmaps are tightly packed.
+    // This is the case for DDS surface data.
+    let sum = |sizes: &[usize]| sizes.iter().try_fold(0usize, |sum, s| sum.checked_add(*s));
+    let layer_size = sum(&mip_sizes)?;
+
+    // Each layer should have the same number of mipmaps.
+    let layer_offset = (layer as usize).checked_mul(layer_size)?;
+    let mip_offset = sum(mip_sizes.get(0..mipmap as usize)?)?;
+    let depth_offset = mip_size2d.checked_mul(depth_level as usize)?;
+    layer_offset
+        .checked_add(mip_offset)?
+        .checked_add(depth_offset)
+}
+
+/// The size in bytes of a `width` x `height` x `depth` mipmap
+/// or [None] if the size would overflow or any of the block dimensions are zero.
+///
+/// Dimensions are rounded up to whole blocks, so each mipmap has at least one block.
+/// See [calculate_offset] for a description of the block parameters.
+///
+/// # Examples
+/// ```rust
+/// use image_dds::layout::mip_size;
+///
+/// // A 5x5 BC1 mipmap is padded to 8x8 pixels or 2x2 blocks.
+/// assert_eq!(Some(4 * 8), mip_size(5, 5, 1, 4, 4, 1, 8));
+/// ```
+pub fn mip_size(
+    width: usize,
+    height: usize,
+    depth: usize,
+    block_width: usize,
+    block_height: usize,
+    block_depth: usize,
+    block_size_in_bytes: usize,
+) -> Option<usize> {
+    if block_width == 0 || block_height == 0 || block_depth == 0 {
+        return None;
+    }
+    width
+        .div_ceil(block_width)
+        .checked_mul(height.div_ceil(block_height))
+        .and_then(|v| v.checked_mul(depth.div_ceil(block_depth)))
+        .and_then(|v| v.checked_mul(block_size_in_bytes))
+}
+
+/// The size in bytes of the tightly packed data for all `layers` and `mipmaps`
+/// of a surface with the given base mip level dimensions and `format`
+/// or [None] if the size would overflow.
+///
+/// This is the expected length of [Surface::data](crate::Surface::data)
+/// and matches [Surface::expected_data_size](crate::Surface::expected_data_size).
+///
+/// # Examples
+/// ```rust
+/// use image_dds::{layout::surface_size, ImageFormat};
+///
+/// // A 4x4 BC7 cube map with 3 mipmaps uses a single 16 byte block for each mipmap.
+/// let size = surface_size(4, 4, 1, 6, 3, ImageFormat::BC7RgbaUnorm);
+/// assert_eq!(Some(16 * 3 * 6), size);
+/// ```
+pub fn surface_size(
+    width: u32,
+    height: u32,
+    depth: u32,
+    layers: u32,
+    mipmaps: u32,
+    format: ImageFormat,
+) -> Option<usize> {
+    let (block_width, block_height, block_depth) = format.block_dimensions();
+    let layer_size = (0..mipmaps).try_fold(0usize, |size, mipmap| {
+        mip_size(
+            mip_dimension(width, mipmap) as usize,
+            mip_dimension(height, mipmap) as usize,
+            mip_dimension(depth, mipmap) as usize,
+            block_width as usize,
+            block_height as usize,
+            block_depth as usize,
+            format.block_size_in_bytes(),
+        )
+        .and_then(|mip_size| size.checked_add(mip_size))
+    })?;
+    layer_size.checked_mul(layers as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn calculate_offset_layer0_mip0() {
+        assert_eq!(
+            0,
+            calculate_offset(0, 0, 0, (8, 8, 8), (4, 4, 4), 16, 4).unwrap()
+        );
+    }
+
+    #[test]
+    fn calculate_offset_layer0_mip2() {
+        // The sum of the first 2 mipmaps.
+        assert_eq!(
+            128 + 16,
+            calculate_offset(0, 0, 2, (8, 8, 8), (4, 4, 4), 16, 4).unwrap()
+        );
+    }
+
+    #[test]
+    fn calculate_offset_layer2_mip0() {
+        // The sum of the first 2 array layers.
+        // Each mipmap must have at least a full block of data.
+        assert_eq!(
+            (128 + 16 + 16 + 16) * 2,
+            calculate_offset(2, 0, 0, (8, 8, 8), (4, 4, 4), 16, 4).unwrap()
+        );
+    }
+
+    #[test]
+    fn calculate_offset_layer2_mip2() {
+        // The sum of the first two layers and two more mipmaps.
+        // Each mipmap must have at least a full block of data.
+        assert_eq!(
+            (128 + 16 + 16 + 16) * 2 + 128 + 16,
+            calculate_offset(2, 0, 2, (8, 8, 8), (4, 4, 4), 16, 4).unwrap()
+        );
+    }
+
+    #[test]
+    fn calculate_offset_level2() {
+        // Each 2D level is rounded up to 16x16 pixels.
+        assert_eq!(
+            16 * 16 * 2,
+            calculate_offset(0, 2, 0, (15, 15, 15), (4, 4, 4), 16, 1).unwrap()
+        );
+    }
+
+    #[test]
+    fn calculate_offset_level3() {
+        // Each 2D level is 16x16 pixels.
+        assert_eq!(
+            16 * 16 * 3 * 4,
+            calculate_offset(0, 3, 0, (16, 16, 16), (1, 1, 1), 4, 1).unwrap()
+        );
+    }
+
+    #[test]
+    fn calculate_offset_overflow() {
+        assert_eq!(
+            None,
+            calculate_offset(u32::MAX, 0, 0, (65536, 65536, 1), (1, 1, 1), 16, 1)
+        );
+        assert_eq!(
+            None,
+            calculate_offset(0, u32::MAX, 0, (u32::MAX, u32::MAX, 1), (1, 1, 1), 16, 1)
+        );
+    }
+
+    #[test]
+    fn calculate_offset_zero_block_dimensions() {
+        assert_eq!(None, calculate_offset(0, 0, 0, (4, 4, 1), (0, 4, 1), 16, 1));
+        assert_eq!(None, mip_size(4, 4, 1, 4, 4, 0, 16));
+        assert_eq!(None, mip_size(usize::MAX, 1, 1, 4, 4, 1, 16));
+    }
+
+    #[test]
+    fn surface_size_rgba8_mipmaps() {
+        // 4x4, 2x2, and 1x1 mipmaps for 2 layers.
+        assert_eq!(
+            Some((16 + 4 + 1) * 4 * 2),
+            surface_size(4, 4, 1, 2, 3, ImageFormat::Rgba8Unorm)
+        );
+    }
+
+    #[test]
+    fn surface_size_bc1_3d() {
+        // Each depth slice is padded to whole blocks.
+        // The second mipmap is 2x2x1 pixels and uses a single block.
+        assert_eq!(
+            Some(2 * 2 * 3 * 8 + 8),
+            surface_size(5, 5, 3, 1, 2, ImageFormat::BC1RgbaUnorm)
+        );
+    }
+
+    #[test]
+    fn surface_size_overflow() {
+        assert_eq!(
+            None,
+            surface_size(u32::MAX, u32::MAX, u32::MAX, 1, 1, ImageFormat::Rgba32Float)
+        );
+    }
+}
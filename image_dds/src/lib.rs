@@ -37,16 +37,28 @@
 //! The `"ddsfile"` and `"image"` features can then be enabled individually.
 //! The `"encode"` feature is enabled by default but can be disabled
 //! to resolve compilation errors on some targets if not needed.
+//! The `"ktx2"` feature is disabled by default and enables converting to and from KTX2 files
+//! with [Surface::to_ktx2] and [Surface::from_ktx2].
+//! The `"rayon"` feature is disabled by default and enables encoding multiple surfaces in parallel
+//! with [encode_batch]. Rows of blocks for BCn formats are also encoded and decoded in parallel
+//! with identical results to the serial code.
 //!
 //! # Limitations
 //! Not all targets will compile by default due to intel-tex-rs-2 using the Intel ISPC compiler
 //! and lacking precompiled kernels for all targets.
 
 mod bcn;
+pub mod layout;
 mod rgba;
 mod surface;
 
-pub use surface::{Surface, SurfaceRgba32Float, SurfaceRgba8};
+use layout::{calculate_offset, mip_size};
+
+pub use surface::{
+    ChannelStats, GpuLimits, Surface, SurfaceRgba16, SurfaceRgba32Float, SurfaceRgba8,
+};
+
+pub use rgba::swizzle_rgba8;
 
 pub mod error;
 use error::*;
@@ -58,15 +70,28 @@ pub use ddsfile;
 pub use image;
 
 mod decode;
+#[cfg(feature = "timing")]
+pub use decode::Subresource;
+mod phash;
 
 #[cfg(feature = "encode")]
 mod encode;
+#[cfg(feature = "encode")]
+pub use encode::{encode_batch, EncodeStreamingError};
 
 #[cfg(feature = "ddsfile")]
 mod dds;
 #[cfg(feature = "ddsfile")]
 pub use dds::*;
 
+#[cfg(feature = "ktx2")]
+pub use ktx2;
+
+#[cfg(feature = "ktx2")]
+mod ktx;
+#[cfg(feature = "ktx2")]
+pub use ktx::*;
+
 /// The conversion quality when encoding to compressed formats.
 ///
 /// Higher quality settings run significantly slower.
@@ -84,7 +109,7 @@ pub enum Quality {
     Fast,
     /// Normal export speed and quality.
     Normal,
-    /// Slower exports for slightly higher quality.
+    /// Slower exports for higher quality.
     Slow,
 }
 
@@ -111,17 +136,242 @@ pub enum Mipmaps {
     GeneratedAutomatic,
 }
 
-/// Supported image formats for encoding and decoding.
+/// Filters for generating mipmaps from the previous mip level when encoding.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "strum",
+    derive(strum::EnumString, strum::Display, strum::EnumIter)
+)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum MipmapFilter {
+    /// Average each 2x2x2 pixel region.
+    /// This is the fastest filter but produces softer mipmaps.
+    #[default]
+    Box,
+    /// Linear interpolation weighted by distance.
+    Triangle,
+    /// A windowed sinc filter that preserves more detail than [MipmapFilter::Triangle].
+    /// This may produce ringing around sharp edges.
+    Lanczos3,
+}
+
+/// Dithering methods for reducing banding when encoding to low bit depth formats
+/// like [ImageFormat::Bgra4Unorm].
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "strum",
+    derive(strum::EnumString, strum::Display, strum::EnumIter)
+)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum DitherMode {
+    /// Convert each value without dithering.
+    /// This truncates values to the lower bit depth like previous versions.
+    #[default]
+    None,
+    /// Offset values using a repeating 4x4 Bayer matrix before rounding.
+    /// The pattern is stable and does not depend on neighboring pixels.
+    Ordered,
+    /// Diffuse the rounding error of each value to neighboring pixels.
+    /// This produces smoother gradients than [DitherMode::Ordered] with a less regular pattern.
+    FloydSteinberg,
+}
+
+/// The source for an output channel when reordering channels with [swizzle_rgba8].
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "strum",
+    derive(strum::EnumString, strum::Display, strum::EnumIter)
+)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Channel {
+    /// The red channel.
+    R,
+    /// The green channel.
+    G,
+    /// The blue channel.
+    B,
+    /// The alpha channel.
+    A,
+    /// A constant value of `255`.
+    One,
+    /// A constant value of `0`.
+    Zero,
+}
+
+/// Options for how to pad the number of blocks in each row and column for [Surface::repad].
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "strum",
+    derive(strum::EnumString, strum::Display, strum::EnumIter)
+)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum BlockPadding {
+    /// Pad the width and height in blocks to a multiple of the given number of blocks.
+    /// A value of `0` or `1` does not add any blocks.
+    MultipleOfBlocks(u32),
+    /// Pad the width and height in blocks to the next power of two.
+    PowerOfTwo,
+}
+
+impl BlockPadding {
+    fn pad(self, blocks: u32) -> Option<u32> {
+        match self {
+            BlockPadding::MultipleOfBlocks(n) => blocks.div_ceil(n.max(1)).checked_mul(n.max(1)),
+            BlockPadding::PowerOfTwo => blocks.checked_next_power_of_two(),
+        }
+    }
+}
+
+/// Options for how to handle invalid compressed blocks when decoding.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "strum",
+    derive(strum::EnumString, strum::Display, strum::EnumIter)
+)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum InvalidBlockHandling {
+    /// Decode invalid blocks to transparent black.
+    /// This matches the behavior of most GPU hardware.
+    #[default]
+    ZeroFill,
+    /// Return an error for the first invalid block.
+    Error,
+}
+
+/// Options for the blue channel when decoding formats with only red and green channels.
 ///
-/// Not all DDS formats are supported,
-/// but all current variants for [ImageFormat] are supported by some version of DDS.
-#[non_exhaustive]
+/// This applies to [ImageFormat::Rg8Unorm], [ImageFormat::Rg8Snorm],
+/// [ImageFormat::BC5RgUnorm], and [ImageFormat::BC5RgSnorm].
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(
     feature = "strum",
     derive(strum::EnumString, strum::Display, strum::EnumIter)
 )]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum RgBlueDefault {
+    /// Use the zero value for the format.
+    /// This is `0` for unorm formats and `128` for snorm formats when decoding to RGBA8.
+    #[default]
+    Zero,
+    /// Use `128` when decoding to RGBA8 or `0.5` when decoding to RGBAF32.
+    Half,
+    /// Calculate Z for a unit length normal vector from the X and Y values in red and green.
+    ReconstructZ,
+}
+
+/// Additional settings for decoding surfaces.
+///
+/// The default options are used for methods without options like [Surface::decode_rgba8].
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct DecodeOptions {
+    /// How to handle compressed blocks using reserved or invalid encodings like BC7 blocks with no mode.
+    pub on_invalid_block: InvalidBlockHandling,
+    /// The value for the blue channel for formats with only red and green channels.
+    pub rg_blue: RgBlueDefault,
+    /// Decode [ImageFormat::BC4RUnorm] as [ImageFormat::BC4RSnorm] for files with the wrong format.
+    pub interpret_bc4_as_signed: bool,
+    /// Decode [ImageFormat::BC5RgUnorm] as [ImageFormat::BC5RgSnorm] for files with the wrong format.
+    pub interpret_bc5_as_signed: bool,
+    /// Decode BC1 blocks using the 3 color mode to transparent black instead of opaque black.
+    ///
+    /// This is `true` by default. Set to `false` for BC1 data intended as opaque RGB.
+    pub bc1_honor_transparency: bool,
+    /// The maximum number of decoded pixels for all requested layers, depth slices, and mipmaps
+    /// or [None] to disable the limit.
+    ///
+    /// This is checked before allocating any memory to avoid running out of memory
+    /// for untrusted files with very large dimensions in the header.
+    /// The default of `Some(1 << 32)` only rejects surfaces that would decode
+    /// to more than 16 GiB of RGBA8 data.
+    pub max_pixels: Option<u64>,
+    /// Return an error if the data contains a whole number of additional array layers.
+    ///
+    /// Decoding only reads the specified layers, so this can detect surfaces
+    /// with an incorrect layer count like cube maps with `layers` set to `1`.
+    pub check_layer_count: bool,
+}
+
+impl Default for DecodeOptions {
+    fn default() -> Self {
+        Self {
+            on_invalid_block: InvalidBlockHandling::default(),
+            rg_blue: RgBlueDefault::default(),
+            interpret_bc4_as_signed: false,
+            interpret_bc5_as_signed: false,
+            bc1_honor_transparency: true,
+            max_pixels: Some(1 << 32),
+            check_layer_count: false,
+        }
+    }
+}
+
+/// Additional settings for encoding surfaces.
+///
+/// The default options are used for methods without options like [SurfaceRgba32Float::encode].
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct EncodeOptions {
+    /// Replace NaN with `0.0` and clamp infinite values to the largest finite half float
+    /// before encoding to [ImageFormat::BC6hRgbUfloat].
+    /// This is `true` by default since non finite values can produce invalid blocks.
+    pub sanitize_hdr: bool,
+    /// The source channel index for the red, green, blue, and alpha channels of the output.
+    /// The channel index is `0` for red, `1` for green, `2` for blue, and `3` for alpha.
+    ///
+    /// For example, `[2, 1, 0, 3]` swaps the red and blue channels before encoding.
+    /// Single and two channel formats like [ImageFormat::BC4RUnorm] and [ImageFormat::BC5RgUnorm]
+    /// use the first one or two indices, so `[1, 1, 2, 3]` encodes green to BC4
+    /// and `[3, 0, 2, 3]` encodes alpha and red to BC5.
+    /// Channels not used by the output format like alpha for [ImageFormat::Bgr8Unorm] are ignored.
+    /// The default of `[0, 1, 2, 3]` encodes the data unchanged.
+    pub channels: [usize; 4],
+    /// Set the alpha of all pixels to fully opaque before encoding.
+    ///
+    /// This also avoids transparent pixels for [ImageFormat::BC1RgbaUnorm]
+    /// and uses the opaque encoding settings for [ImageFormat::BC7RgbaUnorm].
+    /// Alpha is set after remapping [EncodeOptions::channels].
+    pub force_opaque: bool,
+    /// The filter for generated mipmaps when using [Mipmaps::GeneratedExact]
+    /// or [Mipmaps::GeneratedAutomatic].
+    /// Non power of two dimensions are resampled to the rounded down mipmap dimensions.
+    /// The default is [MipmapFilter::Box].
+    pub mipmap_filter: MipmapFilter,
+    /// The dithering applied to all channels when encoding to [ImageFormat::Bgra4Unorm].
+    /// Other formats are not affected.
+    /// The default is [DitherMode::None].
+    pub dither: DitherMode,
+}
+
+impl Default for EncodeOptions {
+    fn default() -> Self {
+        Self {
+            sanitize_hdr: true,
+            channels: [0, 1, 2, 3],
+            force_opaque: false,
+            mipmap_filter: MipmapFilter::Box,
+            dither: DitherMode::None,
+        }
+    }
+}
+
+/// Supported image formats for encoding and decoding.
+///
+/// Not all DDS formats are supported.
+/// Most variants have an equivalent DXGI or legacy D3D format for DDS,
+/// but [ImageFormat::Rgb16Float] and [ImageFormat::Rgb8Unorm] can't be written to DDS files.
+#[non_exhaustive]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "strum", derive(strum::EnumIter))]
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum ImageFormat {
     R8Unorm,
@@ -132,10 +382,35 @@ pub enum ImageFormat {
     Rgba8UnormSrgb,
     Rgba16Float,
     Rgba32Float,
+    /// Half float RGB without alpha.
+    /// There is no DXGI or D3D format, so this can't be saved to DDS.
+    Rgb16Float,
+    /// Unsigned float RGB with 9-bit mantissas and a shared 5-bit exponent.
+    Rgb9e5Ufloat,
+    /// Unsigned float RGB with 11 bits for red and green and 10 bits for blue.
+    Rg11b10Float,
+    /// RGB8 without alpha.
+    /// There is no DXGI or D3D format, so this can't be saved to DDS.
+    Rgb8Unorm,
     Bgr8Unorm,
     Bgra8Unorm,
     Bgra8UnormSrgb,
     Bgra4Unorm,
+    /// BGRA8 with the alpha channel ignored
+    Bgrx8Unorm,
+    /// RGBA8 with the alpha channel ignored
+    Rgbx8Unorm,
+    /// 16-bit RGB with 5 bits for red and blue and 6 bits for green.
+    /// Blue is stored in the least significant bits like D3D R5G6B5.
+    B5g6r5Unorm,
+    /// Luminance decoded to grayscale RGB with opaque alpha.
+    /// Encoding uses the red channel.
+    L8Unorm,
+    /// Alpha only decoded with black RGB.
+    A8Unorm,
+    /// Luminance and alpha like D3D A8L8.
+    /// Encoding uses the red and alpha channels.
+    La8Unorm,
     /// DXT1
     BC1RgbaUnorm,
     BC1RgbaUnormSrgb,
@@ -145,6 +420,9 @@ pub enum ImageFormat {
     /// DXT5
     BC3RgbaUnorm,
     BC3RgbaUnormSrgb,
+    /// DXT5nm or RXGB normal map with X in alpha and Y in green.
+    /// Decoding reconstructs the normal as RGB with opaque alpha.
+    BC3NormalXgxr,
     /// RGTC1
     BC4RUnorm,
     BC4RSnorm,
@@ -160,6 +438,248 @@ pub enum ImageFormat {
 }
 
 impl ImageFormat {
+    /// All supported formats in the order they are declared.
+    ///
+    /// This is always available and does not depend on the `"strum"` feature.
+    pub const fn all() -> &'static [ImageFormat] {
+        &[
+            ImageFormat::R8Unorm,
+            ImageFormat::R8Snorm,
+            ImageFormat::Rg8Unorm,
+            ImageFormat::Rg8Snorm,
+            ImageFormat::Rgba8Unorm,
+            ImageFormat::Rgba8UnormSrgb,
+            ImageFormat::Rgba16Float,
+            ImageFormat::Rgba32Float,
+            ImageFormat::Rgb16Float,
+            ImageFormat::Rgb9e5Ufloat,
+            ImageFormat::Rg11b10Float,
+            ImageFormat::Rgb8Unorm,
+            ImageFormat::Bgr8Unorm,
+            ImageFormat::Bgra8Unorm,
+            ImageFormat::Bgra8UnormSrgb,
+            ImageFormat::Bgra4Unorm,
+            ImageFormat::Bgrx8Unorm,
+            ImageFormat::Rgbx8Unorm,
+            ImageFormat::B5g6r5Unorm,
+            ImageFormat::L8Unorm,
+            ImageFormat::A8Unorm,
+            ImageFormat::La8Unorm,
+            ImageFormat::BC1RgbaUnorm,
+            ImageFormat::BC1RgbaUnormSrgb,
+            ImageFormat::BC2RgbaUnorm,
+            ImageFormat::BC2RgbaUnormSrgb,
+            ImageFormat::BC3RgbaUnorm,
+            ImageFormat::BC3RgbaUnormSrgb,
+            ImageFormat::BC3NormalXgxr,
+            ImageFormat::BC4RUnorm,
+            ImageFormat::BC4RSnorm,
+            ImageFormat::BC5RgUnorm,
+            ImageFormat::BC5RgSnorm,
+            ImageFormat::BC6hRgbUfloat,
+            ImageFormat::BC6hRgbSfloat,
+            ImageFormat::BC7RgbaUnorm,
+            ImageFormat::BC7RgbaUnormSrgb,
+        ]
+    }
+
+    /// Returns `true` if any RGBA8 data can be encoded to this format
+    /// and decoded back to RGBA8 without any loss in precision.
+    ///
+    /// This is `false` for compressed formats and
+    /// formats with fewer channels or bits per channel like [ImageFormat::Bgra4Unorm].
+    pub fn is_lossless_for_rgba8(&self) -> bool {
+        matches!(
+            self,
+            ImageFormat::Rgba8Unorm
+                | ImageFormat::Rgba8UnormSrgb
+                | ImageFormat::Bgra8Unorm
+                | ImageFormat::Bgra8UnormSrgb
+                | ImageFormat::Rgba16Float
+                | ImageFormat::Rgba32Float
+        )
+    }
+
+    /// Returns `true` if the format stores floating point data like
+    /// [ImageFormat::Rgba16Float] or [ImageFormat::BC6hRgbUfloat].
+    ///
+    /// Float formats can store values outside the range `0.0` to `1.0`
+    /// and should be decoded with [Surface::decode_rgbaf32] to avoid clamping.
+    pub fn is_float(&self) -> bool {
+        matches!(
+            self,
+            ImageFormat::Rgba16Float
+                | ImageFormat::Rgba32Float
+                | ImageFormat::Rgb16Float
+                | ImageFormat::Rgb9e5Ufloat
+                | ImageFormat::Rg11b10Float
+                | ImageFormat::BC6hRgbUfloat
+                | ImageFormat::BC6hRgbSfloat
+        )
+    }
+
+    /// Returns `true` if the format is best decoded with [Surface::decode_rgbaf32]
+    /// instead of [Surface::decode_rgba8].
+    ///
+    /// This includes [float](ImageFormat::is_float) formats and signed normalized formats
+    /// like [ImageFormat::BC5RgSnorm] that decode to the range `-1.0` to `1.0`.
+    pub fn prefers_float(&self) -> bool {
+        self.is_float()
+            || matches!(
+                self,
+                ImageFormat::R8Snorm
+                    | ImageFormat::Rg8Snorm
+                    | ImageFormat::BC4RSnorm
+                    | ImageFormat::BC5RgSnorm
+            )
+    }
+
+    /// The [image::ColorType] of the decoded data for [ImageFormat::prefers_float].
+    ///
+    /// This is [image::ColorType::Rgba32F] if the format prefers float
+    /// and [image::ColorType::Rgba8] otherwise.
+    #[cfg(feature = "image")]
+    pub fn decoded_color_type(&self) -> image::ColorType {
+        if self.prefers_float() {
+            image::ColorType::Rgba32F
+        } else {
+            image::ColorType::Rgba8
+        }
+    }
+
+    /// Returns `true` if surfaces can be encoded to this format.
+    ///
+    /// This is `false` for all formats if the `"encode"` feature is disabled.
+    /// Encoding to [ImageFormat::BC6hRgbSfloat] is not supported
+    /// since the BC6H encoder only produces unsigned blocks.
+    pub fn can_encode(&self) -> bool {
+        cfg!(feature = "encode") && !matches!(self, ImageFormat::BC6hRgbSfloat)
+    }
+
+    /// Returns `true` if surfaces in this format can be decoded to RGBA8 or RGBAF32.
+    ///
+    /// All formats currently support decoding.
+    pub fn can_decode(&self) -> bool {
+        true
+    }
+
+    /// Returns `true` if the color channels are stored with the sRGB transfer function
+    /// like [ImageFormat::BC7RgbaUnormSrgb].
+    ///
+    /// Alpha is always stored as linear.
+    pub fn is_srgb(&self) -> bool {
+        matches!(
+            self,
+            ImageFormat::Rgba8UnormSrgb
+                | ImageFormat::Bgra8UnormSrgb
+                | ImageFormat::BC1RgbaUnormSrgb
+                | ImageFormat::BC2RgbaUnormSrgb
+                | ImageFormat::BC3RgbaUnormSrgb
+                | ImageFormat::BC7RgbaUnormSrgb
+        )
+    }
+
+    /// The name of the variant like `"BC7RgbaUnorm"` used for [Display](std::fmt::Display)
+    /// and [FromStr](std::str::FromStr).
+    ///
+    /// Names are stable and do not depend on the `"strum"` feature.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ImageFormat::R8Unorm => "R8Unorm",
+            ImageFormat::R8Snorm => "R8Snorm",
+            ImageFormat::Rg8Unorm => "Rg8Unorm",
+            ImageFormat::Rg8Snorm => "Rg8Snorm",
+            ImageFormat::Rgba8Unorm => "Rgba8Unorm",
+            ImageFormat::Rgba8UnormSrgb => "Rgba8UnormSrgb",
+            ImageFormat::Rgba16Float => "Rgba16Float",
+            ImageFormat::Rgba32Float => "Rgba32Float",
+            ImageFormat::Rgb16Float => "Rgb16Float",
+            ImageFormat::Rgb9e5Ufloat => "Rgb9e5Ufloat",
+            ImageFormat::Rg11b10Float => "Rg11b10Float",
+            ImageFormat::Rgb8Unorm => "Rgb8Unorm",
+            ImageFormat::Bgr8Unorm => "Bgr8Unorm",
+            ImageFormat::Bgra8Unorm => "Bgra8Unorm",
+            ImageFormat::Bgra8UnormSrgb => "Bgra8UnormSrgb",
+            ImageFormat::Bgra4Unorm => "Bgra4Unorm",
+            ImageFormat::Bgrx8Unorm => "Bgrx8Unorm",
+            ImageFormat::Rgbx8Unorm => "Rgbx8Unorm",
+            ImageFormat::B5g6r5Unorm => "B5g6r5Unorm",
+            ImageFormat::L8Unorm => "L8Unorm",
+            ImageFormat::A8Unorm => "A8Unorm",
+            ImageFormat::La8Unorm => "La8Unorm",
+            ImageFormat::BC1RgbaUnorm => "BC1RgbaUnorm",
+            ImageFormat::BC1RgbaUnormSrgb => "BC1RgbaUnormSrgb",
+            ImageFormat::BC2RgbaUnorm => "BC2RgbaUnorm",
+            ImageFormat::BC2RgbaUnormSrgb => "BC2RgbaUnormSrgb",
+            ImageFormat::BC3RgbaUnorm => "BC3RgbaUnorm",
+            ImageFormat::BC3RgbaUnormSrgb => "BC3RgbaUnormSrgb",
+            ImageFormat::BC3NormalXgxr => "BC3NormalXgxr",
+            ImageFormat::BC4RUnorm => "BC4RUnorm",
+            ImageFormat::BC4RSnorm => "BC4RSnorm",
+            ImageFormat::BC5RgUnorm => "BC5RgUnorm",
+            ImageFormat::BC5RgSnorm => "BC5RgSnorm",
+            ImageFormat::BC6hRgbUfloat => "BC6hRgbUfloat",
+            ImageFormat::BC6hRgbSfloat => "BC6hRgbSfloat",
+            ImageFormat::BC7RgbaUnorm => "BC7RgbaUnorm",
+            ImageFormat::BC7RgbaUnormSrgb => "BC7RgbaUnormSrgb",
+        }
+    }
+
+    /// The common name for the format used by other tools like `"DXT1"` or `"BC7"` for logging or UIs.
+    ///
+    /// Use [ImageFormat::dxgi_name] or [ImageFormat::d3d_name] for the name of the format in DDS files.
+    pub fn dds_label(&self) -> &'static str {
+        match self {
+            ImageFormat::R8Unorm => "R8",
+            ImageFormat::R8Snorm => "R8 SNORM",
+            ImageFormat::Rg8Unorm => "RG8",
+            ImageFormat::Rg8Snorm => "RG8 SNORM",
+            ImageFormat::Rgba8Unorm => "RGBA8",
+            ImageFormat::Rgba8UnormSrgb => "RGBA8 sRGB",
+            ImageFormat::Rgba16Float => "RGBA16F",
+            ImageFormat::Rgba32Float => "RGBA32F",
+            ImageFormat::Rgb16Float => "RGB16F",
+            ImageFormat::Rgb9e5Ufloat => "RGB9E5",
+            ImageFormat::Rg11b10Float => "RG11B10F",
+            ImageFormat::Rgb8Unorm => "RGB8",
+            ImageFormat::Bgr8Unorm => "BGR8",
+            ImageFormat::Bgra8Unorm => "BGRA8",
+            ImageFormat::Bgra8UnormSrgb => "BGRA8 sRGB",
+            ImageFormat::Bgra4Unorm => "BGRA4",
+            ImageFormat::Bgrx8Unorm => "BGRX8",
+            ImageFormat::Rgbx8Unorm => "RGBX8",
+            ImageFormat::B5g6r5Unorm => "R5G6B5",
+            ImageFormat::L8Unorm => "L8",
+            ImageFormat::A8Unorm => "A8",
+            ImageFormat::La8Unorm => "A8L8",
+            ImageFormat::BC1RgbaUnorm => "DXT1",
+            ImageFormat::BC1RgbaUnormSrgb => "DXT1 sRGB",
+            ImageFormat::BC2RgbaUnorm => "DXT3",
+            ImageFormat::BC2RgbaUnormSrgb => "DXT3 sRGB",
+            ImageFormat::BC3RgbaUnorm => "DXT5",
+            ImageFormat::BC3RgbaUnormSrgb => "DXT5 sRGB",
+            ImageFormat::BC3NormalXgxr => "DXT5nm",
+            ImageFormat::BC4RUnorm => "BC4",
+            ImageFormat::BC4RSnorm => "BC4 SNORM",
+            ImageFormat::BC5RgUnorm => "BC5",
+            ImageFormat::BC5RgSnorm => "BC5 SNORM",
+            ImageFormat::BC6hRgbUfloat => "BC6H UF16",
+            ImageFormat::BC6hRgbSfloat => "BC6H SF16",
+            ImageFormat::BC7RgbaUnorm => "BC7",
+            ImageFormat::BC7RgbaUnormSrgb => "BC7 sRGB",
+        }
+    }
+
+    /// Returns `true` if both formats use the same block dimensions and bytes per block.
+    ///
+    /// This only checks the byte layout and not how the bytes are interpreted.
+    /// Layout compatible formats like [ImageFormat::BC1RgbaUnorm] and [ImageFormat::BC4RUnorm]
+    /// can reuse the same data without changing its size but may not produce meaningful results.
+    pub fn is_layout_compatible(&self, other: &ImageFormat) -> bool {
+        self.block_dimensions() == other.block_dimensions()
+            && self.block_size_in_bytes() == other.block_size_in_bytes()
+    }
+
     // TODO: Is it worth making these public?
     fn block_dimensions(&self) -> (u32, u32, u32) {
         match self {
@@ -169,6 +689,7 @@ impl ImageFormat {
             ImageFormat::BC2RgbaUnormSrgb => (4, 4, 1),
             ImageFormat::BC3RgbaUnorm => (4, 4, 1),
             ImageFormat::BC3RgbaUnormSrgb => (4, 4, 1),
+            ImageFormat::BC3NormalXgxr => (4, 4, 1),
             ImageFormat::BC4RUnorm => (4, 4, 1),
             ImageFormat::BC4RSnorm => (4, 4, 1),
             ImageFormat::BC5RgUnorm => (4, 4, 1),
@@ -185,9 +706,19 @@ impl ImageFormat {
             ImageFormat::Rgba8UnormSrgb => (1, 1, 1),
             ImageFormat::Rgba16Float => (1, 1, 1),
             ImageFormat::Rgba32Float => (1, 1, 1),
+            ImageFormat::Rgb16Float => (1, 1, 1),
+            ImageFormat::Rgb9e5Ufloat => (1, 1, 1),
+            ImageFormat::Rg11b10Float => (1, 1, 1),
             ImageFormat::Bgra8Unorm => (1, 1, 1),
             ImageFormat::Bgra8UnormSrgb => (1, 1, 1),
             ImageFormat::Bgra4Unorm => (1, 1, 1),
+            ImageFormat::Bgrx8Unorm => (1, 1, 1),
+            ImageFormat::Rgbx8Unorm => (1, 1, 1),
+            ImageFormat::B5g6r5Unorm => (1, 1, 1),
+            ImageFormat::L8Unorm => (1, 1, 1),
+            ImageFormat::A8Unorm => (1, 1, 1),
+            ImageFormat::La8Unorm => (1, 1, 1),
+            ImageFormat::Rgb8Unorm => (1, 1, 1),
             ImageFormat::Bgr8Unorm => (1, 1, 1),
         }
     }
@@ -203,6 +734,9 @@ impl ImageFormat {
             ImageFormat::Rgba8UnormSrgb => 4,
             ImageFormat::Rgba16Float => 8,
             ImageFormat::Rgba32Float => 16,
+            ImageFormat::Rgb16Float => 6,
+            ImageFormat::Rgb9e5Ufloat => 4,
+            ImageFormat::Rg11b10Float => 4,
             ImageFormat::Bgra8Unorm => 4,
             ImageFormat::Bgra8UnormSrgb => 4,
             ImageFormat::BC1RgbaUnorm => 8,
@@ -211,6 +745,7 @@ impl ImageFormat {
             ImageFormat::BC2RgbaUnormSrgb => 16,
             ImageFormat::BC3RgbaUnorm => 16,
             ImageFormat::BC3RgbaUnormSrgb => 16,
+            ImageFormat::BC3NormalXgxr => 16,
             ImageFormat::BC4RUnorm => 8,
             ImageFormat::BC4RSnorm => 8,
             ImageFormat::BC5RgUnorm => 16,
@@ -220,11 +755,110 @@ impl ImageFormat {
             ImageFormat::BC7RgbaUnorm => 16,
             ImageFormat::BC7RgbaUnormSrgb => 16,
             ImageFormat::Bgra4Unorm => 2,
+            ImageFormat::Bgrx8Unorm => 4,
+            ImageFormat::Rgbx8Unorm => 4,
+            ImageFormat::B5g6r5Unorm => 2,
+            ImageFormat::L8Unorm => 1,
+            ImageFormat::A8Unorm => 1,
+            ImageFormat::La8Unorm => 2,
+            ImageFormat::Rgb8Unorm => 3,
             ImageFormat::Bgr8Unorm => 3,
         }
     }
 }
 
+impl std::fmt::Display for ImageFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl std::str::FromStr for ImageFormat {
+    type Err = ParseImageFormatError;
+
+    /// Parse the [name](ImageFormat::name) of a format like `"BC7RgbaUnorm"`.
+    ///
+    /// Matching ignores case as well as `'-'` and `'_'`,
+    /// so `"bc7-rgba-unorm"` and `"BC7_RGBA_UNORM"` are also accepted.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalize = |s: &str| {
+            s.chars()
+                .filter(|c| *c != '-' && *c != '_')
+                .map(|c| c.to_ascii_lowercase())
+                .collect::<String>()
+        };
+        let name = normalize(s);
+        ImageFormat::all()
+            .iter()
+            .find(|f| normalize(f.name()) == name)
+            .copied()
+            .ok_or_else(|| ParseImageFormatError {
+                name: s.to_string(),
+            })
+    }
+}
+
+/// A relative estimate of the work required to encode a surface.
+///
+/// Costs are only meaningful when compared with other costs
+/// and do not correspond to any specific unit of time.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub struct EncodeCost(pub u64);
+
+/// Estimate the relative cost of encoding a `width` x `height` surface with `mipmaps` to `format`.
+///
+/// This is a heuristic based on the number of blocks and the selected format and quality
+/// and is intended for displaying progress or expected durations in applications.
+pub fn estimate_encode_cost(
+    width: u32,
+    height: u32,
+    mipmaps: u32,
+    format: ImageFormat,
+    quality: Quality,
+) -> EncodeCost {
+    let (block_width, block_height, _) = format.block_dimensions();
+    let blocks: u64 = (0..mipmaps.max(1))
+        .map(|mipmap| {
+            let mip_width = mip_dimension(width, mipmap) as usize;
+            let mip_height = mip_dimension(height, mipmap) as usize;
+            (div_round_up(mip_width, block_width as usize)
+                * div_round_up(mip_height, block_height as usize)) as u64
+        })
+        .sum();
+
+    // Factors are rough relative costs per block for the compressors.
+    let format_factor = match format {
+        ImageFormat::BC1RgbaUnorm
+        | ImageFormat::BC1RgbaUnormSrgb
+        | ImageFormat::BC4RUnorm
+        | ImageFormat::BC4RSnorm => 16,
+        ImageFormat::BC2RgbaUnorm
+        | ImageFormat::BC2RgbaUnormSrgb
+        | ImageFormat::BC3RgbaUnorm
+        | ImageFormat::BC3RgbaUnormSrgb
+        | ImageFormat::BC3NormalXgxr
+        | ImageFormat::BC5RgUnorm
+        | ImageFormat::BC5RgSnorm => 32,
+        ImageFormat::BC6hRgbUfloat | ImageFormat::BC6hRgbSfloat => 256,
+        ImageFormat::BC7RgbaUnorm | ImageFormat::BC7RgbaUnormSrgb => 256,
+        // Uncompressed formats only convert each pixel.
+        _ => 1,
+    };
+
+    // Quality has no effect for uncompressed formats.
+    let quality_factor = if format.block_dimensions() == (1, 1, 1) {
+        1
+    } else {
+        match quality {
+            Quality::Fast => 1,
+            Quality::Normal => 4,
+            Quality::Slow => 16,
+        }
+    };
+
+    EncodeCost(blocks.saturating_mul(format_factor * quality_factor))
+}
+
 fn max_mipmap_count(max_dimension: u32) -> u32 {
     // log2(x) + 1
     u32::BITS - max_dimension.leading_zeros()
@@ -233,18 +867,28 @@ fn max_mipmap_count(max_dimension: u32) -> u32 {
 /// The reduced value for `base_dimension` at level `mipmap`.
 pub fn mip_dimension(base_dimension: u32, mipmap: u32) -> u32 {
     // Halve for each mip level.
-    (base_dimension >> mipmap).max(1)
+    // Levels past the number of bits are always 1 instead of overflowing.
+    base_dimension.checked_shr(mipmap).unwrap_or(0).max(1)
 }
 
 // TODO: Is this the best way to handle this?
 trait Pixel: Default + Copy {
+    /// The value for a normalized value of `1.0`.
+    const MAX: f32;
+
     fn from_f32(f: f32) -> Self;
     fn to_f32(&self) -> f32;
+
+    /// `true` if the color channels for sRGB formats are already converted to linear.
+    const LINEAR: bool = false;
 }
 
 impl Pixel for u8 {
+    const MAX: f32 = 255.0;
+
     fn from_f32(f: f32) -> Self {
-        f as Self
+        // Round to nearest to match float_to_unorm8.
+        f.round() as Self
     }
 
     fn to_f32(&self) -> f32 {
@@ -253,6 +897,9 @@ impl Pixel for u8 {
 }
 
 impl Pixel for f32 {
+    const MAX: f32 = 1.0;
+    const LINEAR: bool = true;
+
     fn from_f32(f: f32) -> Self {
         f
     }
@@ -262,15 +909,16 @@ impl Pixel for f32 {
     }
 }
 
+#[cfg(feature = "encode")]
 fn downsample_rgba<T: Pixel>(
-    new_width: usize,
-    new_height: usize,
-    new_depth: usize,
-    width: usize,
-    height: usize,
-    depth: usize,
+    new_dimensions: [usize; 3],
+    dimensions: [usize; 3],
     data: &[T],
+    srgb: bool,
 ) -> Vec<T> {
+    let [new_width, new_height, new_depth] = new_dimensions;
+    let [width, height, depth] = dimensions;
+
     // Halve the width and height by averaging pixels.
     // This is faster than resizing using the image crate.
     let mut new_data = vec![T::default(); new_width * new_height * new_depth * 4];
@@ -282,6 +930,10 @@ fn downsample_rgba<T: Pixel>(
                 // Average a 2x2x2 pixel region from data into a 1x1x1 pixel region.
                 // This is equivalent to a 3D convolution or pooling operation over the pixels.
                 for c in 0..4 {
+                    // Average sRGB color channels in linear space to avoid darkening.
+                    // Alpha is always linear.
+                    let is_srgb = srgb && c < 3;
+
                     let mut sum = 0.0;
                     let mut count = 0u64;
                     for z2 in 0..2 {
@@ -296,7 +948,12 @@ fn downsample_rgba<T: Pixel>(
                                             let index = (sampled_z * width * height)
                                                 + (sampled_y * width)
                                                 + sampled_x;
-                                            sum += data[index * 4 + c].to_f32();
+                                            let value = data[index * 4 + c].to_f32();
+                                            sum += if is_srgb {
+                                                srgb_to_linear(value / T::MAX)
+                                            } else {
+                                                value
+                                            };
                                             count += 1;
                                         }
                                     }
@@ -304,7 +961,12 @@ fn downsample_rgba<T: Pixel>(
                             }
                         }
                     }
-                    new_data[new_index * 4 + c] = T::from_f32(sum / count.max(1) as f32);
+                    let average = sum / count.max(1) as f32;
+                    new_data[new_index * 4 + c] = T::from_f32(if is_srgb {
+                        linear_to_srgb(average) * T::MAX
+                    } else {
+                        average
+                    });
                 }
             }
         }
@@ -313,6 +975,125 @@ fn downsample_rgba<T: Pixel>(
     new_data
 }
 
+#[cfg(feature = "encode")]
+fn resample_rgba<T: Pixel>(
+    new_dimensions: [usize; 3],
+    dimensions: [usize; 3],
+    data: &[T],
+    filter: MipmapFilter,
+    srgb: bool,
+) -> Vec<T> {
+    // Resample sRGB color channels in linear space to avoid darkening.
+    // Alpha is always linear.
+    let is_srgb = |i: usize| srgb && i % 4 < 3;
+
+    // The filters are separable, so each dimension can be resampled independently.
+    let mut resampled: Vec<_> = data
+        .iter()
+        .enumerate()
+        .map(|(i, v)| {
+            if is_srgb(i) {
+                srgb_to_linear(v.to_f32() / T::MAX)
+            } else {
+                v.to_f32()
+            }
+        })
+        .collect();
+    let mut current = dimensions;
+    for axis in 0..3 {
+        resampled = resample_axis(&resampled, current, axis, new_dimensions[axis], filter);
+        current[axis] = new_dimensions[axis];
+    }
+    resampled
+        .into_iter()
+        .enumerate()
+        .map(|(i, v)| {
+            if is_srgb(i) {
+                T::from_f32(linear_to_srgb(v) * T::MAX)
+            } else {
+                T::from_f32(v)
+            }
+        })
+        .collect()
+}
+
+#[cfg(feature = "encode")]
+fn resample_axis(
+    data: &[f32],
+    dimensions: [usize; 3],
+    axis: usize,
+    new_size: usize,
+    filter: MipmapFilter,
+) -> Vec<f32> {
+    let mut new_dimensions = dimensions;
+    new_dimensions[axis] = new_size;
+    let [width, height, _] = dimensions;
+    let [new_width, new_height, new_depth] = new_dimensions;
+
+    let weights = filter_weights(dimensions[axis], new_size, filter);
+
+    let mut new_data = vec![0.0; new_width * new_height * new_depth * 4];
+    for z in 0..new_depth {
+        for y in 0..new_height {
+            for x in 0..new_width {
+                let new_index = ((z * new_height + y) * new_width + x) * 4;
+                let mut position = [x, y, z];
+                let (start, pixel_weights) = &weights[position[axis]];
+                for (i, weight) in pixel_weights.iter().enumerate() {
+                    position[axis] = start + i;
+                    let [x2, y2, z2] = position;
+                    let index = ((z2 * height + y2) * width + x2) * 4;
+                    for c in 0..4 {
+                        new_data[new_index + c] += weight * data[index + c];
+                    }
+                }
+            }
+        }
+    }
+    new_data
+}
+
+#[cfg(feature = "encode")]
+fn filter_weights(size: usize, new_size: usize, filter: MipmapFilter) -> Vec<(usize, Vec<f32>)> {
+    let (support, kernel): (f32, fn(f32) -> f32) = match filter {
+        MipmapFilter::Box => (0.5, |x| if x.abs() <= 0.5 { 1.0 } else { 0.0 }),
+        MipmapFilter::Triangle => (1.0, |x| (1.0 - x.abs()).max(0.0)),
+        MipmapFilter::Lanczos3 => (3.0, |x| sinc(x) * sinc(x / 3.0)),
+    };
+
+    // Widen the filter when downsampling to cover all input pixels.
+    let ratio = size as f32 / new_size as f32;
+    let scale = ratio.max(1.0);
+
+    (0..new_size)
+        .map(|i| {
+            let center = (i as f32 + 0.5) * ratio;
+            let start = (center - support * scale).floor().max(0.0) as usize;
+            let end = ((center + support * scale).ceil() as usize).min(size);
+
+            // Normalize the weights since the filter may be cut off at the edges.
+            let mut weights: Vec<_> = (start..end)
+                .map(|j| kernel((j as f32 + 0.5 - center) / scale))
+                .collect();
+            let sum: f32 = weights.iter().sum();
+            if sum != 0.0 {
+                weights.iter_mut().for_each(|w| *w /= sum);
+            }
+            (start, weights)
+        })
+        .collect()
+}
+
+#[cfg(feature = "encode")]
+fn sinc(x: f32) -> f32 {
+    if x == 0.0 {
+        1.0
+    } else {
+        let x = x * std::f32::consts::PI;
+        x.sin() / x
+    }
+}
+
 #[inline(always)]
 fn div_round_up(x: usize, d: usize) -> usize {
     (x + d - 1) / d
@@ -323,77 +1104,6 @@ fn round_up(x: usize, n: usize) -> usize {
     ((x + n - 1) / n) * n
 }
 
-fn calculate_offset(
-    layer: u32,
-    depth_level: u32,
-    mipmap: u32,
-    dimensions: (u32, u32, u32),
-    block_dimensions: (u32, u32, u32),
-    block_size_in_bytes: usize,
-    mipmaps_per_layer: u32,
-) -> Option<usize> {
-    // Surfaces typically use a row-major memory layout like surface[layer][mipmap][z][y][x].
-    // Not all mipmaps are the same size, so the offset calculation is slightly more complex.
-    let (width, height, depth) = dimensions;
-    let (block_width, block_height, block_depth) = block_dimensions;
-
-    let mip_sizes = (0..mipmaps_per_layer)
-        .map(|i| {
-            let mip_width = mip_dimension(width, i) as usize;
-            let mip_height = mip_dimension(height, i) as usize;
-            let mip_depth = mip_dimension(depth, i) as usize;
-
-            mip_size(
-                mip_width,
-                mip_height,
-                mip_depth,
-                block_width as usize,
-                block_height as usize,
-                block_depth as usize,
-                block_size_in_bytes,
-            )
-        })
-        .collect::<Option<Vec<_>>>()?;
-
-    // Each depth level adds another rounded 2D slice.
-    let mip_width = mip_dimension(width, mipmap) as usize;
-    let mip_height = mip_dimension(height, mipmap) as usize;
-    let mip_size2d = mip_size(
-        mip_width,
-        mip_height,
-        1,
-        block_width as usize,
-        block_height as usize,
-        block_depth as usize,
-        block_size_in_bytes,
-    )?;
-
-    // Assume mipmaps are tightly packed.
-    // This is the case for DDS surface data.
-    let layer_size: usize = mip_sizes.iter().sum();
-
-    // Each layer should have the same number of mipmaps.
-    let layer_offset = layer as usize * layer_size;
-    let mip_offset: usize = mip_sizes.get(0..mipmap as usize)?.iter().sum();
-    let depth_offset = mip_size2d * depth_level as usize;
-    Some(layer_offset + mip_offset + depth_offset)
-}
-
-fn mip_size(
-    width: usize,
-    height: usize,
-    depth: usize,
-    block_width: usize,
-    block_height: usize,
-    block_depth: usize,
-    block_size_in_bytes: usize,
-) -> Option<usize> {
-    div_round_up(width, block_width)
-        .checked_mul(div_round_up(height, block_height))
-        .and_then(|v| v.checked_mul(div_round_up(depth, block_depth)))
-        .and_then(|v| v.checked_mul(block_size_in_bytes))
-}
-
 fn snorm_to_unorm(x: u8) -> u8 {
     // Validated against decoding R8Snorm DDS with GPU and paint.net (DirectXTex).
     if x < 128 {
@@ -420,14 +1130,96 @@ fn snorm_to_float(x: u8) -> f32 {
     ((x as i8) as f32 / 127.0).max(-1.0)
 }
 
+fn float_to_unorm8(x: f32) -> u8 {
+    // Round half up for consistent results when converting from float formats.
+    // Casts saturate, so values outside 0.0 to 1.0 are clamped and NaN is 0.
+    (x * 255.0).round() as u8
+}
+
 fn float_to_snorm(x: f32) -> i8 {
     ((x.clamp(-1.0, 1.0)) * 127.0).round() as i8
 }
 
+fn srgb_to_linear(x: f32) -> f32 {
+    if x <= 0.04045 {
+        x / 12.92
+    } else {
+        ((x + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+#[cfg(feature = "encode")]
+fn linear_to_srgb(x: f32) -> f32 {
+    if x <= 0.0031308 {
+        x * 12.92
+    } else {
+        1.055 * x.powf(1.0 / 2.4) - 0.055
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn block_padding_overflow() {
+        assert_eq!(Some(6), BlockPadding::MultipleOfBlocks(3).pad(5));
+        assert_eq!(None, BlockPadding::MultipleOfBlocks(2).pad(u32::MAX));
+        assert_eq!(Some(8), BlockPadding::PowerOfTwo.pad(5));
+        assert_eq!(None, BlockPadding::PowerOfTwo.pad(u32::MAX));
+    }
+
+    #[test]
+    fn float_to_unorm8_rounding() {
+        assert_eq!(0, float_to_unorm8(0.0));
+        assert_eq!(255, float_to_unorm8(1.0));
+        assert_eq!(64, float_to_unorm8(0.25));
+        assert_eq!(128, float_to_unorm8(0.5));
+        assert_eq!(0, float_to_unorm8(-0.5));
+        assert_eq!(255, float_to_unorm8(2.0));
+        assert_eq!(255, float_to_unorm8(f32::INFINITY));
+        assert_eq!(0, float_to_unorm8(f32::NAN));
+    }
+
+    #[test]
+    fn float_to_unorm8_all_values() {
+        // Every value should survive a round trip through each float type.
+        for u in 0..=255u8 {
+            let f = u as f32 / 255.0;
+            assert_eq!(u, float_to_unorm8(f));
+            assert_eq!(u, float_to_unorm8(half::f16::from_f32(f).to_f32()));
+        }
+    }
+
+    #[test]
+    fn pixel_u8_from_f32_rounding() {
+        assert_eq!(0u8, u8::from_f32(0.49));
+        assert_eq!(2u8, u8::from_f32(1.5));
+        assert_eq!(2u8, u8::from_f32(2.49));
+        assert_eq!(255u8, u8::from_f32(254.5));
+        assert_eq!(255u8, u8::from_f32(300.0));
+        assert_eq!(0u8, u8::from_f32(-1.0));
+    }
+
+    #[cfg(feature = "strum")]
+    #[test]
+    fn image_format_all_matches_iter() {
+        use strum::IntoEnumIterator;
+
+        assert_eq!(
+            ImageFormat::iter().collect::<Vec<_>>(),
+            ImageFormat::all().to_vec()
+        );
+    }
+
+    #[test]
+    fn dds_label_unique() {
+        let mut labels: Vec<_> = ImageFormat::all().iter().map(|f| f.dds_label()).collect();
+        labels.sort();
+        labels.dedup();
+        assert_eq!(ImageFormat::all().len(), labels.len());
+    }
+
     #[test]
     fn max_mipmap_count_zero() {
         assert_eq!(0, max_mipmap_count(0));
@@ -443,6 +1235,153 @@ mod tests {
         assert_eq!(4, max_mipmap_count(12));
     }
 
+    #[test]
+    fn estimate_encode_cost_bc7_bc1() {
+        let bc7 = estimate_encode_cost(256, 256, 9, ImageFormat::BC7RgbaUnorm, Quality::Slow);
+        let bc1 = estimate_encode_cost(256, 256, 9, ImageFormat::BC1RgbaUnorm, Quality::Fast);
+        assert!(bc7 > bc1);
+    }
+
+    #[test]
+    fn estimate_encode_cost_mipmaps() {
+        let base = estimate_encode_cost(256, 256, 1, ImageFormat::BC7RgbaUnorm, Quality::Fast);
+        let mipmaps = estimate_encode_cost(256, 256, 9, ImageFormat::BC7RgbaUnorm, Quality::Fast);
+        assert!(mipmaps > base);
+    }
+
+    #[test]
+    fn estimate_encode_cost_uncompressed_quality() {
+        assert_eq!(
+            estimate_encode_cost(4, 4, 1, ImageFormat::Rgba8Unorm, Quality::Fast),
+            estimate_encode_cost(4, 4, 1, ImageFormat::Rgba8Unorm, Quality::Slow)
+        );
+    }
+
+    #[test]
+    fn is_srgb_formats() {
+        assert!(ImageFormat::BC7RgbaUnormSrgb.is_srgb());
+        assert!(ImageFormat::Bgra8UnormSrgb.is_srgb());
+        assert!(!ImageFormat::BC7RgbaUnorm.is_srgb());
+        assert!(!ImageFormat::Rgba16Float.is_srgb());
+    }
+
+    #[test]
+    fn can_encode_decode_formats() {
+        assert_eq!(
+            cfg!(feature = "encode"),
+            ImageFormat::BC6hRgbUfloat.can_encode()
+        );
+        assert!(!ImageFormat::BC6hRgbSfloat.can_encode());
+        assert!(ImageFormat::all().iter().all(|f| f.can_decode()));
+    }
+
+    #[test]
+    fn is_float_formats() {
+        assert!(ImageFormat::BC6hRgbUfloat.is_float());
+        assert!(ImageFormat::BC6hRgbSfloat.is_float());
+        assert!(ImageFormat::Rgba32Float.is_float());
+        assert!(!ImageFormat::Rgba8Unorm.is_float());
+        assert!(!ImageFormat::BC7RgbaUnorm.is_float());
+    }
+
+    #[test]
+    fn image_format_name_roundtrip() {
+        for format in ImageFormat::all() {
+            assert_eq!(Ok(*format), format.to_string().parse());
+        }
+    }
+
+    #[test]
+    fn image_format_from_str() {
+        assert_eq!(Ok(ImageFormat::BC7RgbaUnorm), "BC7RgbaUnorm".parse());
+        assert_eq!(Ok(ImageFormat::BC7RgbaUnorm), "bc7-rgba-unorm".parse());
+        assert_eq!(Ok(ImageFormat::BC6hRgbUfloat), "BC6H_RGB_UFLOAT".parse());
+        assert_eq!(
+            Err(ParseImageFormatError {
+                name: "BC8".to_string()
+            }),
+            "BC8".parse::<ImageFormat>()
+        );
+    }
+
+    #[test]
+    fn prefers_float_formats() {
+        assert!(ImageFormat::BC6hRgbUfloat.prefers_float());
+        assert!(ImageFormat::Rg11b10Float.prefers_float());
+        assert!(ImageFormat::BC5RgSnorm.prefers_float());
+        assert!(ImageFormat::R8Snorm.prefers_float());
+        assert!(!ImageFormat::BC5RgUnorm.prefers_float());
+        assert!(!ImageFormat::Rgba8UnormSrgb.prefers_float());
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn decoded_color_type_formats() {
+        assert_eq!(
+            image::ColorType::Rgba32F,
+            ImageFormat::Rgba16Float.decoded_color_type()
+        );
+        assert_eq!(
+            image::ColorType::Rgba8,
+            ImageFormat::BC7RgbaUnorm.decoded_color_type()
+        );
+    }
+
+    #[test]
+    fn layout_compatible_srgb() {
+        assert!(ImageFormat::Rgba8Unorm.is_layout_compatible(&ImageFormat::Rgba8UnormSrgb));
+        assert!(ImageFormat::Bgra8Unorm.is_layout_compatible(&ImageFormat::Bgra8UnormSrgb));
+        assert!(ImageFormat::BC7RgbaUnorm.is_layout_compatible(&ImageFormat::BC7RgbaUnormSrgb));
+    }
+
+    #[test]
+    fn layout_compatible_bc1_bc4() {
+        assert!(ImageFormat::BC1RgbaUnorm.is_layout_compatible(&ImageFormat::BC4RUnorm));
+        assert!(ImageFormat::BC4RSnorm.is_layout_compatible(&ImageFormat::BC1RgbaUnormSrgb));
+    }
+
+    #[test]
+    fn layout_incompatible() {
+        // Same size but different block dimensions.
+        assert!(!ImageFormat::Rgba32Float.is_layout_compatible(&ImageFormat::BC7RgbaUnorm));
+        // Same block dimensions but different size.
+        assert!(!ImageFormat::BC1RgbaUnorm.is_layout_compatible(&ImageFormat::BC3RgbaUnorm));
+        assert!(!ImageFormat::Rgba8Unorm.is_layout_compatible(&ImageFormat::Bgr8Unorm));
+    }
+
+    #[cfg(feature = "encode")]
+    #[test]
+    fn resample_rgba8_identity() {
+        let original: Vec<_> = (0..3 * 2 * 4).map(|i| i as u8 * 10).collect();
+        for filter in [MipmapFilter::Triangle, MipmapFilter::Lanczos3] {
+            assert_eq!(
+                original,
+                resample_rgba([3, 2, 1], [3, 2, 1], &original, filter, false)
+            );
+        }
+    }
+
+    #[cfg(feature = "encode")]
+    #[test]
+    fn resample_rgbaf32_triangle_5x1() {
+        // Odd dimensions round down and weight the center pixel the most.
+        let original: Vec<_> = [0.0f32, 0.0, 3.0, 0.0, 0.0]
+            .iter()
+            .flat_map(|v| [*v; 4])
+            .collect();
+        let resampled = resample_rgba(
+            [2, 1, 1],
+            [5, 1, 1],
+            &original,
+            MipmapFilter::Triangle,
+            false,
+        );
+        assert_eq!(2 * 4, resampled.len());
+        assert!((resampled[0] - resampled[4]).abs() < 1e-5);
+        assert!(resampled[0] > 0.0 && resampled[0] < 3.0);
+    }
+
+    #[cfg(feature = "encode")]
     #[test]
     fn downsample_rgba8_4x4() {
         // Test that a checkerboard is averaged.
@@ -451,11 +1390,23 @@ mod tests {
             .flatten()
             .collect();
         assert_eq!(
-            vec![127u8; 2 * 2 * 1 * 4],
-            downsample_rgba(2, 2, 1, 4, 4, 1, &original)
+            vec![128u8; 2 * 2 * 1 * 4],
+            downsample_rgba([2, 2, 1], [4, 4, 1], &original, false)
+        );
+    }
+
+    #[cfg(feature = "encode")]
+    #[test]
+    fn downsample_rgba8_srgb_4x4() {
+        // Test that a checkerboard is averaged in linear space except for alpha.
+        let original = [0u8, 0u8, 0u8, 0u8, 255u8, 255u8, 255u8, 255u8].repeat(4 * 4 / 2);
+        assert_eq!(
+            [188u8, 188u8, 188u8, 128u8].repeat(2 * 2),
+            downsample_rgba([2, 2, 1], [4, 4, 1], &original, true)
         );
     }
 
+    #[cfg(feature = "encode")]
     #[test]
     fn downsample_rgba8_3x3() {
         // Test that a checkerboard is averaged.
@@ -466,11 +1417,12 @@ mod tests {
         .flatten()
         .collect();
         assert_eq!(
-            vec![127u8; 1 * 1 * 4],
-            downsample_rgba(1, 1, 1, 3, 3, 1, &original)
+            vec![128u8; 1 * 1 * 4],
+            downsample_rgba([1, 1, 1], [3, 3, 1], &original, false)
         );
     }
 
+    #[cfg(feature = "encode")]
     #[test]
     fn downsample_rgba8_2x2x2() {
         // Test that two slices of 2x2 pixels are averaged.
@@ -479,16 +1431,42 @@ mod tests {
             255, 255, 255, 255, 255, 255, 255, 255,
         ];
         assert_eq!(
-            vec![127u8; 1 * 1 * 1 * 4],
-            downsample_rgba(1, 1, 1, 2, 2, 2, &original)
+            vec![128u8; 1 * 1 * 1 * 4],
+            downsample_rgba([1, 1, 1], [2, 2, 2], &original, false)
         );
     }
 
+    #[cfg(feature = "encode")]
     #[test]
     fn downsample_rgba8_0x0() {
-        assert_eq!(vec![0u8; 4], downsample_rgba(1, 1, 1, 0, 0, 1, &[]));
+        assert_eq!(
+            vec![0u8; 4],
+            downsample_rgba([1, 1, 1], [0, 0, 1], &[], false)
+        );
+    }
+
+    #[cfg(feature = "encode")]
+    #[test]
+    fn downsample_rgba8_2x2_integer_rounding() {
+        for (values, expected) in [
+            ([0u8, 0, 0, 1], 0u8),
+            ([0, 0, 1, 1], 1),
+            ([0, 1, 1, 1], 1),
+            ([254, 255, 255, 255], 255),
+            ([1, 2, 2, 2], 2),
+            ([10, 11, 12, 12], 11),
+        ] {
+            let original: Vec<_> = values.iter().flat_map(|v| [*v; 4]).collect();
+            let reference = ((values.iter().map(|v| *v as u32).sum::<u32>() + 2) / 4) as u8;
+            assert_eq!(expected, reference);
+            assert_eq!(
+                vec![expected; 4],
+                downsample_rgba([1, 1, 1], [2, 2, 1], &original, false)
+            );
+        }
     }
 
+    #[cfg(feature = "encode")]
     #[test]
     fn downsample_rgbaf32_4x4() {
         // Test that a checkerboard is averaged.
@@ -500,10 +1478,11 @@ mod tests {
         .collect();
         assert_eq!(
             vec![0.5; 2 * 2 * 1 * 4],
-            downsample_rgba(2, 2, 1, 4, 4, 1, &original)
+            downsample_rgba([2, 2, 1], [4, 4, 1], &original, false)
         );
     }
 
+    #[cfg(feature = "encode")]
     #[test]
     fn downsample_rgbaf32_3x3() {
         // Test that a checkerboard is averaged.
@@ -516,10 +1495,11 @@ mod tests {
         .collect();
         assert_eq!(
             vec![0.5; 1 * 1 * 4],
-            downsample_rgba(1, 1, 1, 3, 3, 1, &original)
+            downsample_rgba([1, 1, 1], [3, 3, 1], &original, false)
         );
     }
 
+    #[cfg(feature = "encode")]
     #[test]
     fn downsample_rgbaf32_2x2x2() {
         // Test that two slices of 2x2 pixels are averaged.
@@ -530,67 +1510,16 @@ mod tests {
         ];
         assert_eq!(
             vec![0.5; 1 * 1 * 1 * 4],
-            downsample_rgba(1, 1, 1, 2, 2, 2, &original)
+            downsample_rgba([1, 1, 1], [2, 2, 2], &original, false)
         );
     }
 
+    #[cfg(feature = "encode")]
     #[test]
     fn downsample_rgbaf32_0x0() {
-        assert_eq!(vec![0.0f32; 4], downsample_rgba(1, 1, 1, 0, 0, 1, &[]));
-    }
-
-    #[test]
-    fn calculate_offset_layer0_mip0() {
-        assert_eq!(
-            0,
-            calculate_offset(0, 0, 0, (8, 8, 8), (4, 4, 4), 16, 4).unwrap()
-        );
-    }
-
-    #[test]
-    fn calculate_offset_layer0_mip2() {
-        // The sum of the first 2 mipmaps.
-        assert_eq!(
-            128 + 16,
-            calculate_offset(0, 0, 2, (8, 8, 8), (4, 4, 4), 16, 4).unwrap()
-        );
-    }
-
-    #[test]
-    fn calculate_offset_layer2_mip0() {
-        // The sum of the first 2 array layers.
-        // Each mipmap must have at least a full block of data.
-        assert_eq!(
-            (128 + 16 + 16 + 16) * 2,
-            calculate_offset(2, 0, 0, (8, 8, 8), (4, 4, 4), 16, 4).unwrap()
-        );
-    }
-
-    #[test]
-    fn calculate_offset_layer2_mip2() {
-        // The sum of the first two layers and two more mipmaps.
-        // Each mipmap must have at least a full block of data.
-        assert_eq!(
-            (128 + 16 + 16 + 16) * 2 + 128 + 16,
-            calculate_offset(2, 0, 2, (8, 8, 8), (4, 4, 4), 16, 4).unwrap()
-        );
-    }
-
-    #[test]
-    fn calculate_offset_level2() {
-        // Each 2D level is rounded up to 16x16 pixels.
-        assert_eq!(
-            16 * 16 * 2,
-            calculate_offset(0, 2, 0, (15, 15, 15), (4, 4, 4), 16, 1).unwrap()
-        );
-    }
-
-    #[test]
-    fn calculate_offset_level3() {
-        // Each 2D level is 16x16 pixels.
         assert_eq!(
-            16 * 16 * 3 * 4,
-            calculate_offset(0, 3, 0, (16, 16, 16), (1, 1, 1), 4, 1).unwrap()
+            vec![0.0f32; 4],
+            downsample_rgba([1, 1, 1], [0, 0, 1], &[], false)
         );
     }
 
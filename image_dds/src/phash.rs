@@ -0,0 +1,171 @@
+use crate::{mip_dimension, Surface, SurfaceError};
+
+// The hash uses the lowest 8x8 frequencies of a 32x32 luminance image.
+const SIZE: usize = 32;
+const HASH_SIZE: usize = 8;
+
+impl<T: AsRef<[u8]>> Surface<T> {
+    /// Calculate a DCT based perceptual hash of the first layer and depth slice.
+    ///
+    /// Visually similar images produce hashes with a small Hamming distance
+    /// even if they use different formats like [crate::ImageFormat::BC1RgbaUnorm]
+    /// and [crate::ImageFormat::BC7RgbaUnorm].
+    /// Compare hashes using `(a ^ b).count_ones()`.
+    pub fn phash(&self) -> Result<u64, SurfaceError> {
+        // Decode the smallest mipmap that still has enough pixels to avoid decoding the full image.
+        let mipmap = (0..self.mipmaps)
+            .rev()
+            .find(|m| {
+                mip_dimension(self.width, *m) as usize >= SIZE
+                    && mip_dimension(self.height, *m) as usize >= SIZE
+            })
+            .unwrap_or_default();
+
+        let rgba8 = self.decode_layers_mipmaps_rgba8(0..1, mipmap..mipmap + 1)?;
+        let width = rgba8.width as usize;
+        let height = rgba8.height as usize;
+
+        let luma = downsample_luma(&rgba8.data[..width * height * 4], width, height);
+        let dct = dct_2d(&luma);
+
+        // Ignore the DC coefficient since it only affects overall brightness.
+        let coefficients: Vec<_> = (0..HASH_SIZE)
+            .flat_map(|v| (0..HASH_SIZE).map(move |u| (u, v)))
+            .map(|(u, v)| dct[v * SIZE + u])
+            .collect();
+        let mut sorted = coefficients[1..].to_vec();
+        sorted.sort_by(f32::total_cmp);
+        let median = sorted[sorted.len() / 2];
+
+        Ok(coefficients
+            .iter()
+            .enumerate()
+            .fold(0u64, |hash, (i, c)| hash | (((*c > median) as u64) << i)))
+    }
+}
+
+fn downsample_luma(rgba8: &[u8], width: usize, height: usize) -> Vec<f32> {
+    // Average the pixels covered by each output pixel.
+    let mut luma = vec![0.0; SIZE * SIZE];
+    for y in 0..SIZE {
+        let y_start = y * height / SIZE;
+        let y_end = ((y + 1) * height / SIZE).max(y_start + 1);
+        for x in 0..SIZE {
+            let x_start = x * width / SIZE;
+            let x_end = ((x + 1) * width / SIZE).max(x_start + 1);
+
+            let mut sum = 0.0;
+            for sy in y_start..y_end {
+                for sx in x_start..x_end {
+                    let i = (sy * width + sx) * 4;
+                    sum += 0.299 * rgba8[i] as f32
+                        + 0.587 * rgba8[i + 1] as f32
+                        + 0.114 * rgba8[i + 2] as f32;
+                }
+            }
+            luma[y * SIZE + x] = sum / ((y_end - y_start) * (x_end - x_start)) as f32;
+        }
+    }
+    luma
+}
+
+fn dct_2d(values: &[f32]) -> Vec<f32> {
+    // Only the lowest frequencies are needed for the hash.
+    let cosines: Vec<_> = (0..HASH_SIZE)
+        .flat_map(|u| {
+            (0..SIZE).map(move |x| {
+                ((2 * x + 1) as f32 * u as f32 * std::f32::consts::PI / (2 * SIZE) as f32).cos()
+            })
+        })
+        .collect();
+
+    let mut dct = vec![0.0; SIZE * SIZE];
+    for v in 0..HASH_SIZE {
+        for u in 0..HASH_SIZE {
+            let mut sum = 0.0;
+            for y in 0..SIZE {
+                for x in 0..SIZE {
+                    sum += values[y * SIZE + x] * cosines[u * SIZE + x] * cosines[v * SIZE + y];
+                }
+            }
+            dct[v * SIZE + u] = sum;
+        }
+    }
+    dct
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "encode")]
+    use crate::{ImageFormat, Mipmaps, Quality, SurfaceRgba8};
+
+    #[cfg(feature = "encode")]
+    fn encoded_phash(data: &[u8], format: ImageFormat) -> u64 {
+        SurfaceRgba8 {
+            width: 64,
+            height: 64,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            data,
+        }
+        .encode(format, Quality::Fast, Mipmaps::GeneratedAutomatic)
+        .unwrap()
+        .phash()
+        .unwrap()
+    }
+
+    #[cfg(feature = "encode")]
+    #[test]
+    fn phash_bc1_bc7() {
+        // A diagonal gradient with a bright square.
+        let gradient: Vec<_> = (0..64 * 64)
+            .flat_map(|i| {
+                let (x, y) = (i % 64, i / 64);
+                let value = if (16..32).contains(&x) && (16..32).contains(&y) {
+                    255
+                } else {
+                    (x + y) as u8
+                };
+                [value, value / 2, 255 - value, 255]
+            })
+            .collect();
+
+        // Horizontal stripes.
+        let stripes: Vec<_> = (0..64 * 64)
+            .flat_map(|i| {
+                let value = if (i / 64 / 8) % 2 == 0 { 0 } else { 255 };
+                [value, value, value, 255]
+            })
+            .collect();
+
+        let bc1 = encoded_phash(&gradient, ImageFormat::BC1RgbaUnorm);
+        let bc7 = encoded_phash(&gradient, ImageFormat::BC7RgbaUnorm);
+        let other = encoded_phash(&stripes, ImageFormat::BC7RgbaUnorm);
+
+        assert!((bc1 ^ bc7).count_ones() <= 4);
+        assert!((bc1 ^ other).count_ones() >= 16);
+    }
+
+    #[test]
+    fn phash_small_surface() {
+        // Surfaces smaller than the hash size repeat pixels.
+        let surface = |width, data: &'static [u8]| Surface {
+            width,
+            height: 1,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: crate::ImageFormat::Rgba8Unorm,
+            data,
+        };
+        assert_eq!(
+            surface(1, &[255u8, 0, 0, 255]).phash().unwrap(),
+            surface(2, &[255u8, 0, 0, 255, 255, 0, 0, 255])
+                .phash()
+                .unwrap()
+        );
+    }
+}
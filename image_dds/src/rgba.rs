@@ -1,7 +1,10 @@
 use bytemuck::{Pod, Zeroable};
 use half::f16;
 
-use crate::{float_to_snorm, snorm_to_float, snorm_to_unorm, unorm_to_snorm, SurfaceError};
+use crate::{
+    float_to_snorm, float_to_unorm8, snorm_to_float, snorm_to_unorm, unorm_to_snorm, Channel,
+    DitherMode, SurfaceError,
+};
 
 #[repr(C)]
 #[derive(Clone, Copy, Pod, Zeroable)]
@@ -15,6 +18,18 @@ pub struct Rgbaf16([f16; 4]);
 #[derive(Clone, Copy, Pod, Zeroable)]
 pub struct Rgbaf32([f32; 4]);
 
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct Rgbf16([f16; 3]);
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct Rgb9e5(u32);
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct Rg11b10(u32);
+
 #[repr(C)]
 #[derive(Clone, Copy, Pod, Zeroable)]
 pub struct R8(u8);
@@ -47,6 +62,30 @@ pub struct Bgra8([u8; 4]);
 #[derive(Clone, Copy, Pod, Zeroable)]
 pub struct Bgra4([u8; 2]);
 
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct Bgrx8([u8; 4]);
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct B5g6r5([u8; 2]);
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct L8(u8);
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct A8(u8);
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct La8([u8; 2]);
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct Rgbx8([u8; 4]);
+
 pub trait Pixel {
     const SIZE: usize;
 
@@ -67,10 +106,14 @@ macro_rules! pixel_impl {
 pixel_impl!(Rg8, 2);
 pixel_impl!(Rg8Snorm, 2);
 pixel_impl!(Bgra4, 2);
+pixel_impl!(B5g6r5, 2);
+pixel_impl!(La8, 2);
 pixel_impl!(Rgb8, 3);
 pixel_impl!(Bgr8, 3);
 pixel_impl!(Rgba8, 4);
 pixel_impl!(Bgra8, 4);
+pixel_impl!(Bgrx8, 4);
+pixel_impl!(Rgbx8, 4);
 
 pub trait ToRgba<T> {
     fn to_rgba(self) -> [T; 4];
@@ -117,7 +160,7 @@ impl Pixel for Rgbaf16 {
 
 impl ToRgba<u8> for Rgbaf16 {
     fn to_rgba(self) -> [u8; 4] {
-        self.0.map(|f| (f.to_f32() * 255.0) as u8)
+        self.0.map(|f| float_to_unorm8(f.to_f32()))
     }
 }
 
@@ -139,6 +182,164 @@ impl FromRgba<f32> for Rgbaf16 {
     }
 }
 
+impl Pixel for Rgbf16 {
+    const SIZE: usize = 6;
+
+    fn get_pixel(data: &[u8], index: usize) -> Self {
+        let bytes = get_pixel::<6, u8>(data, index, Self::SIZE);
+        Self([
+            f16::from_le_bytes(bytes[0..2].try_into().unwrap()),
+            f16::from_le_bytes(bytes[2..4].try_into().unwrap()),
+            f16::from_le_bytes(bytes[4..6].try_into().unwrap()),
+        ])
+    }
+}
+
+impl ToRgba<u8> for Rgbf16 {
+    fn to_rgba(self) -> [u8; 4] {
+        let [r, g, b] = self.0.map(|f| float_to_unorm8(f.to_f32()));
+        [r, g, b, 255u8]
+    }
+}
+
+impl FromRgba<u8> for Rgbf16 {
+    fn from_rgba(rgba: [u8; 4]) -> Self {
+        let [r, g, b, _] = rgba;
+        Self([r, g, b].map(|u| f16::from_f32(u as f32 / 255.0)))
+    }
+}
+
+impl ToRgba<f32> for Rgbf16 {
+    fn to_rgba(self) -> [f32; 4] {
+        let [r, g, b] = self.0.map(f16::to_f32);
+        [r, g, b, 1.0]
+    }
+}
+
+impl FromRgba<f32> for Rgbf16 {
+    fn from_rgba(rgba: [f32; 4]) -> Self {
+        let [r, g, b, _] = rgba;
+        Self([r, g, b].map(f16::from_f32))
+    }
+}
+
+// https://registry.khronos.org/OpenGL/extensions/EXT/EXT_texture_shared_exponent.txt
+const RGB9E5_MANTISSA_BITS: i32 = 9;
+const RGB9E5_EXP_BIAS: i32 = 15;
+const RGB9E5_MAX_EXP: i32 = 31;
+
+impl Pixel for Rgb9e5 {
+    const SIZE: usize = 4;
+
+    fn get_pixel(data: &[u8], index: usize) -> Self {
+        Self(u32::from_le_bytes(get_pixel(data, index, Self::SIZE)))
+    }
+}
+
+impl ToRgba<u8> for Rgb9e5 {
+    fn to_rgba(self) -> [u8; 4] {
+        let [r, g, b, _] = ToRgba::<f32>::to_rgba(self).map(float_to_unorm8);
+        [r, g, b, 255u8]
+    }
+}
+
+impl FromRgba<u8> for Rgb9e5 {
+    fn from_rgba(rgba: [u8; 4]) -> Self {
+        Self::from_rgba(rgba.map(|u| u as f32 / 255.0))
+    }
+}
+
+impl ToRgba<f32> for Rgb9e5 {
+    fn to_rgba(self) -> [f32; 4] {
+        // The exponent is shared by all channels.
+        let exponent = (self.0 >> 27) as i32 - RGB9E5_EXP_BIAS - RGB9E5_MANTISSA_BITS;
+        let scale = 2.0f32.powi(exponent);
+        let mantissa = |shift: u32| ((self.0 >> shift) & 0x1FF) as f32 * scale;
+        [mantissa(0), mantissa(9), mantissa(18), 1.0]
+    }
+}
+
+impl FromRgba<f32> for Rgb9e5 {
+    fn from_rgba(rgba: [f32; 4]) -> Self {
+        // Clamp to the largest representable value.
+        // Negative values and NaN are clamped to 0.0.
+        let max_value = ((1 << RGB9E5_MANTISSA_BITS) - 1) as f32
+            / (1 << RGB9E5_MANTISSA_BITS) as f32
+            * 2.0f32.powi(RGB9E5_MAX_EXP - RGB9E5_EXP_BIAS);
+        let [r, g, b, _] = rgba.map(|f| if f > 0.0 { f.min(max_value) } else { 0.0 });
+
+        // Choose the smallest exponent that can represent the largest channel.
+        let max_channel = r.max(g).max(b);
+        let mut exponent =
+            (max_channel.log2().floor() as i32).max(-RGB9E5_EXP_BIAS - 1) + 1 + RGB9E5_EXP_BIAS;
+        let max_mantissa =
+            (max_channel / 2.0f32.powi(exponent - RGB9E5_EXP_BIAS - RGB9E5_MANTISSA_BITS) + 0.5)
+                .floor() as u32;
+        if max_mantissa == 1 << RGB9E5_MANTISSA_BITS {
+            exponent += 1;
+        }
+
+        let scale = 2.0f32.powi(exponent - RGB9E5_EXP_BIAS - RGB9E5_MANTISSA_BITS);
+        let [r, g, b] = [r, g, b].map(|f| ((f / scale + 0.5).floor() as u32).min(0x1FF));
+        Self(((exponent as u32) << 27) | (b << 18) | (g << 9) | r)
+    }
+}
+
+impl Pixel for Rg11b10 {
+    const SIZE: usize = 4;
+
+    fn get_pixel(data: &[u8], index: usize) -> Self {
+        Self(u32::from_le_bytes(get_pixel(data, index, Self::SIZE)))
+    }
+}
+
+impl ToRgba<u8> for Rg11b10 {
+    fn to_rgba(self) -> [u8; 4] {
+        let [r, g, b, _] = ToRgba::<f32>::to_rgba(self).map(float_to_unorm8);
+        [r, g, b, 255u8]
+    }
+}
+
+impl FromRgba<u8> for Rg11b10 {
+    fn from_rgba(rgba: [u8; 4]) -> Self {
+        Self::from_rgba(rgba.map(|u| u as f32 / 255.0))
+    }
+}
+
+// The packed floats use the same 5-bit exponent as f16 without the sign bit
+// and with fewer mantissa bits.
+impl ToRgba<f32> for Rg11b10 {
+    fn to_rgba(self) -> [f32; 4] {
+        let r = f16::from_bits(((self.0 & 0x7FF) << 4) as u16);
+        let g = f16::from_bits((((self.0 >> 11) & 0x7FF) << 4) as u16);
+        let b = f16::from_bits((((self.0 >> 22) & 0x3FF) << 5) as u16);
+        [r.to_f32(), g.to_f32(), b.to_f32(), 1.0]
+    }
+}
+
+impl FromRgba<f32> for Rg11b10 {
+    fn from_rgba(rgba: [f32; 4]) -> Self {
+        let [r, g, b, _] = rgba;
+        let r = packed_float_bits(r, 6);
+        let g = packed_float_bits(g, 6);
+        let b = packed_float_bits(b, 5);
+        Self((b << 22) | (g << 11) | r)
+    }
+}
+
+fn packed_float_bits(f: f32, mantissa_bits: u32) -> u32 {
+    // Clamp to the largest finite value since the format is unsigned.
+    // Negative values and NaN are clamped to 0.0.
+    let shift = 10 - mantissa_bits;
+    let max_bits = (0x1E << mantissa_bits) | ((1 << mantissa_bits) - 1);
+    let max_value = f16::from_bits((max_bits << shift) as u16).to_f32();
+    let f = if f > 0.0 { f.min(max_value) } else { 0.0 };
+
+    // Round to nearest when removing the extra f16 mantissa bits.
+    let bits = f16::from_f32(f).to_bits() as u32;
+    ((bits + (1 << (shift - 1))) >> shift).min(max_bits)
+}
+
 impl Pixel for Rgbaf32 {
     const SIZE: usize = 16;
 
@@ -156,7 +357,7 @@ impl Pixel for Rgbaf32 {
 
 impl ToRgba<u8> for Rgbaf32 {
     fn to_rgba(self) -> [u8; 4] {
-        self.0.map(|f| (f * 255.0) as u8)
+        self.0.map(float_to_unorm8)
     }
 }
 
@@ -315,6 +516,32 @@ impl FromRgba<u8> for Bgra8 {
     }
 }
 
+impl ToRgba<u8> for Bgrx8 {
+    fn to_rgba(self) -> [u8; 4] {
+        // The unused channel is treated as opaque alpha.
+        [self.0[2], self.0[1], self.0[0], 255u8]
+    }
+}
+
+impl FromRgba<u8> for Bgrx8 {
+    fn from_rgba(rgba: [u8; 4]) -> Self {
+        Self([rgba[2], rgba[1], rgba[0], 255u8])
+    }
+}
+
+impl ToRgba<u8> for Rgbx8 {
+    fn to_rgba(self) -> [u8; 4] {
+        // The unused channel is treated as opaque alpha.
+        [self.0[0], self.0[1], self.0[2], 255u8]
+    }
+}
+
+impl FromRgba<u8> for Rgbx8 {
+    fn from_rgba(rgba: [u8; 4]) -> Self {
+        Self([rgba[0], rgba[1], rgba[2], 255u8])
+    }
+}
+
 impl ToRgba<u8> for Bgra4 {
     fn to_rgba(self) -> [u8; 4] {
         // TODO: How to implement this efficiently?
@@ -341,6 +568,85 @@ impl FromRgba<u8> for Bgra4 {
     }
 }
 
+impl ToRgba<u8> for B5g6r5 {
+    fn to_rgba(self) -> [u8; 4] {
+        // Expand 5 and 6 bit input channels to 8 bit output channels.
+        // Most significant bit -> RGB -> least significant bit.
+        let value = u16::from_le_bytes(self.0);
+        let r = ((value >> 11) & 0x1F) as u8;
+        let g = ((value >> 5) & 0x3F) as u8;
+        let b = (value & 0x1F) as u8;
+        [
+            (r << 3) | (r >> 2),
+            (g << 2) | (g >> 4),
+            (b << 3) | (b >> 2),
+            255u8,
+        ]
+    }
+}
+
+impl FromRgba<u8> for B5g6r5 {
+    fn from_rgba(rgba: [u8; 4]) -> Self {
+        // Round each channel to the nearest representable value.
+        let quantize = |u: u8, max: u16| (u as u16 * max + 127) / 255;
+        let value =
+            (quantize(rgba[0], 31) << 11) | (quantize(rgba[1], 63) << 5) | quantize(rgba[2], 31);
+        Self(value.to_le_bytes())
+    }
+}
+
+impl Pixel for L8 {
+    const SIZE: usize = 1;
+
+    fn get_pixel(data: &[u8], index: usize) -> Self {
+        Self(data[index])
+    }
+}
+
+impl ToRgba<u8> for L8 {
+    fn to_rgba(self) -> [u8; 4] {
+        [self.0, self.0, self.0, 255u8]
+    }
+}
+
+impl FromRgba<u8> for L8 {
+    fn from_rgba(rgba: [u8; 4]) -> Self {
+        Self(rgba[0])
+    }
+}
+
+impl Pixel for A8 {
+    const SIZE: usize = 1;
+
+    fn get_pixel(data: &[u8], index: usize) -> Self {
+        Self(data[index])
+    }
+}
+
+impl ToRgba<u8> for A8 {
+    fn to_rgba(self) -> [u8; 4] {
+        [0u8, 0u8, 0u8, self.0]
+    }
+}
+
+impl FromRgba<u8> for A8 {
+    fn from_rgba(rgba: [u8; 4]) -> Self {
+        Self(rgba[3])
+    }
+}
+
+impl ToRgba<u8> for La8 {
+    fn to_rgba(self) -> [u8; 4] {
+        [self.0[0], self.0[0], self.0[0], self.0[1]]
+    }
+}
+
+impl FromRgba<u8> for La8 {
+    fn from_rgba(rgba: [u8; 4]) -> Self {
+        Self([rgba[0], rgba[3]])
+    }
+}
+
 pub fn encode_rgba<P, T>(width: u32, height: u32, data: &[T]) -> Result<Vec<u8>, SurfaceError>
 where
     P: Pixel + FromRgba<T> + Pod,
@@ -396,10 +702,235 @@ fn expected_size(width: u32, height: u32, bytes_per_pixel: usize) -> Option<usiz
         .checked_mul(bytes_per_pixel)
 }
 
+/// Reorder the channels of RGBA8 `data` in place.
+///
+/// Each output channel in `mapping` selects a channel from the original pixel or a constant.
+/// For example, `[Channel::A, Channel::G, Channel::Zero, Channel::One]`
+/// moves the X component of a normal map stored in alpha to red.
+///
+/// Any trailing bytes that do not form a complete pixel are unchanged.
+pub fn swizzle_rgba8(data: &mut [u8], mapping: [Channel; 4]) {
+    for pixel in data.chunks_exact_mut(4) {
+        let [r, g, b, a] = [pixel[0], pixel[1], pixel[2], pixel[3]];
+        for (output, channel) in pixel.iter_mut().zip(mapping) {
+            *output = match channel {
+                Channel::R => r,
+                Channel::G => g,
+                Channel::B => b,
+                Channel::A => a,
+                Channel::One => 255,
+                Channel::Zero => 0,
+            };
+        }
+    }
+}
+
+const BAYER_4X4: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+/// Quantize the RGBA8 `data` to `bits` per channel using the given dithering `mode`.
+///
+/// The output is still RGBA8 but only contains values representable with `bits`
+/// like multiples of 17 for 4 bits, so converting to the lower bit depth is exact.
+/// Each depth slice of `width` x `height` pixels is dithered separately.
+pub fn dither_rgba8(width: u32, height: u32, data: &[u8], bits: u32, mode: DitherMode) -> Vec<u8> {
+    let max = ((1u32 << bits) - 1) as f32;
+    let step = 255.0 / max;
+    let quantize = |v: f32| ((v / step).round().clamp(0.0, max) * step).round() as u8;
+
+    let width = width.max(1) as usize;
+    let height = height.max(1) as usize;
+    match mode {
+        DitherMode::None => data.iter().map(|v| quantize(*v as f32)).collect(),
+        DitherMode::Ordered => data
+            .iter()
+            .enumerate()
+            .map(|(i, v)| {
+                let pixel = i / 4;
+                let (x, y) = (pixel % width, pixel / width % height);
+                let threshold = (BAYER_4X4[y % 4][x % 4] as f32 + 0.5) / 16.0 - 0.5;
+                quantize(*v as f32 + threshold * step)
+            })
+            .collect(),
+        DitherMode::FloydSteinberg => data
+            .chunks(width.saturating_mul(height).saturating_mul(4))
+            .flat_map(|slice| floyd_steinberg(width, slice, quantize))
+            .collect(),
+    }
+}
+
+fn floyd_steinberg(width: usize, data: &[u8], quantize: impl Fn(f32) -> u8) -> Vec<u8> {
+    let mut values: Vec<f32> = data.iter().map(|v| *v as f32).collect();
+    let height = data.len() / 4 / width;
+    let mut output = Vec::with_capacity(data.len());
+    for (i, value) in data.iter().enumerate() {
+        let pixel = i / 4;
+        let (x, y) = (pixel % width, pixel / width);
+        let quantized = if y < height {
+            quantize(values[i])
+        } else {
+            // Trailing partial rows are only rounded.
+            quantize(*value as f32)
+        };
+        let error = values[i] - quantized as f32;
+        let mut diffuse = |dx: isize, dy: usize, weight: f32| {
+            let nx = x as isize + dx;
+            if nx >= 0 && (nx as usize) < width && y + dy < height {
+                let index = ((y + dy) * width + nx as usize) * 4 + i % 4;
+                values[index] += error * weight;
+            }
+        };
+        if y < height {
+            diffuse(1, 0, 7.0 / 16.0);
+            diffuse(-1, 1, 3.0 / 16.0);
+            diffuse(0, 1, 5.0 / 16.0);
+            diffuse(1, 1, 1.0 / 16.0);
+        }
+        output.push(quantized);
+    }
+    output
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn dither_mean(mode: DitherMode) -> f32 {
+        let data = vec![8u8; 16 * 16 * 4];
+        let dithered = dither_rgba8(16, 16, &data, 4, mode);
+        assert!(dithered.iter().all(|v| v.is_multiple_of(17)));
+        dithered.iter().map(|v| *v as f32).sum::<f32>() / dithered.len() as f32
+    }
+
+    #[test]
+    fn dither_rgba8_none() {
+        assert_eq!(0.0, dither_mean(DitherMode::None));
+        assert_eq!(
+            vec![0, 17, 17, 255],
+            dither_rgba8(1, 1, &[8, 9, 25, 250], 4, DitherMode::None)
+        );
+    }
+
+    #[test]
+    fn dither_rgba8_ordered() {
+        assert!((dither_mean(DitherMode::Ordered) - 8.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn dither_rgba8_floyd_steinberg() {
+        assert!((dither_mean(DitherMode::FloydSteinberg) - 8.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn dither_rgba8_representable_values_unchanged() {
+        let data: Vec<_> = (0..16).flat_map(|i| [i * 17; 4]).collect();
+        for mode in [DitherMode::Ordered, DitherMode::FloydSteinberg] {
+            assert_eq!(data, dither_rgba8(4, 4, &data, 4, mode));
+        }
+    }
+
+    #[test]
+    fn dither_rgba8_floyd_steinberg_slices() {
+        // Error should not diffuse from one depth slice into the next.
+        let slice: Vec<_> = (0..16).flat_map(|i| [i * 3 + 5; 4]).collect();
+        let data = [slice.clone(), slice.clone()].concat();
+        let dithered = dither_rgba8(4, 4, &data, 4, DitherMode::FloydSteinberg);
+        assert_eq!(
+            dither_rgba8(4, 4, &slice, 4, DitherMode::FloydSteinberg),
+            dithered[..slice.len()]
+        );
+        assert_eq!(dithered[..slice.len()], dithered[slice.len()..]);
+    }
+
+    #[test]
+    fn rgb9e5_from_rgbaf32() {
+        // 1.0 uses a mantissa of 256 with an exponent of 16.
+        let encoded = encode_rgba::<Rgb9e5, f32>(1, 1, &[1.0, 0.5, 0.0, 0.25]).unwrap();
+        assert_eq!(
+            ((16u32 << 27) | (128 << 9) | 256).to_le_bytes().to_vec(),
+            encoded
+        );
+    }
+
+    #[test]
+    fn rgb9e5_rgbaf32_roundtrip() {
+        let rgba = [1.0f32, 0.5, 0.25, 1.0, 100.0, 3.0, 0.0, 1.0];
+        let encoded = encode_rgba::<Rgb9e5, f32>(2, 1, &rgba).unwrap();
+        assert_eq!(
+            rgba.to_vec(),
+            decode_rgba::<Rgb9e5, f32>(2, 1, &encoded).unwrap()
+        );
+    }
+
+    #[test]
+    fn rgb9e5_from_rgbaf32_clamped() {
+        // Negative values, NaN, and values larger than the maximum are clamped.
+        let encoded =
+            encode_rgba::<Rgb9e5, f32>(1, 1, &[-1.0, f32::NAN, f32::INFINITY, 1.0]).unwrap();
+        assert_eq!(
+            vec![0.0, 0.0, 65408.0, 1.0],
+            decode_rgba::<Rgb9e5, f32>(1, 1, &encoded).unwrap()
+        );
+    }
+
+    #[test]
+    fn rgb9e5_to_rgba8() {
+        let data = ((16u32 << 27) | (128 << 18) | (256 << 9) | 511).to_le_bytes();
+        assert_eq!(
+            vec![255, 255, 128, 255],
+            decode_rgba::<Rgb9e5, u8>(1, 1, &data).unwrap()
+        );
+    }
+
+    #[test]
+    fn rg11b10_rgbaf32_roundtrip() {
+        let rgba = [1.0f32, 0.5, 0.25, 1.0, 100.0, 2.0, 0.0, 1.0];
+        let encoded = encode_rgba::<Rg11b10, f32>(2, 1, &rgba).unwrap();
+        assert_eq!(
+            rgba.to_vec(),
+            decode_rgba::<Rg11b10, f32>(2, 1, &encoded).unwrap()
+        );
+    }
+
+    #[test]
+    fn rg11b10_from_rgbaf32() {
+        // 1.0 has an exponent of 15 and no mantissa bits.
+        let encoded = encode_rgba::<Rg11b10, f32>(1, 1, &[1.0, 0.5, 0.25, 1.0]).unwrap();
+        assert_eq!(
+            ((13u32 << 5 << 22) | (14 << 6 << 11) | (15 << 6))
+                .to_le_bytes()
+                .to_vec(),
+            encoded
+        );
+    }
+
+    #[test]
+    fn rg11b10_from_rgbaf32_clamped() {
+        // Negative values, NaN, and values larger than the maximum are clamped.
+        let encoded =
+            encode_rgba::<Rg11b10, f32>(1, 1, &[-1.0, f32::NAN, f32::INFINITY, 1.0]).unwrap();
+        assert_eq!(
+            vec![0.0, 0.0, 64512.0, 1.0],
+            decode_rgba::<Rg11b10, f32>(1, 1, &encoded).unwrap()
+        );
+    }
+
+    #[test]
+    fn swizzle_rgba8_argb() {
+        let mut data = vec![1, 2, 3, 4, 5, 6, 7, 8];
+        swizzle_rgba8(&mut data, [Channel::G, Channel::B, Channel::A, Channel::R]);
+        assert_eq!(vec![2, 3, 4, 1, 6, 7, 8, 5], data);
+    }
+
+    #[test]
+    fn swizzle_rgba8_constants() {
+        let mut data = vec![1, 2, 3, 4];
+        swizzle_rgba8(
+            &mut data,
+            [Channel::A, Channel::G, Channel::Zero, Channel::One],
+        );
+        assert_eq!(vec![4, 2, 0, 255], data);
+    }
+
     #[test]
     fn r8_from_rgba8_valid() {
         assert_eq!(vec![1], encode_rgba::<R8, u8>(1, 1, &[1, 2, 3, 4]).unwrap());
@@ -677,6 +1208,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn rgba8_from_rgb8_valid() {
+        assert_eq!(
+            vec![1, 2, 3, 255],
+            decode_rgba::<Rgb8, u8>(1, 1, &[1, 2, 3]).unwrap()
+        );
+    }
+
+    #[test]
+    fn rgba8_from_rgb8_invalid() {
+        let result = decode_rgba::<Rgb8, u8>(1, 1, &[1, 2]);
+        assert_eq!(
+            result,
+            Err(SurfaceError::NotEnoughData {
+                expected: 3,
+                actual: 2
+            })
+        );
+    }
+
     #[test]
     fn rgba8_from_bgr8_valid() {
         assert_eq!(
@@ -700,7 +1251,7 @@ mod tests {
     #[test]
     fn rgba8_from_rgbaf32_valid() {
         assert_eq!(
-            vec![0, 63, 127, 255],
+            vec![0, 64, 128, 255],
             decode_rgba::<Rgbaf32, u8>(
                 1,
                 1,
@@ -747,7 +1298,7 @@ mod tests {
     #[test]
     fn rgba8_from_rgbaf16_valid() {
         assert_eq!(
-            vec![0, 63, 127, 255],
+            vec![0, 64, 128, 255],
             decode_rgba::<Rgbaf16, u8>(
                 1,
                 1,
@@ -896,6 +1447,105 @@ mod tests {
         );
     }
 
+    #[test]
+    fn rgbaf32_from_rgbf16_valid() {
+        assert_eq!(
+            vec![0.0, 0.25, -2.0, 1.0],
+            decode_rgba::<Rgbf16, f32>(
+                1,
+                1,
+                bytemuck::cast_slice(&[
+                    f16::from_f32(0.0f32),
+                    f16::from_f32(0.25f32),
+                    f16::from_f32(-2.0f32),
+                ])
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn rgbaf32_from_rgbf16_invalid() {
+        let result = decode_rgba::<Rgbf16, f32>(1, 1, &[0; 5]);
+        assert_eq!(
+            result,
+            Err(SurfaceError::NotEnoughData {
+                expected: 6,
+                actual: 5
+            })
+        );
+    }
+
+    #[test]
+    fn rgbf16_rgbaf32_roundtrip() {
+        // Include the smallest subnormal, the largest subnormal, and the largest finite value.
+        let values = [
+            f16::from_bits(0x0001),
+            f16::from_bits(0x03ff),
+            -f16::from_bits(0x0001),
+            f16::MAX,
+            f16::from_f32(-0.5),
+            f16::from_f32(1024.0),
+        ];
+        let rgba: Vec<_> = values
+            .chunks_exact(3)
+            .flat_map(|rgb| [rgb[0].to_f32(), rgb[1].to_f32(), rgb[2].to_f32(), 1.0])
+            .collect();
+
+        let encoded = encode_rgba::<Rgbf16, f32>(2, 1, &rgba).unwrap();
+        assert_eq!(bytemuck::cast_slice::<_, u8>(&values), encoded);
+        assert_eq!(rgba, decode_rgba::<Rgbf16, f32>(2, 1, &encoded).unwrap());
+    }
+
+    #[test]
+    fn rgba8_from_rgbf16_valid() {
+        assert_eq!(
+            vec![0, 64, 255, 255],
+            decode_rgba::<Rgbf16, u8>(
+                1,
+                1,
+                bytemuck::cast_slice(&[
+                    f16::from_f32(0.0f32),
+                    f16::from_f32(0.25f32),
+                    f16::from_f32(2.0f32),
+                ])
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn bgrx8_from_rgba8_valid() {
+        assert_eq!(
+            vec![3, 2, 1, 255],
+            encode_rgba::<Bgrx8, u8>(1, 1, &[1, 2, 3, 4]).unwrap()
+        );
+    }
+
+    #[test]
+    fn rgba8_from_bgrx8_valid() {
+        assert_eq!(
+            vec![3, 2, 1, 255],
+            decode_rgba::<Bgrx8, u8>(1, 1, &[1, 2, 3, 4]).unwrap()
+        );
+    }
+
+    #[test]
+    fn rgbx8_from_rgba8_valid() {
+        assert_eq!(
+            vec![1, 2, 3, 255],
+            encode_rgba::<Rgbx8, u8>(1, 1, &[1, 2, 3, 4]).unwrap()
+        );
+    }
+
+    #[test]
+    fn rgba8_from_rgbx8_valid() {
+        assert_eq!(
+            vec![1, 2, 3, 255],
+            decode_rgba::<Rgbx8, u8>(1, 1, &[1, 2, 3, 0]).unwrap()
+        );
+    }
+
     #[test]
     fn bgra4_from_rgba8_valid() {
         assert_eq!(
@@ -924,6 +1574,64 @@ mod tests {
         );
     }
 
+    #[test]
+    fn b5g6r5_from_rgba8_valid() {
+        assert_eq!(
+            vec![0x1F, 0xF8],
+            encode_rgba::<B5g6r5, u8>(1, 1, &[255, 0, 255, 128]).unwrap()
+        );
+    }
+
+    #[test]
+    fn rgba8_from_b5g6r5_valid() {
+        // Red 31, green 32, blue 1
+        assert_eq!(
+            vec![255, 130, 8, 255],
+            decode_rgba::<B5g6r5, u8>(1, 1, &[0x01, 0xFC]).unwrap()
+        );
+    }
+
+    #[test]
+    fn rgba8_from_l8_valid() {
+        assert_eq!(
+            vec![7, 7, 7, 255],
+            decode_rgba::<L8, u8>(1, 1, &[7]).unwrap()
+        );
+    }
+
+    #[test]
+    fn rgba8_from_a8_valid() {
+        assert_eq!(vec![0, 0, 0, 7], decode_rgba::<A8, u8>(1, 1, &[7]).unwrap());
+    }
+
+    #[test]
+    fn la8_from_rgba8_valid() {
+        assert_eq!(
+            vec![1, 4],
+            encode_rgba::<La8, u8>(1, 1, &[1, 2, 3, 4]).unwrap()
+        );
+    }
+
+    #[test]
+    fn rgba8_from_la8_valid() {
+        assert_eq!(
+            vec![1, 1, 1, 2],
+            decode_rgba::<La8, u8>(1, 1, &[1, 2]).unwrap()
+        );
+    }
+
+    #[test]
+    fn rgba8_from_la8_invalid() {
+        let result = decode_rgba::<La8, u8>(1, 1, &[1]);
+        assert_eq!(
+            result,
+            Err(SurfaceError::NotEnoughData {
+                expected: 2,
+                actual: 1
+            })
+        );
+    }
+
     #[test]
     fn rgba8_from_bgra4_invalid() {
         let result = decode_rgba::<Bgra4, u8>(1, 1, &[1]);
@@ -1,8 +1,51 @@
+use std::ops::Range;
+
 use crate::{
-    calculate_offset, error::CreateImageError, max_mipmap_count, mip_dimension, mip_size,
-    ImageFormat, SurfaceError,
+    calculate_offset, error::CreateImageError, layout::surface_size, max_mipmap_count,
+    mip_dimension, mip_size, BlockPadding, ImageFormat, SurfaceError,
 };
 
+/// Hardware limits for [Surface::check_gpu_limits].
+///
+/// The default limits match the Direct3D 11 requirements.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct GpuLimits {
+    /// The maximum width or height of 1D and 2D textures in pixels.
+    pub max_dimension_2d: u32,
+    /// The maximum width, height, or depth of 3D textures in pixels.
+    pub max_dimension_3d: u32,
+    /// The maximum number of array layers.
+    pub max_array_layers: u32,
+    /// Require all dimensions to be powers of two.
+    pub require_power_of_two: bool,
+}
+
+impl Default for GpuLimits {
+    fn default() -> Self {
+        Self {
+            max_dimension_2d: 16384,
+            max_dimension_3d: 2048,
+            max_array_layers: 2048,
+            require_power_of_two: false,
+        }
+    }
+}
+
+/// The range and average of a single channel from [SurfaceRgba32Float::channel_stats].
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct ChannelStats {
+    /// The smallest value ignoring NaN.
+    pub min: f32,
+    /// The largest value ignoring NaN.
+    pub max: f32,
+    /// The average of all values.
+    pub mean: f32,
+}
+
 /// A surface with an image format known at runtime.
 #[derive(Debug, PartialEq)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
@@ -32,6 +75,34 @@ pub struct Surface<T> {
 }
 
 impl<T: AsRef<[u8]>> Surface<T> {
+    /// Create a new surface and check that the dimensions and data are valid.
+    ///
+    /// The width, height, and depth must be non zero,
+    /// the mipmap count must not exceed the maximum for the dimensions,
+    /// and `data` must contain at least the base mip level.
+    /// The fields can also be set directly to skip validation.
+    pub fn new(
+        width: u32,
+        height: u32,
+        depth: u32,
+        layers: u32,
+        mipmaps: u32,
+        image_format: ImageFormat,
+        data: T,
+    ) -> Result<Self, SurfaceError> {
+        let surface = Self {
+            width,
+            height,
+            depth,
+            layers,
+            mipmaps,
+            image_format,
+            data,
+        };
+        surface.validate()?;
+        Ok(surface)
+    }
+
     /// Get the range of image data corresponding to the specified `layer`, `depth_level`, and `mipmap`.
     ///
     /// The dimensions of the returned data should be calculated using [mip_dimension].
@@ -48,6 +119,306 @@ impl<T: AsRef<[u8]>> Surface<T> {
         )
     }
 
+    /// Copy the data for all depth slices of the specified `layer` and `mipmap` for uploading to the GPU.
+    ///
+    /// Compressed formats are padded to whole blocks, so the dimensions of the data
+    /// are the [mip_dimension] rounded up to a multiple of the block dimensions.
+    /// Returns [None] if the expected range is not fully contained within the buffer.
+    pub fn mip_bytes(&self, layer: u32, mipmap: u32) -> Option<Vec<u8>> {
+        let range = self.mip_range(layer, mipmap)?;
+        self.data.as_ref().get(range).map(|data| data.to_vec())
+    }
+
+    /// The byte range in [data](#structfield.data) for the specified `layer`, `depth_level`, and `mipmap`.
+    ///
+    /// This is the range used by [Surface::get] and avoids copying the data.
+    /// Returns [None] if the indices are out of bounds
+    /// or the range is not fully contained within the buffer.
+    pub fn subresource_range(
+        &self,
+        layer: u32,
+        depth_level: u32,
+        mipmap: u32,
+    ) -> Option<Range<usize>> {
+        if layer >= self.layers
+            || mipmap >= self.mipmaps
+            || depth_level >= mip_dimension(self.depth, mipmap)
+        {
+            return None;
+        }
+
+        let range = mipmap_range::<u8>(
+            (self.width, self.height, self.depth),
+            self.mipmaps,
+            self.image_format,
+            layer,
+            depth_level,
+            mipmap,
+        )?;
+        (range.end <= self.data.as_ref().len()).then_some(range)
+    }
+
+    /// Iterate over the `(layer, mipmap, range)` for each layer and mipmap in the order stored in
+    /// [data](#structfield.data).
+    ///
+    /// Each range covers all depth slices of the mipmap like [Surface::mip_bytes].
+    /// Subresources not fully contained within the buffer are skipped.
+    pub fn subresources(&self) -> impl Iterator<Item = (u32, u32, Range<usize>)> + '_ {
+        (0..self.layers).flat_map(move |layer| {
+            (0..self.mipmaps)
+                .filter_map(move |mipmap| Some((layer, mipmap, self.mip_range(layer, mipmap)?)))
+        })
+    }
+
+    pub(crate) fn mip_range(&self, layer: u32, mipmap: u32) -> Option<Range<usize>> {
+        if layer >= self.layers || mipmap >= self.mipmaps {
+            return None;
+        }
+
+        let slice = mipmap_range::<u8>(
+            (self.width, self.height, self.depth),
+            self.mipmaps,
+            self.image_format,
+            layer,
+            0,
+            mipmap,
+        )?;
+        let size = slice
+            .len()
+            .checked_mul(mip_dimension(self.depth, mipmap) as usize)?;
+        let range = slice.start..slice.start.checked_add(size)?;
+        (range.end <= self.data.as_ref().len()).then_some(range)
+    }
+
+    /// The BC7 rotation value from `0` to `3` for each block in the surface data for debugging.
+    ///
+    /// Blocks are in the same order as the data for all layers and mipmaps.
+    /// Modes 4 and 5 can swap alpha with red (1), green (2), or blue (3).
+    /// All other blocks use a rotation of `0`.
+    /// Returns an empty list if the format is not [ImageFormat::BC7RgbaUnorm] or [ImageFormat::BC7RgbaUnormSrgb].
+    pub fn bc7_rotations(&self) -> Vec<u8> {
+        if !matches!(
+            self.image_format,
+            ImageFormat::BC7RgbaUnorm | ImageFormat::BC7RgbaUnormSrgb
+        ) {
+            return Vec::new();
+        }
+
+        self.data
+            .as_ref()
+            .chunks_exact(16)
+            .map(|block| crate::bcn::bc7_rotation(block.try_into().unwrap()))
+            .collect()
+    }
+
+    /// Describe the expected data layout for each layer and mipmap for debugging size mismatches.
+    ///
+    /// Offsets and sizes are in bytes and include all depth slices.
+    pub fn layout_report(&self) -> String {
+        use std::fmt::Write;
+
+        let block_dimensions = self.image_format.block_dimensions();
+        let block_size_in_bytes = self.image_format.block_size_in_bytes();
+
+        let mut report = String::new();
+        let _ = writeln!(
+            report,
+            "{:?} {}x{}x{}, {} layers, {} mipmaps",
+            self.image_format, self.width, self.height, self.depth, self.layers, self.mipmaps
+        );
+        let _ = writeln!(
+            report,
+            "block dimensions {}x{}x{}, block size {} bytes",
+            block_dimensions.0, block_dimensions.1, block_dimensions.2, block_size_in_bytes
+        );
+
+        for layer in 0..self.layers {
+            for mipmap in 0..self.mipmaps {
+                let offset = calculate_offset(
+                    layer,
+                    0,
+                    mipmap,
+                    (self.width, self.height, self.depth),
+                    block_dimensions,
+                    block_size_in_bytes,
+                    self.mipmaps,
+                );
+                let size = mip_size(
+                    mip_dimension(self.width, mipmap) as usize,
+                    mip_dimension(self.height, mipmap) as usize,
+                    mip_dimension(self.depth, mipmap) as usize,
+                    block_dimensions.0 as usize,
+                    block_dimensions.1 as usize,
+                    block_dimensions.2 as usize,
+                    block_size_in_bytes,
+                );
+
+                let _ = writeln!(
+                    report,
+                    "layer {layer} mipmap {mipmap}: offset {}, size {}",
+                    display_size(offset),
+                    display_size(size)
+                );
+            }
+        }
+
+        let _ = write!(
+            report,
+            "expected size {}, actual size {}",
+            display_size(self.expected_data_size()),
+            self.data.as_ref().len()
+        );
+        report
+    }
+
+    /// The size in bytes of the data for all layers and mipmaps
+    /// or [None] if the size would overflow.
+    pub fn expected_data_size(&self) -> Option<usize> {
+        surface_size(
+            self.width,
+            self.height,
+            self.depth,
+            self.layers,
+            self.mipmaps,
+            self.image_format,
+        )
+    }
+
+    /// Check that the data length exactly matches [Surface::expected_data_size].
+    ///
+    /// Use [Surface::layout_report] for more details on the expected layout.
+    pub fn assert_data_size(&self) -> Result<(), SurfaceError> {
+        let expected = self
+            .expected_data_size()
+            .ok_or(SurfaceError::PixelCountWouldOverflow {
+                width: self.width,
+                height: self.height,
+                depth: self.depth,
+            })?;
+        let actual = self.data.as_ref().len();
+        if actual != expected {
+            Err(SurfaceError::UnexpectedDataSize { expected, actual })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Check that the surface does not exceed the hardware `limits` before uploading to the GPU.
+    ///
+    /// Cube maps count as 6 array layers.
+    /// This does not check that the surface has enough data. See [Surface::get].
+    pub fn check_gpu_limits(&self, limits: GpuLimits) -> Result<(), SurfaceError> {
+        let max_dimension = if self.depth > 1 {
+            limits.max_dimension_3d
+        } else {
+            limits.max_dimension_2d
+        };
+        if self.width.max(self.height).max(self.depth) > max_dimension {
+            return Err(SurfaceError::ExceedsMaxDimension {
+                width: self.width,
+                height: self.height,
+                depth: self.depth,
+                max_dimension,
+            });
+        }
+
+        if self.layers > limits.max_array_layers {
+            return Err(SurfaceError::ExceedsMaxArrayLayers {
+                layers: self.layers,
+                max_array_layers: limits.max_array_layers,
+            });
+        }
+
+        let max_mipmaps = max_mipmap_count(self.width.max(self.height).max(self.depth));
+        if self.mipmaps > max_mipmaps {
+            return Err(SurfaceError::UnexpectedMipmapCount {
+                mipmaps: self.mipmaps,
+                max_mipmaps,
+            });
+        }
+
+        if limits.require_power_of_two
+            && !(self.width.is_power_of_two()
+                && self.height.is_power_of_two()
+                && self.depth.is_power_of_two())
+        {
+            return Err(SurfaceError::NonPowerOfTwoDimensions {
+                width: self.width,
+                height: self.height,
+                depth: self.depth,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Pad the number of blocks in each row and column of all layers and mipmaps
+    /// by duplicating the blocks on the right and bottom edges.
+    ///
+    /// This operates directly on the compressed block data,
+    /// so the padding is lossless for block compressed formats like BC7.
+    /// The padded width and height are always whole blocks.
+    /// Padding can't change the virtual dimensions mid-block,
+    /// so any padding pixels already stored in partial blocks along the edges
+    /// become part of the image as is.
+    pub fn repad(&self, padding: BlockPadding) -> Result<Surface<Vec<u8>>, SurfaceError> {
+        self.validate()?;
+
+        let (block_width, block_height, _) = self.image_format.block_dimensions();
+        let block_size_in_bytes = self.image_format.block_size_in_bytes();
+        let blocks = |dimension: u32, block_dimension: u32| dimension.div_ceil(block_dimension);
+
+        let pad = |dimension: u32, block_dimension: u32| {
+            padding
+                .pad(blocks(dimension, block_dimension))?
+                .checked_mul(block_dimension)
+        };
+        let (width, height) = pad(self.width, block_width)
+            .zip(pad(self.height, block_height))
+            .ok_or(SurfaceError::PaddedDimensionsWouldOverflow {
+                width: self.width,
+                height: self.height,
+                padding,
+            })?;
+
+        let mut data = Vec::new();
+        for layer in 0..self.layers {
+            for mipmap in 0..self.mipmaps {
+                // The padded mipmaps always have at least as many blocks as the original mipmaps.
+                let old_blocks_x = blocks(mip_dimension(self.width, mipmap), block_width);
+                let old_blocks_y = blocks(mip_dimension(self.height, mipmap), block_height);
+                let new_blocks_x = blocks(mip_dimension(width, mipmap), block_width);
+                let new_blocks_y = blocks(mip_dimension(height, mipmap), block_height);
+
+                for level in 0..mip_dimension(self.depth, mipmap) {
+                    let mip_data = self
+                        .get(layer, level, mipmap)
+                        .ok_or(SurfaceError::MipmapDataOutOfBounds { layer, mipmap })?;
+
+                    for y in 0..new_blocks_y {
+                        let old_y = y.min(old_blocks_y - 1);
+                        for x in 0..new_blocks_x {
+                            let old_x = x.min(old_blocks_x - 1);
+                            let start =
+                                (old_y * old_blocks_x + old_x) as usize * block_size_in_bytes;
+                            data.extend_from_slice(&mip_data[start..start + block_size_in_bytes]);
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(Surface {
+            width,
+            height,
+            depth: self.depth,
+            layers: self.layers,
+            mipmaps: self.mipmaps,
+            image_format: self.image_format,
+            data,
+        })
+    }
+
     // TODO: Add tests for each of these cases.
     pub(crate) fn validate(&self) -> Result<(), SurfaceError> {
         if self.width == 0 || self.height == 0 || self.depth == 0 {
@@ -97,6 +468,93 @@ impl<T: AsRef<[u8]>> Surface<T> {
     }
 }
 
+impl Surface<Vec<u8>> {
+    /// Get a mutable view of the image data corresponding to the specified `layer`, `depth_level`, and `mipmap`.
+    ///
+    /// This uses the same range as [Surface::get] for editing data in place.
+    /// Returns [None] if the expected range is not fully contained within the buffer.
+    pub fn get_mut(&mut self, layer: u32, depth_level: u32, mipmap: u32) -> Option<&mut [u8]> {
+        let range = mipmap_range::<u8>(
+            (self.width, self.height, self.depth),
+            self.mipmaps,
+            self.image_format,
+            layer,
+            depth_level,
+            mipmap,
+        )?;
+        self.data.get_mut(range)
+    }
+
+    /// Combine single mipmap surfaces into a single surface with `mipmaps.len()` mipmaps.
+    ///
+    /// This allows encoding each mipmap with different settings like quality.
+    /// Each surface after the first should have half the dimensions of the previous surface
+    /// and the same format and array layers as the first surface.
+    pub fn combine_mips<T: AsRef<[u8]>>(mipmaps: &[Surface<T>]) -> Result<Self, SurfaceError> {
+        let base = mipmaps.first().ok_or(SurfaceError::ZeroSizedSurface {
+            width: 0,
+            height: 0,
+            depth: 0,
+        })?;
+        base.validate()?;
+
+        for (mipmap, surface) in mipmaps.iter().enumerate() {
+            let mipmap = mipmap as u32;
+            if surface.image_format != base.image_format
+                || surface.layers != base.layers
+                || surface.mipmaps != 1
+                || surface.width != mip_dimension(base.width, mipmap)
+                || surface.height != mip_dimension(base.height, mipmap)
+                || surface.depth != mip_dimension(base.depth, mipmap)
+            {
+                return Err(SurfaceError::IncompatibleMipmap { mipmap });
+            }
+        }
+
+        let (block_width, block_height, block_depth) = base.image_format.block_dimensions();
+        let block_size_in_bytes = base.image_format.block_size_in_bytes();
+
+        // Data is ordered by layer and then mipmap.
+        let mut data = Vec::new();
+        for layer in 0..base.layers {
+            for (mipmap, surface) in mipmaps.iter().enumerate() {
+                let layer_size = mip_size(
+                    surface.width as usize,
+                    surface.height as usize,
+                    surface.depth as usize,
+                    block_width as usize,
+                    block_height as usize,
+                    block_depth as usize,
+                    block_size_in_bytes,
+                )
+                .ok_or(SurfaceError::PixelCountWouldOverflow {
+                    width: surface.width,
+                    height: surface.height,
+                    depth: surface.depth,
+                })?;
+                let start = layer as usize * layer_size;
+                let layer_data = surface.data.as_ref().get(start..start + layer_size).ok_or(
+                    SurfaceError::MipmapDataOutOfBounds {
+                        layer,
+                        mipmap: mipmap as u32,
+                    },
+                )?;
+                data.extend_from_slice(layer_data);
+            }
+        }
+
+        Ok(Surface {
+            width: base.width,
+            height: base.height,
+            depth: base.depth,
+            layers: base.layers,
+            mipmaps: mipmaps.len() as u32,
+            image_format: base.image_format,
+            data,
+        })
+    }
+}
+
 /// An uncompressed [ImageFormat::Rgba8Unorm] surface with 4 bytes per pixel.
 #[derive(Debug, PartialEq)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
@@ -123,7 +581,37 @@ pub struct SurfaceRgba8<T> {
     pub data: T,
 }
 
+impl<T: AsMut<[u8]>> SurfaceRgba8<T> {
+    /// Reorder the channels of all pixels in place using [crate::swizzle_rgba8].
+    pub fn swizzle(&mut self, mapping: [crate::Channel; 4]) {
+        crate::swizzle_rgba8(self.data.as_mut(), mapping);
+    }
+}
+
 impl<T: AsRef<[u8]>> SurfaceRgba8<T> {
+    /// Create a new surface and check that the dimensions and data are valid.
+    ///
+    /// See [Surface::new] for the checks performed.
+    pub fn new(
+        width: u32,
+        height: u32,
+        depth: u32,
+        layers: u32,
+        mipmaps: u32,
+        data: T,
+    ) -> Result<Self, SurfaceError> {
+        let surface = Self {
+            width,
+            height,
+            depth,
+            layers,
+            mipmaps,
+            data,
+        };
+        surface.validate()?;
+        Ok(surface)
+    }
+
     /// Get the range of 2D image data corresponding to the specified `layer`, `depth_level`, and `mipmap`.
     ///
     /// The dimensions of the returned data should be calculated using [mip_dimension].
@@ -154,6 +642,125 @@ impl<T: AsRef<[u8]>> SurfaceRgba8<T> {
         })
     }
 
+    /// Create a copy of the surface with `channel` set to `value` for all layers and mipmaps.
+    ///
+    /// The channel index is `0` for red, `1` for green, `2` for blue, and `3` for alpha.
+    ///
+    /// # Panics
+    /// Panics if `channel` is not less than `4`.
+    pub fn fill_channel(&self, channel: usize, value: u8) -> SurfaceRgba8<Vec<u8>> {
+        assert!(channel < 4, "channel index {channel} is out of range");
+
+        let mut data = self.data.as_ref().to_vec();
+        for pixel in data.chunks_exact_mut(4) {
+            pixel[channel] = value;
+        }
+
+        SurfaceRgba8 {
+            width: self.width,
+            height: self.height,
+            depth: self.depth,
+            layers: self.layers,
+            mipmaps: self.mipmaps,
+            data,
+        }
+    }
+
+    /// Place the base mip level of `other` to the right of the base mip level of `self`.
+    ///
+    /// Both surfaces must have the same height, depth, and array layers.
+    /// The result has only a single mipmap.
+    pub fn concat_horizontal<U: AsRef<[u8]>>(
+        &self,
+        other: &SurfaceRgba8<U>,
+    ) -> Result<SurfaceRgba8<Vec<u8>>, SurfaceError> {
+        self.concat(other, true)
+    }
+
+    /// Place the base mip level of `other` below the base mip level of `self`.
+    ///
+    /// Both surfaces must have the same width, depth, and array layers.
+    /// The result has only a single mipmap.
+    pub fn concat_vertical<U: AsRef<[u8]>>(
+        &self,
+        other: &SurfaceRgba8<U>,
+    ) -> Result<SurfaceRgba8<Vec<u8>>, SurfaceError> {
+        self.concat(other, false)
+    }
+
+    fn concat<U: AsRef<[u8]>>(
+        &self,
+        other: &SurfaceRgba8<U>,
+        horizontal: bool,
+    ) -> Result<SurfaceRgba8<Vec<u8>>, SurfaceError> {
+        let (width, height) = if horizontal {
+            (self.width.checked_add(other.width), Some(self.height))
+        } else {
+            (Some(self.width), self.height.checked_add(other.height))
+        };
+        let (width, height) = width
+            .zip(height)
+            .ok_or(SurfaceError::PixelCountWouldOverflow {
+                width: self.width,
+                height: self.height,
+                depth: self.depth,
+            })?;
+
+        let matching_size = if horizontal {
+            self.height == other.height
+        } else {
+            self.width == other.width
+        };
+        if !matching_size || self.depth != other.depth || self.layers != other.layers {
+            return Err(SurfaceError::IncompatibleSurfaceDimensions {
+                width: self.width,
+                height: self.height,
+                depth: self.depth,
+                layers: self.layers,
+                other_width: other.width,
+                other_height: other.height,
+                other_depth: other.depth,
+                other_layers: other.layers,
+            });
+        }
+        self.validate()?;
+        other.validate()?;
+
+        let mut data = Vec::new();
+        for layer in 0..self.layers {
+            for level in 0..self.depth {
+                let a = self
+                    .get(layer, level, 0)
+                    .ok_or(SurfaceError::MipmapDataOutOfBounds { layer, mipmap: 0 })?;
+                let b = other
+                    .get(layer, level, 0)
+                    .ok_or(SurfaceError::MipmapDataOutOfBounds { layer, mipmap: 0 })?;
+
+                if horizontal {
+                    // Interleave the rows from each surface.
+                    let rows_a = a.chunks_exact(self.width as usize * 4);
+                    let rows_b = b.chunks_exact(other.width as usize * 4);
+                    for (row_a, row_b) in rows_a.zip(rows_b) {
+                        data.extend_from_slice(row_a);
+                        data.extend_from_slice(row_b);
+                    }
+                } else {
+                    data.extend_from_slice(a);
+                    data.extend_from_slice(b);
+                }
+            }
+        }
+
+        Ok(SurfaceRgba8 {
+            width,
+            height,
+            depth: self.depth,
+            layers: self.layers,
+            mipmaps: 1,
+            data,
+        })
+    }
+
     pub(crate) fn validate(&self) -> Result<(), SurfaceError> {
         Surface {
             width: self.width,
@@ -241,10 +848,154 @@ impl<T: AsRef<[u8]>> SurfaceRgba8<T> {
             },
         )
     }
+
+    /// Iterate over the images for each mipmap using [to_image](Self::to_image).
+    ///
+    /// The iterator yields one image for each of the [mipmaps](#structfield.mipmaps)
+    /// and stops at the first mipmap that returns an error.
+    pub fn images(&self) -> impl Iterator<Item = image::RgbaImage> + '_ {
+        (0..self.mipmaps).map_while(|mipmap| self.to_image(mipmap).ok())
+    }
+
+    /// Copy all layers and depth slices for the given `mipmap` into an existing image
+    /// to reuse its allocation.
+    ///
+    /// The dimensions of `out` must match the dimensions of [SurfaceRgba8::to_image].
+    pub fn write_to_image(
+        &self,
+        mipmap: u32,
+        out: &mut image::RgbaImage,
+    ) -> Result<(), CreateImageError> {
+        let expected_width = mip_dimension(self.width, mipmap);
+        let expected_height =
+            mip_dimension(self.height, mipmap) * mip_dimension(self.depth, mipmap) * self.layers;
+        if out.dimensions() != (expected_width, expected_height) {
+            return Err(CreateImageError::UnexpectedImageDimensions {
+                width: out.width(),
+                height: out.height(),
+                expected_width,
+                expected_height,
+            });
+        }
+
+        // Use the same vertical layout as to_image.
+        let buffer: &mut [u8] = out;
+        let mut offset = 0;
+        for layer in 0..self.layers {
+            for level in 0..mip_dimension(self.depth, mipmap) {
+                let data = self
+                    .get(layer, level, mipmap)
+                    .ok_or(SurfaceError::MipmapDataOutOfBounds { layer, mipmap })?;
+                buffer[offset..offset + data.len()].copy_from_slice(data);
+                offset += data.len();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Split the base mip level into separate grayscale images for the red, green, blue, and alpha channels.
+    ///
+    /// This is useful for inspecting textures that pack different data into each channel.
+    /// Array layers and depth slices are arranged vertically from top to bottom like [SurfaceRgba8::to_image].
+    pub fn unpack_channels(&self) -> Result<[image::GrayImage; 4], CreateImageError> {
+        let image = self.to_image(0)?;
+        Ok([0, 1, 2, 3].map(|c| {
+            image::GrayImage::from_fn(image.width(), image.height(), |x, y| {
+                image::Luma([image.get_pixel(x, y)[c]])
+            })
+        }))
+    }
+
+    /// Resize the base mip level for all layers and depth slices to `width` x `height` using `filter`.
+    ///
+    /// This is intended for using a smaller mipmap as a placeholder for the full resolution image
+    /// such as when only lower mip levels have finished loading.
+    /// The result has only a single mipmap.
+    pub fn upsample_to(
+        &self,
+        width: u32,
+        height: u32,
+        filter: image::imageops::FilterType,
+    ) -> Result<SurfaceRgba8<Vec<u8>>, SurfaceError> {
+        let mut data = Vec::new();
+        for layer in 0..self.layers {
+            for level in 0..self.depth {
+                let image = self
+                    .get_image(layer, level, 0)
+                    .ok_or(SurfaceError::MipmapDataOutOfBounds { layer, mipmap: 0 })?;
+                let resized = image::imageops::resize(&image, width, height, filter);
+                data.extend_from_slice(resized.as_raw());
+            }
+        }
+
+        Ok(SurfaceRgba8 {
+            width,
+            height,
+            depth: self.depth,
+            layers: self.layers,
+            mipmaps: 1,
+            data,
+        })
+    }
+}
+
+impl SurfaceRgba8<Vec<u8>> {
+    /// Create a cube map with a single mipmap from six `width` x `height` faces
+    /// in the order +X, -X, +Y, -Y, +Z, -Z.
+    ///
+    /// Any bytes after the first `width * height * 4` bytes of each face are ignored.
+    pub fn from_face_slices(
+        faces: &[&[u8]; 6],
+        width: u32,
+        height: u32,
+    ) -> Result<Self, SurfaceError> {
+        let face_size = (width as usize)
+            .checked_mul(height as usize)
+            .and_then(|s| s.checked_mul(4))
+            .ok_or(SurfaceError::PixelCountWouldOverflow {
+                width,
+                height,
+                depth: 1,
+            })?;
+
+        let mut data = Vec::with_capacity(face_size * 6);
+        for face in faces {
+            let face = face.get(..face_size).ok_or(SurfaceError::NotEnoughData {
+                expected: face_size,
+                actual: face.len(),
+            })?;
+            data.extend_from_slice(face);
+        }
+
+        Self::new(width, height, 1, 6, 1, data)
+    }
 }
 
 #[cfg(feature = "image")]
 impl SurfaceRgba8<Vec<u8>> {
+    /// Create a cube map with a single mipmap from six faces in the order +X, -X, +Y, -Y, +Z, -Z.
+    ///
+    /// All faces must have the same dimensions.
+    /// See [SurfaceRgba8::from_image_layers] for faces stacked vertically in a single image.
+    pub fn from_faces(faces: [&image::RgbaImage; 6]) -> Result<Self, SurfaceError> {
+        let (width, height) = faces[0].dimensions();
+        if let Some(face) = faces.iter().find(|f| f.dimensions() != (width, height)) {
+            return Err(SurfaceError::IncompatibleSurfaceDimensions {
+                width,
+                height,
+                depth: 1,
+                layers: 1,
+                other_width: face.width(),
+                other_height: face.height(),
+                other_depth: 1,
+                other_layers: 1,
+            });
+        }
+
+        Self::from_face_slices(&faces.map(|f| f.as_raw().as_slice()), width, height)
+    }
+
     /// Create an image for all layers and depth slices without copying.
     ///
     /// Fails if the surface has more than one mipmap.
@@ -300,6 +1051,29 @@ pub struct SurfaceRgba32Float<T> {
 }
 
 impl<T: AsRef<[f32]>> SurfaceRgba32Float<T> {
+    /// Create a new surface and check that the dimensions and data are valid.
+    ///
+    /// See [Surface::new] for the checks performed.
+    pub fn new(
+        width: u32,
+        height: u32,
+        depth: u32,
+        layers: u32,
+        mipmaps: u32,
+        data: T,
+    ) -> Result<Self, SurfaceError> {
+        let surface = Self {
+            width,
+            height,
+            depth,
+            layers,
+            mipmaps,
+            data,
+        };
+        surface.validate()?;
+        Ok(surface)
+    }
+
     /// Get the range of 2D image data corresponding to the specified `layer`, `depth_level`, and `mipmap`.
     ///
     /// The dimensions of the returned data should be calculated using [mip_dimension].
@@ -337,15 +1111,119 @@ impl<T: AsRef<[f32]>> SurfaceRgba32Float<T> {
         })
     }
 
-    pub(crate) fn validate(&self) -> Result<(), SurfaceError> {
-        Surface {
-            width: self.width,
-            height: self.height,
-            depth: self.depth,
-            layers: self.layers,
-            mipmaps: self.mipmaps,
-            image_format: ImageFormat::Rgba32Float,
-            data: bytemuck::cast_slice(self.data.as_ref()),
+    /// Calculate the [ChannelStats] for each RGBA channel of all layers and mipmaps.
+    ///
+    /// This is useful for checking the range of values before choosing a format
+    /// like [ImageFormat::BC6hRgbUfloat] or [ImageFormat::BC7RgbaUnorm].
+    /// Surfaces without any pixels return `0.0` for all values.
+    pub fn channel_stats(&self) -> [ChannelStats; 4] {
+        let mut min = [f32::INFINITY; 4];
+        let mut max = [f32::NEG_INFINITY; 4];
+        let mut sum = [0.0f64; 4];
+        let mut count = 0usize;
+        for pixel in self.data.as_ref().chunks_exact(4) {
+            for c in 0..4 {
+                min[c] = min[c].min(pixel[c]);
+                max[c] = max[c].max(pixel[c]);
+                sum[c] += pixel[c] as f64;
+            }
+            count += 1;
+        }
+
+        std::array::from_fn(|c| {
+            if count == 0 {
+                ChannelStats {
+                    min: 0.0,
+                    max: 0.0,
+                    mean: 0.0,
+                }
+            } else {
+                ChannelStats {
+                    min: min[c],
+                    max: max[c],
+                    mean: (sum[c] / count as f64) as f32,
+                }
+            }
+        })
+    }
+
+    /// Returns `true` if the alpha channel is not the same value for all pixels.
+    ///
+    /// Surfaces with constant alpha can use formats without alpha like [ImageFormat::BC1RgbaUnorm].
+    pub fn has_alpha_variation(&self) -> bool {
+        let mut alpha = self.data.as_ref().chunks_exact(4).map(|p| p[3]);
+        match alpha.next() {
+            Some(first) => alpha.any(|a| a != first),
+            None => false,
+        }
+    }
+
+    /// Calculate the signed difference `self - other` for each channel of all layers and mipmaps.
+    ///
+    /// This is useful for finding which pixels changed when comparing encoder output.
+    /// Both surfaces must have the same dimensions, array layers, and mipmaps.
+    pub fn difference<U: AsRef<[f32]>>(
+        &self,
+        other: &SurfaceRgba32Float<U>,
+    ) -> Result<SurfaceRgba32Float<Vec<f32>>, SurfaceError> {
+        if (self.width, self.height, self.depth, self.layers)
+            != (other.width, other.height, other.depth, other.layers)
+        {
+            return Err(SurfaceError::IncompatibleSurfaceDimensions {
+                width: self.width,
+                height: self.height,
+                depth: self.depth,
+                layers: self.layers,
+                other_width: other.width,
+                other_height: other.height,
+                other_depth: other.depth,
+                other_layers: other.layers,
+            });
+        }
+        if self.mipmaps != other.mipmaps {
+            return Err(SurfaceError::IncompatibleMipmapCount {
+                mipmaps: self.mipmaps,
+                other_mipmaps: other.mipmaps,
+            });
+        }
+        self.validate()?;
+        other.validate()?;
+
+        // Data is ordered by layer, mipmap, and then depth level.
+        let mut data = Vec::new();
+        for layer in 0..self.layers {
+            for mipmap in 0..self.mipmaps {
+                for level in 0..mip_dimension(self.depth, mipmap) {
+                    let a = self
+                        .get(layer, level, mipmap)
+                        .ok_or(SurfaceError::MipmapDataOutOfBounds { layer, mipmap })?;
+                    let b = other
+                        .get(layer, level, mipmap)
+                        .ok_or(SurfaceError::MipmapDataOutOfBounds { layer, mipmap })?;
+                    data.extend(a.iter().zip(b).map(|(a, b)| a - b));
+                }
+            }
+        }
+
+        Ok(SurfaceRgba32Float {
+            width: self.width,
+            height: self.height,
+            depth: self.depth,
+            layers: self.layers,
+            mipmaps: self.mipmaps,
+            data,
+        })
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), SurfaceError> {
+        Surface {
+            width: self.width,
+            height: self.height,
+            depth: self.depth,
+            layers: self.layers,
+            mipmaps: self.mipmaps,
+            image_format: ImageFormat::Rgba32Float,
+            data: bytemuck::cast_slice(self.data.as_ref()),
         }
         .validate()
     }
@@ -422,6 +1300,14 @@ impl<T: AsRef<[f32]>> SurfaceRgba32Float<T> {
             },
         )
     }
+
+    /// Iterate over the images for each mipmap using [to_image](Self::to_image).
+    ///
+    /// The iterator yields one image for each of the [mipmaps](#structfield.mipmaps)
+    /// and stops at the first mipmap that returns an error.
+    pub fn images(&self) -> impl Iterator<Item = image::Rgba32FImage> + '_ {
+        (0..self.mipmaps).map_while(|mipmap| self.to_image(mipmap).ok())
+    }
 }
 
 #[cfg(feature = "image")]
@@ -454,6 +1340,121 @@ impl SurfaceRgba32Float<Vec<f32>> {
     }
 }
 
+/// An uncompressed surface with 16-bit unsigned normalized RGBA data and 8 bytes per pixel.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SurfaceRgba16<T> {
+    /// The width of the surface in pixels.
+    pub width: u32,
+    /// The height of the surface in pixels.
+    pub height: u32,
+    /// The depth of the surface in pixels.
+    /// This should be `1` for 2D surfaces.
+    pub depth: u32,
+    /// The number of array layers in the surface.
+    /// This should be `1` for most surfaces and `6` for cube maps.
+    pub layers: u32,
+    /// The number of mipmaps in the surface.
+    /// This should be `1` if the surface has only the base mip level.
+    /// All array layers are assumed to have the same number of mipmaps.
+    pub mipmaps: u32,
+    /// The combined `u16` image data ordered by layer and then mipmap without additional padding.
+    ///
+    /// A surface with L layers and M mipmaps would have the following layout:
+    /// Layer 0 Mip 0, Layer 0 Mip 1,  ..., Layer L-1 Mip M-1
+    pub data: T,
+}
+
+impl<T: AsRef<[u16]>> SurfaceRgba16<T> {
+    /// Get the range of 2D image data corresponding to the specified `layer`, `depth_level`, and `mipmap`.
+    ///
+    /// The dimensions of the returned data should be calculated using [mip_dimension].
+    /// Returns [None] if the expected range is not fully contained within the buffer.
+    pub fn get(&self, layer: u32, depth_level: u32, mipmap: u32) -> Option<&[u16]> {
+        self.data.as_ref().get(rgba16_mipmap_range(
+            (self.width, self.height, self.depth),
+            self.mipmaps,
+            layer,
+            depth_level,
+            mipmap,
+        )?)
+    }
+}
+
+#[cfg(feature = "image")]
+impl<T: AsRef<[u16]>> SurfaceRgba16<T> {
+    /// Create an image for all layers and depth slices for the given `mipmap`.
+    ///
+    /// Array layers and depth slices are arranged vertically from top to bottom.
+    pub fn to_image(
+        &self,
+        mipmap: u32,
+    ) -> Result<image::ImageBuffer<image::Rgba<u16>, Vec<u16>>, CreateImageError> {
+        // Mipmaps have different dimensions.
+        // A single 2D image can only represent data from a single mip level across layers.
+        let mip_depth = mip_dimension(self.depth, mipmap);
+        let mut image_data = Vec::new();
+        for layer in 0..self.layers {
+            for level in 0..mip_depth {
+                let data = self
+                    .get(layer, level, mipmap)
+                    .ok_or(SurfaceError::MipmapDataOutOfBounds { layer, mipmap })?;
+                image_data.extend_from_slice(data);
+            }
+        }
+        let data_length = image_data.len();
+
+        // Arrange depth and array layers vertically like RGBA8 images.
+        let width = mip_dimension(self.width, mipmap);
+        let height = mip_dimension(self.height, mipmap) * mip_depth * self.layers;
+
+        image::ImageBuffer::from_raw(width, height, image_data).ok_or(
+            crate::CreateImageError::InvalidSurfaceDimensions {
+                width,
+                height,
+                data_length,
+            },
+        )
+    }
+}
+
+#[cfg(feature = "image")]
+impl SurfaceRgba16<Vec<u16>> {
+    /// Create an image for all layers and depth slices without copying.
+    ///
+    /// Fails if the surface has more than one mipmap.
+    /// Array layers and depth slices are arranged vertically from top to bottom.
+    pub fn into_image(
+        self,
+    ) -> Result<image::ImageBuffer<image::Rgba<u16>, Vec<u16>>, CreateImageError> {
+        // Arrange depth and array layers vertically.
+        let width = self.width;
+        let height = self.height * self.depth * self.layers;
+
+        if self.mipmaps > 1 {
+            return Err(CreateImageError::UnexpectedMipmapCount {
+                mipmaps: self.mipmaps,
+                max_mipmaps: 1,
+            });
+        }
+
+        let data_length = self.data.len();
+        image::ImageBuffer::from_raw(width, height, self.data).ok_or(
+            crate::CreateImageError::InvalidSurfaceDimensions {
+                width,
+                height,
+                data_length,
+            },
+        )
+    }
+}
+
+fn display_size(size: Option<usize>) -> String {
+    size.map(|s| s.to_string())
+        .unwrap_or_else(|| "overflow".to_string())
+}
+
 // TODO: Add tests for this.
 fn get_mipmap<T>(
     data: &[T],
@@ -464,10 +1465,62 @@ fn get_mipmap<T>(
     depth_level: u32,
     mipmap: u32,
 ) -> Option<&[T]> {
-    let (width, height, depth) = dimensions;
+    data.get(mipmap_range::<T>(
+        dimensions,
+        mipmaps,
+        format,
+        layer,
+        depth_level,
+        mipmap,
+    )?)
+}
+
+fn mipmap_range<T>(
+    dimensions: (u32, u32, u32),
+    mipmaps: u32,
+    format: ImageFormat,
+    layer: u32,
+    depth_level: u32,
+    mipmap: u32,
+) -> Option<Range<usize>> {
+    block_mipmap_range::<T>(
+        dimensions,
+        mipmaps,
+        (format.block_dimensions(), format.block_size_in_bytes()),
+        layer,
+        depth_level,
+        mipmap,
+    )
+}
 
-    let block_size_in_bytes = format.block_size_in_bytes();
-    let block_dimensions = format.block_dimensions();
+fn rgba16_mipmap_range(
+    dimensions: (u32, u32, u32),
+    mipmaps: u32,
+    layer: u32,
+    depth_level: u32,
+    mipmap: u32,
+) -> Option<Range<usize>> {
+    // Each pixel has 4 channels with 2 bytes each.
+    block_mipmap_range::<u16>(
+        dimensions,
+        mipmaps,
+        ((1, 1, 1), 4 * std::mem::size_of::<u16>()),
+        layer,
+        depth_level,
+        mipmap,
+    )
+}
+
+fn block_mipmap_range<T>(
+    dimensions: (u32, u32, u32),
+    mipmaps: u32,
+    block_layout: ((u32, u32, u32), usize),
+    layer: u32,
+    depth_level: u32,
+    mipmap: u32,
+) -> Option<Range<usize>> {
+    let (width, height, depth) = dimensions;
+    let (block_dimensions, block_size_in_bytes) = block_layout;
 
     // TODO: Create an error for failed offset calculations?
     let offset_in_bytes = calculate_offset(
@@ -497,5 +1550,1189 @@ fn get_mipmap<T>(
 
     let start = offset_in_bytes / std::mem::size_of::<T>();
     let count = size_in_bytes / std::mem::size_of::<T>();
-    data.get(start..start + count)
+    Some(start..start + count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "image")]
+    fn surface_rgba8_images() {
+        let surface = SurfaceRgba8 {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 2,
+            mipmaps: 3,
+            data: vec![0u8; 2 * (64 + 16 + 4)],
+        };
+        assert_eq!(
+            vec![(4, 8), (2, 4), (1, 2)],
+            surface.images().map(|i| i.dimensions()).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "image")]
+    fn surface_rgba32float_images() {
+        let surface = SurfaceRgba32Float {
+            width: 4,
+            height: 2,
+            depth: 1,
+            layers: 1,
+            mipmaps: 3,
+            data: vec![0.0; (8 + 2 + 1) * 4],
+        };
+        assert_eq!(
+            vec![(4, 2), (2, 1), (1, 1)],
+            surface.images().map(|i| i.dimensions()).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn subresource_range_layers_mipmaps() {
+        // Each layer has 16x16 + 8x8 + 4x4 BC1 blocks.
+        let surface = Surface {
+            width: 16,
+            height: 16,
+            depth: 1,
+            layers: 2,
+            mipmaps: 3,
+            image_format: ImageFormat::BC1RgbaUnorm,
+            data: vec![0u8; 2 * (128 + 32 + 8)],
+        };
+        assert_eq!(Some(0..128), surface.subresource_range(0, 0, 0));
+        assert_eq!(Some(160..168), surface.subresource_range(0, 0, 2));
+        assert_eq!(Some(296..328), surface.subresource_range(1, 0, 1));
+        assert_eq!(None, surface.subresource_range(2, 0, 0));
+        assert_eq!(None, surface.subresource_range(0, 1, 0));
+        assert_eq!(None, surface.subresource_range(0, 0, 3));
+        assert_eq!(
+            vec![
+                (0, 0, 0..128),
+                (0, 1, 128..160),
+                (0, 2, 160..168),
+                (1, 0, 168..296),
+                (1, 1, 296..328),
+                (1, 2, 328..336)
+            ],
+            surface.subresources().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn subresource_range_3d() {
+        let surface = Surface {
+            width: 4,
+            height: 4,
+            depth: 4,
+            layers: 1,
+            mipmaps: 2,
+            image_format: ImageFormat::Rgba8Unorm,
+            data: vec![0u8; 256 + 32],
+        };
+        assert_eq!(Some(64..128), surface.subresource_range(0, 1, 0));
+        assert_eq!(Some(272..288), surface.subresource_range(0, 1, 1));
+        assert_eq!(None, surface.subresource_range(0, 2, 1));
+        assert_eq!(
+            vec![(0, 0, 0..256), (0, 1, 256..288)],
+            surface.subresources().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn subresource_range_not_enough_data() {
+        let surface = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 2,
+            image_format: ImageFormat::Rgba8Unorm,
+            data: vec![0u8; 64],
+        };
+        assert_eq!(Some(0..64), surface.subresource_range(0, 0, 0));
+        assert_eq!(None, surface.subresource_range(0, 0, 1));
+        assert_eq!(
+            vec![(0, 0, 0..64)],
+            surface.subresources().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn surface_new_valid() {
+        let surface =
+            Surface::new(4, 4, 1, 1, 1, ImageFormat::BC7RgbaUnorm, vec![0u8; 16]).unwrap();
+        assert_eq!(16, surface.data.len());
+    }
+
+    #[test]
+    fn surface_new_zero_sized() {
+        assert_eq!(
+            Err(SurfaceError::ZeroSizedSurface {
+                width: 0,
+                height: 4,
+                depth: 1
+            }),
+            Surface::new(0, 4, 1, 1, 1, ImageFormat::BC7RgbaUnorm, &[0u8; 16][..])
+        );
+    }
+
+    #[test]
+    fn surface_rgba8_new_not_enough_data() {
+        assert_eq!(
+            Err(SurfaceError::NotEnoughData {
+                expected: 64,
+                actual: 60
+            }),
+            SurfaceRgba8::new(4, 4, 1, 1, 1, &[0u8; 60][..])
+        );
+    }
+
+    #[test]
+    fn surface_rgba32float_new_too_many_mipmaps() {
+        assert_eq!(
+            Err(SurfaceError::UnexpectedMipmapCount {
+                mipmaps: 4,
+                max_mipmaps: 3
+            }),
+            SurfaceRgba32Float::new(4, 4, 1, 1, 4, vec![0.0; 4 * 4 * 4])
+        );
+    }
+
+    #[test]
+    fn mip_bytes_bc7() {
+        // 9x9, 4x4, 2x2, and 1x1 mipmaps for 2 layers.
+        let data: Vec<_> = (0..(9 + 1 + 1 + 1) * 2 * 16).map(|i| i as u8).collect();
+        let surface = Surface {
+            width: 9,
+            height: 9,
+            depth: 1,
+            layers: 2,
+            mipmaps: 4,
+            image_format: ImageFormat::BC7RgbaUnorm,
+            data: data.as_slice(),
+        };
+
+        // Each mipmap is padded to 4x4 blocks.
+        let bytes = surface.mip_bytes(0, 0).unwrap();
+        assert_eq!(mip_size(12, 12, 1, 4, 4, 1, 16).unwrap(), bytes.len());
+        assert_eq!(surface.get(0, 0, 0).unwrap(), bytes);
+
+        let bytes = surface.mip_bytes(1, 2).unwrap();
+        assert_eq!(mip_size(4, 4, 1, 4, 4, 1, 16).unwrap(), bytes.len());
+        assert_eq!(&data[(12 + 10) * 16..(12 + 11) * 16], bytes);
+
+        assert_eq!(None, surface.mip_bytes(2, 0));
+        assert_eq!(None, surface.mip_bytes(0, 4));
+    }
+
+    #[test]
+    fn mip_bytes_3d() {
+        // Include all depth slices of the mipmap.
+        let data: Vec<_> = (0..(4 * 4 * 4 + 2 * 2 * 2 + 1) * 4)
+            .map(|i| i as u8)
+            .collect();
+        let surface = Surface {
+            width: 4,
+            height: 4,
+            depth: 4,
+            layers: 1,
+            mipmaps: 3,
+            image_format: ImageFormat::Rgba8Unorm,
+            data: data.as_slice(),
+        };
+        assert_eq!(
+            &data[4 * 4 * 4 * 4..(4 * 4 * 4 + 2 * 2 * 2) * 4],
+            surface.mip_bytes(0, 1).unwrap()
+        );
+    }
+
+    #[test]
+    fn mip_bytes_not_enough_data() {
+        let surface = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::BC7RgbaUnorm,
+            data: &[0u8; 15][..],
+        };
+        assert_eq!(None, surface.mip_bytes(0, 0));
+    }
+
+    fn bc7_block(first_byte: u8) -> [u8; 16] {
+        let mut block: [u8; 16] = std::array::from_fn(|i| (i * 37 + 11) as u8);
+        block[0] = first_byte;
+        block
+    }
+
+    #[test]
+    fn bc7_rotations_modes() {
+        let data: Vec<_> = [
+            0b0000_0001, // mode 0
+            0b0011_0000, // mode 4 rotation 1
+            0b0101_0000, // mode 4 rotation 2
+            0b1010_0000, // mode 5 rotation 2
+            0b1110_0000, // mode 5 rotation 3
+            0b0100_0000, // mode 6
+        ]
+        .into_iter()
+        .flat_map(bc7_block)
+        .collect();
+
+        let surface = Surface {
+            width: 24,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::BC7RgbaUnorm,
+            data: data.as_slice(),
+        };
+        assert_eq!(vec![0, 1, 2, 2, 3, 0], surface.bc7_rotations());
+    }
+
+    #[test]
+    fn bc7_rotations_swap_alpha() {
+        // Rotation 1 should swap the red and alpha channels of the decoded block.
+        let block = |rotation: u8| Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::BC7RgbaUnorm,
+            data: bc7_block(0b0010_0000 | (rotation << 6)),
+        };
+        assert_eq!(vec![1], block(1).bc7_rotations());
+
+        let expected: Vec<_> = block(0)
+            .decode_rgba8()
+            .unwrap()
+            .data
+            .chunks_exact(4)
+            .flat_map(|p| [p[3], p[1], p[2], p[0]])
+            .collect();
+        assert_eq!(expected, block(1).decode_rgba8().unwrap().data);
+    }
+
+    #[test]
+    fn bc7_rotations_other_format() {
+        let surface = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::BC3RgbaUnorm,
+            data: bc7_block(0b0010_0000),
+        };
+        assert!(surface.bc7_rotations().is_empty());
+    }
+
+    #[test]
+    fn assert_data_size_too_large() {
+        let surface = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 3,
+            image_format: ImageFormat::Rgba8Unorm,
+            data: vec![0u8; (16 + 4 + 1) * 4 + 20],
+        };
+        assert_eq!(Some(84), surface.expected_data_size());
+        assert_eq!(
+            Err(SurfaceError::UnexpectedDataSize {
+                expected: 84,
+                actual: 104
+            }),
+            surface.assert_data_size()
+        );
+    }
+
+    #[test]
+    fn assert_data_size_bc1_mipmaps() {
+        // Each mipmap uses at least one block.
+        let surface = Surface {
+            width: 8,
+            height: 8,
+            depth: 1,
+            layers: 2,
+            mipmaps: 4,
+            image_format: ImageFormat::BC1RgbaUnorm,
+            data: vec![0u8; (32 + 8 + 8 + 8) * 2],
+        };
+        assert_eq!(Ok(()), surface.assert_data_size());
+    }
+
+    #[test]
+    fn layout_report_bc7_cube() {
+        let surface = Surface {
+            width: 8,
+            height: 8,
+            depth: 1,
+            layers: 6,
+            mipmaps: 2,
+            image_format: ImageFormat::BC7RgbaUnorm,
+            data: vec![0u8; 100],
+        };
+        let report = surface.layout_report();
+        assert!(report.contains("BC7RgbaUnorm 8x8x1, 6 layers, 2 mipmaps"));
+        assert!(report.contains("block dimensions 4x4x1, block size 16 bytes"));
+        assert!(report.contains("layer 1 mipmap 1: offset 144, size 16"));
+        assert!(report.ends_with("expected size 480, actual size 100"));
+    }
+
+    #[test]
+    fn get_mut_mipmap() {
+        let mut surface = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 2,
+            mipmaps: 3,
+            image_format: ImageFormat::Rgba8Unorm,
+            data: vec![0u8; (16 + 4 + 1) * 4 * 2],
+        };
+        surface.get_mut(1, 0, 1).unwrap().fill(255);
+
+        // Only layer 1 mip 1 should change.
+        assert_eq!(Some(&[0u8; 16 * 4][..]), surface.get(1, 0, 0));
+        assert_eq!(Some(&[255u8; 4 * 4][..]), surface.get(1, 0, 1));
+        assert_eq!(Some(&[0u8; 4][..]), surface.get(1, 0, 2));
+        assert!(surface.data[..(16 + 4 + 1) * 4].iter().all(|u| *u == 0));
+        assert_eq!(
+            &[255u8; 4 * 4][..],
+            &surface.data[(16 + 4 + 1 + 16) * 4..(16 + 4 + 1 + 16 + 4) * 4]
+        );
+    }
+
+    #[test]
+    fn get_mut_out_of_bounds() {
+        let mut surface = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::Rgba8Unorm,
+            data: vec![0u8; 16 * 4],
+        };
+        assert_eq!(None, surface.get_mut(1, 0, 0));
+    }
+
+    #[cfg(feature = "encode")]
+    #[test]
+    fn combine_mips_encoded() {
+        use crate::{Mipmaps, Quality};
+
+        // Encode each mipmap with different settings.
+        let mipmaps: Vec<_> = [(8, Quality::Slow), (4, Quality::Normal), (2, Quality::Fast)]
+            .into_iter()
+            .map(|(size, quality)| {
+                let data: Vec<_> = (0..size * size * 2 * 4).map(|i| (i * 7) as u8).collect();
+                SurfaceRgba8 {
+                    width: size,
+                    height: size,
+                    depth: 1,
+                    layers: 2,
+                    mipmaps: 1,
+                    data,
+                }
+                .encode(ImageFormat::BC7RgbaUnorm, quality, Mipmaps::Disabled)
+                .unwrap()
+            })
+            .collect();
+
+        let combined = Surface::combine_mips(&mipmaps).unwrap();
+        assert_eq!(8, combined.width);
+        assert_eq!(8, combined.height);
+        assert_eq!(2, combined.layers);
+        assert_eq!(3, combined.mipmaps);
+
+        for (mipmap, surface) in mipmaps.iter().enumerate() {
+            let mipmap = mipmap as u32;
+            assert_eq!(
+                surface.decode_rgba8().unwrap().data,
+                combined
+                    .decode_layers_mipmaps_rgba8(0..2, mipmap..mipmap + 1)
+                    .unwrap()
+                    .data
+            );
+        }
+    }
+
+    #[test]
+    fn combine_mips_invalid_dimensions() {
+        let surface = |width, height| Surface {
+            width,
+            height,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::Rgba8Unorm,
+            data: vec![0u8; width as usize * height as usize * 4],
+        };
+        assert_eq!(
+            Err(SurfaceError::IncompatibleMipmap { mipmap: 2 }),
+            Surface::combine_mips(&[surface(4, 4), surface(2, 2), surface(2, 2)])
+        );
+    }
+
+    #[test]
+    fn combine_mips_empty() {
+        assert_eq!(
+            Err(SurfaceError::ZeroSizedSurface {
+                width: 0,
+                height: 0,
+                depth: 0
+            }),
+            Surface::combine_mips::<Vec<u8>>(&[])
+        );
+    }
+
+    #[test]
+    fn check_gpu_limits_default() {
+        let surface = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 6,
+            mipmaps: 3,
+            image_format: ImageFormat::Rgba8Unorm,
+            data: Vec::new(),
+        };
+        assert_eq!(Ok(()), surface.check_gpu_limits(GpuLimits::default()));
+    }
+
+    #[test]
+    fn check_gpu_limits_max_dimension() {
+        let surface = Surface {
+            width: 8,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::Rgba8Unorm,
+            data: Vec::new(),
+        };
+        assert_eq!(
+            Err(SurfaceError::ExceedsMaxDimension {
+                width: 8,
+                height: 4,
+                depth: 1,
+                max_dimension: 4
+            }),
+            surface.check_gpu_limits(GpuLimits {
+                max_dimension_2d: 4,
+                ..Default::default()
+            })
+        );
+    }
+
+    #[test]
+    fn check_gpu_limits_max_dimension_3d() {
+        let surface = Surface {
+            width: 4,
+            height: 4,
+            depth: 8,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::Rgba8Unorm,
+            data: Vec::new(),
+        };
+        assert_eq!(
+            Err(SurfaceError::ExceedsMaxDimension {
+                width: 4,
+                height: 4,
+                depth: 8,
+                max_dimension: 4
+            }),
+            surface.check_gpu_limits(GpuLimits {
+                max_dimension_3d: 4,
+                ..Default::default()
+            })
+        );
+    }
+
+    #[test]
+    fn check_gpu_limits_max_array_layers() {
+        let surface = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 6,
+            mipmaps: 1,
+            image_format: ImageFormat::Rgba8Unorm,
+            data: Vec::new(),
+        };
+        assert_eq!(
+            Err(SurfaceError::ExceedsMaxArrayLayers {
+                layers: 6,
+                max_array_layers: 1
+            }),
+            surface.check_gpu_limits(GpuLimits {
+                max_array_layers: 1,
+                ..Default::default()
+            })
+        );
+    }
+
+    #[test]
+    fn check_gpu_limits_mipmaps() {
+        let surface = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 4,
+            image_format: ImageFormat::Rgba8Unorm,
+            data: Vec::new(),
+        };
+        assert_eq!(
+            Err(SurfaceError::UnexpectedMipmapCount {
+                mipmaps: 4,
+                max_mipmaps: 3
+            }),
+            surface.check_gpu_limits(GpuLimits::default())
+        );
+    }
+
+    #[test]
+    fn check_gpu_limits_power_of_two() {
+        let surface = Surface {
+            width: 4,
+            height: 3,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::Rgba8Unorm,
+            data: Vec::new(),
+        };
+        assert_eq!(Ok(()), surface.check_gpu_limits(GpuLimits::default()));
+        assert_eq!(
+            Err(SurfaceError::NonPowerOfTwoDimensions {
+                width: 4,
+                height: 3,
+                depth: 1
+            }),
+            surface.check_gpu_limits(GpuLimits {
+                require_power_of_two: true,
+                ..Default::default()
+            })
+        );
+    }
+
+    #[test]
+    fn fill_channel_zero_alpha() {
+        let surface = SurfaceRgba8 {
+            width: 2,
+            height: 1,
+            depth: 1,
+            layers: 1,
+            mipmaps: 2,
+            data: vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12],
+        };
+        assert_eq!(
+            SurfaceRgba8 {
+                width: 2,
+                height: 1,
+                depth: 1,
+                layers: 1,
+                mipmaps: 2,
+                data: vec![1, 2, 3, 0, 5, 6, 7, 0, 9, 10, 11, 0],
+            },
+            surface.fill_channel(3, 0)
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn fill_channel_invalid_channel() {
+        let surface = SurfaceRgba8 {
+            width: 1,
+            height: 1,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            data: vec![0u8; 4],
+        };
+        surface.fill_channel(4, 0);
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn unpack_channels_rgba() {
+        let surface = SurfaceRgba8 {
+            width: 2,
+            height: 1,
+            depth: 1,
+            layers: 1,
+            mipmaps: 2,
+            data: vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12],
+        };
+        let [r, g, b, a] = surface.unpack_channels().unwrap();
+        assert_eq!((2, 1), r.dimensions());
+        assert_eq!(&[1, 5], r.as_raw().as_slice());
+        assert_eq!(&[2, 6], g.as_raw().as_slice());
+        assert_eq!(&[3, 7], b.as_raw().as_slice());
+        assert_eq!(&[4, 8], a.as_raw().as_slice());
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn upsample_to_2x2_to_4x4() {
+        let surface = SurfaceRgba8 {
+            width: 2,
+            height: 2,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            data: vec![
+                0, 0, 0, 255, 255, 255, 255, 255, 0, 0, 0, 255, 255, 255, 255, 255,
+            ],
+        };
+        let upsampled = surface
+            .upsample_to(4, 4, image::imageops::FilterType::Triangle)
+            .unwrap();
+        assert_eq!(4, upsampled.width);
+        assert_eq!(4, upsampled.height);
+        assert_eq!(1, upsampled.mipmaps);
+        // Each row should blend from black to white.
+        for row in upsampled.data.chunks_exact(4 * 4) {
+            assert_eq!(
+                &[0, 0, 0, 255, 64, 64, 64, 255, 191, 191, 191, 255, 255, 255, 255, 255],
+                row
+            );
+        }
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn surface_rgba16_into_image() {
+        let image = SurfaceRgba16 {
+            width: 1,
+            height: 1,
+            depth: 1,
+            layers: 2,
+            mipmaps: 1,
+            data: vec![1u16, 2, 3, 4, 65535, 0, 65535, 0],
+        }
+        .into_image()
+        .unwrap();
+
+        assert_eq!((1, 2), image.dimensions());
+        assert_eq!(
+            &[1u16, 2, 3, 4, 65535, 0, 65535, 0],
+            image.as_raw().as_slice()
+        );
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn surface_rgba16_into_image_mipmaps() {
+        let result = SurfaceRgba16 {
+            width: 2,
+            height: 2,
+            depth: 1,
+            layers: 1,
+            mipmaps: 2,
+            data: vec![0u16; (2 * 2 + 1) * 4],
+        }
+        .into_image();
+
+        assert!(matches!(
+            result,
+            Err(CreateImageError::UnexpectedMipmapCount {
+                mipmaps: 2,
+                max_mipmaps: 1
+            })
+        ));
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn surface_rgba16_to_image_mipmap() {
+        // Select the 1x1 mipmap from both layers.
+        let data: Vec<_> = (0..(2 * 2 + 1) * 4 * 2).map(|i| i as u16).collect();
+        let image = SurfaceRgba16 {
+            width: 2,
+            height: 2,
+            depth: 1,
+            layers: 2,
+            mipmaps: 2,
+            data,
+        }
+        .to_image(1)
+        .unwrap();
+
+        assert_eq!((1, 2), image.dimensions());
+        assert_eq!(
+            &[16u16, 17, 18, 19, 36, 37, 38, 39],
+            image.as_raw().as_slice()
+        );
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn surface_rgba16_to_image_3d_mipmap() {
+        // The 2x2x1 mipmap only has a single depth slice.
+        let data: Vec<_> = (0..(4 * 4 * 2 + 2 * 2) * 4).map(|i| i as u16).collect();
+        let image = SurfaceRgba16 {
+            width: 4,
+            height: 4,
+            depth: 2,
+            layers: 1,
+            mipmaps: 2,
+            data,
+        }
+        .to_image(1)
+        .unwrap();
+
+        assert_eq!((2, 2), image.dimensions());
+        assert_eq!(&(128..144).collect::<Vec<u16>>(), image.as_raw());
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn surface_rgba16_to_image_invalid_mipmap() {
+        let result = SurfaceRgba16 {
+            width: 2,
+            height: 2,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            data: vec![0u16; 2 * 2 * 4],
+        }
+        .to_image(1);
+
+        assert!(matches!(
+            result,
+            Err(CreateImageError::DecompressSurface(
+                SurfaceError::MipmapDataOutOfBounds {
+                    layer: 0,
+                    mipmap: 1
+                }
+            ))
+        ));
+    }
+
+    #[test]
+    fn swizzle_rgba8_surface() {
+        let mut surface = SurfaceRgba8 {
+            width: 1,
+            height: 1,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            data: vec![1u8, 2, 3, 4],
+        };
+        surface.swizzle([
+            crate::Channel::B,
+            crate::Channel::G,
+            crate::Channel::R,
+            crate::Channel::A,
+        ]);
+        assert_eq!(vec![3, 2, 1, 4], surface.data);
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn write_to_image_reuse() {
+        let mut image = image::RgbaImage::new(1, 2);
+
+        let decode = |data: &'static [u8]| {
+            Surface {
+                width: 1,
+                height: 1,
+                depth: 1,
+                layers: 2,
+                mipmaps: 1,
+                image_format: ImageFormat::Bgra8Unorm,
+                data,
+            }
+            .decode_rgba8()
+            .unwrap()
+        };
+        decode(&[3, 2, 1, 4, 7, 6, 5, 8])
+            .write_to_image(0, &mut image)
+            .unwrap();
+        assert_eq!(&[1, 2, 3, 4, 5, 6, 7, 8], image.as_raw().as_slice());
+
+        decode(&[6, 7, 8, 5, 2, 3, 4, 1])
+            .write_to_image(0, &mut image)
+            .unwrap();
+        assert_eq!(&[8, 7, 6, 5, 4, 3, 2, 1], image.as_raw().as_slice());
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn write_to_image_invalid_dimensions() {
+        let mut image = image::RgbaImage::new(2, 2);
+        let result = SurfaceRgba8 {
+            width: 2,
+            height: 2,
+            depth: 1,
+            layers: 1,
+            mipmaps: 2,
+            data: &[0u8; (2 * 2 + 1) * 4][..],
+        }
+        .write_to_image(1, &mut image);
+
+        assert!(matches!(
+            result,
+            Err(CreateImageError::UnexpectedImageDimensions {
+                width: 2,
+                height: 2,
+                expected_width: 1,
+                expected_height: 1
+            })
+        ));
+    }
+
+    fn bc1_solid_block(color: u16) -> [u8; 8] {
+        // All pixels use the first endpoint color.
+        let [lo, hi] = color.to_le_bytes();
+        [lo, hi, lo, hi, 0, 0, 0, 0]
+    }
+
+    #[test]
+    fn repad_overflow() {
+        let surface = Surface {
+            width: 8,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::BC1RgbaUnorm,
+            data: &[0u8; 16][..],
+        };
+        assert!(matches!(
+            surface.repad(BlockPadding::MultipleOfBlocks(u32::MAX)),
+            Err(SurfaceError::PaddedDimensionsWouldOverflow {
+                width: 8,
+                height: 4,
+                padding: BlockPadding::MultipleOfBlocks(u32::MAX)
+            })
+        ));
+    }
+
+    #[test]
+    fn repad_bc1_duplicate_edge_blocks() {
+        // A 5x5 surface has 2x2 blocks with red, green, blue, and white.
+        let data: Vec<_> = [0xF800, 0x07E0, 0x001F, 0xFFFF]
+            .into_iter()
+            .flat_map(bc1_solid_block)
+            .collect();
+        let surface = Surface {
+            width: 5,
+            height: 5,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::BC1RgbaUnorm,
+            data,
+        }
+        .repad(BlockPadding::MultipleOfBlocks(3))
+        .unwrap();
+
+        assert_eq!((12, 12), (surface.width, surface.height));
+        assert_eq!(9 * 8, surface.data.len());
+
+        let rgba8 = surface.decode_rgba8().unwrap();
+        let pixel = |x: usize, y: usize| &rgba8.data[(y * 12 + x) * 4..(y * 12 + x) * 4 + 4];
+        assert_eq!(&[255, 0, 0, 255], pixel(0, 0));
+        assert_eq!(&[0, 255, 0, 255], pixel(4, 0));
+        assert_eq!(&[0, 255, 0, 255], pixel(11, 0));
+        assert_eq!(&[0, 0, 255, 255], pixel(0, 11));
+        assert_eq!(&[255, 255, 255, 255], pixel(4, 4));
+        assert_eq!(&[255, 255, 255, 255], pixel(11, 11));
+    }
+
+    #[test]
+    fn repad_bc7_mipmaps_power_of_two() {
+        // 12x4 has 3x1 blocks and 6x2 has 2x1 blocks.
+        let surface = Surface {
+            width: 12,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 2,
+            image_format: ImageFormat::BC7RgbaUnorm,
+            data: (0..5).flat_map(|i| [i; 16]).collect::<Vec<u8>>(),
+        }
+        .repad(BlockPadding::PowerOfTwo)
+        .unwrap();
+
+        assert_eq!((16, 4), (surface.width, surface.height));
+        assert_eq!(
+            vec![0, 1, 2, 2, 3, 4],
+            surface
+                .data
+                .chunks_exact(16)
+                .map(|b| b[0])
+                .collect::<Vec<_>>()
+        );
+    }
+
+    fn solid_rgba8(width: u32, height: u32, value: u8) -> SurfaceRgba8<Vec<u8>> {
+        SurfaceRgba8 {
+            width,
+            height,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            data: vec![value; width as usize * height as usize * 4],
+        }
+    }
+
+    #[test]
+    fn concat_horizontal_4x4() {
+        let surface = solid_rgba8(4, 4, 1)
+            .concat_horizontal(&solid_rgba8(4, 4, 2))
+            .unwrap();
+
+        assert_eq!((8, 4, 1), (surface.width, surface.height, surface.mipmaps));
+        let row: Vec<_> = [[1u8; 16], [2u8; 16]].concat();
+        assert_eq!(row.repeat(4), surface.data);
+    }
+
+    #[test]
+    fn concat_vertical_4x4() {
+        let surface = solid_rgba8(4, 4, 1)
+            .concat_vertical(&solid_rgba8(4, 4, 2))
+            .unwrap();
+
+        assert_eq!((4, 8, 1), (surface.width, surface.height, surface.mipmaps));
+        assert_eq!([[1u8; 64], [2u8; 64]].concat(), surface.data);
+    }
+
+    #[test]
+    fn concat_horizontal_different_height() {
+        let result = solid_rgba8(4, 4, 1).concat_horizontal(&solid_rgba8(4, 2, 2));
+        assert!(matches!(
+            result,
+            Err(SurfaceError::IncompatibleSurfaceDimensions {
+                height: 4,
+                other_height: 2,
+                ..
+            })
+        ));
+        assert!(solid_rgba8(4, 4, 1)
+            .concat_vertical(&solid_rgba8(4, 2, 2))
+            .is_ok());
+    }
+
+    #[test]
+    fn channel_stats_rgbaf32() {
+        let surface = SurfaceRgba32Float {
+            width: 2,
+            height: 1,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            data: vec![-1.0, 0.0, 2.0, 1.0, 3.0, 0.5, 2.0, 1.0],
+        };
+        assert_eq!(
+            [
+                ChannelStats {
+                    min: -1.0,
+                    max: 3.0,
+                    mean: 1.0
+                },
+                ChannelStats {
+                    min: 0.0,
+                    max: 0.5,
+                    mean: 0.25
+                },
+                ChannelStats {
+                    min: 2.0,
+                    max: 2.0,
+                    mean: 2.0
+                },
+                ChannelStats {
+                    min: 1.0,
+                    max: 1.0,
+                    mean: 1.0
+                },
+            ],
+            surface.channel_stats()
+        );
+        assert!(!surface.has_alpha_variation());
+    }
+
+    #[test]
+    fn has_alpha_variation_rgbaf32() {
+        let surface = SurfaceRgba32Float {
+            width: 2,
+            height: 1,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            data: vec![0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.5],
+        };
+        assert!(surface.has_alpha_variation());
+    }
+
+    #[test]
+    fn from_face_slices_cube() {
+        let faces: [Vec<u8>; 6] = std::array::from_fn(|i| vec![i as u8; 2 * 2 * 4]);
+        let surface =
+            SurfaceRgba8::from_face_slices(&faces.each_ref().map(|f| f.as_slice()), 2, 2).unwrap();
+        assert_eq!(
+            (2, 2, 1, 6, 1),
+            (
+                surface.width,
+                surface.height,
+                surface.depth,
+                surface.layers,
+                surface.mipmaps
+            )
+        );
+        for (layer, face) in faces.iter().enumerate() {
+            assert_eq!(Some(face.as_slice()), surface.get(layer as u32, 0, 0));
+        }
+    }
+
+    #[test]
+    fn from_face_slices_not_enough_data() {
+        let face = [0u8; 2 * 2 * 4];
+        let small = [0u8; 4];
+        let faces = [&face[..], &face, &face, &small, &face, &face];
+        assert_eq!(
+            Err(SurfaceError::NotEnoughData {
+                expected: 16,
+                actual: 4
+            }),
+            SurfaceRgba8::from_face_slices(&faces, 2, 2)
+        );
+    }
+
+    #[test]
+    fn from_face_slices_zero_size() {
+        let faces = [&[][..]; 6];
+        assert_eq!(
+            Err(SurfaceError::ZeroSizedSurface {
+                width: 0,
+                height: 0,
+                depth: 1
+            }),
+            SurfaceRgba8::from_face_slices(&faces, 0, 0)
+        );
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn from_faces_mismatched_dimensions() {
+        let face = image::RgbaImage::new(2, 2);
+        let other = image::RgbaImage::new(4, 2);
+        assert_eq!(
+            Err(SurfaceError::IncompatibleSurfaceDimensions {
+                width: 2,
+                height: 2,
+                depth: 1,
+                layers: 1,
+                other_width: 4,
+                other_height: 2,
+                other_depth: 1,
+                other_layers: 1,
+            }),
+            SurfaceRgba8::from_faces([&face, &face, &face, &face, &face, &other])
+        );
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn from_faces_cube() {
+        let faces: [_; 6] =
+            std::array::from_fn(|i| image::RgbaImage::from_pixel(2, 2, image::Rgba([i as u8; 4])));
+        let surface = SurfaceRgba8::from_faces(faces.each_ref()).unwrap();
+        assert_eq!(6, surface.layers);
+        assert_eq!(Some(&[5u8; 16][..]), surface.get(5, 0, 0));
+    }
+
+    #[test]
+    fn difference_identical_surfaces() {
+        let data: Vec<_> = (0..(4 * 4 + 2 * 2 + 1) * 4 * 2).map(|i| i as f32).collect();
+        let surface = SurfaceRgba32Float {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 2,
+            mipmaps: 3,
+            data: data.as_slice(),
+        };
+
+        let difference = surface.difference(&surface).unwrap();
+        assert_eq!(
+            (4, 4, 2, 3),
+            (
+                difference.width,
+                difference.height,
+                difference.layers,
+                difference.mipmaps
+            )
+        );
+        assert_eq!(vec![0.0; data.len()], difference.data);
+    }
+
+    #[test]
+    fn difference_signed() {
+        let surface = |data| SurfaceRgba32Float {
+            width: 1,
+            height: 1,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            data,
+        };
+        let difference = surface(vec![1.0, 0.5, 0.0, 1.0])
+            .difference(&surface(vec![0.5, 1.0, 0.0, 0.0]))
+            .unwrap();
+        assert_eq!(vec![0.5, -0.5, 0.0, 1.0], difference.data);
+    }
+
+    #[test]
+    fn difference_mismatched_dimensions() {
+        let surface = |width| SurfaceRgba32Float {
+            width,
+            height: 1,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            data: vec![0.0; width as usize * 4],
+        };
+        assert!(matches!(
+            surface(1).difference(&surface(2)),
+            Err(SurfaceError::IncompatibleSurfaceDimensions {
+                width: 1,
+                other_width: 2,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn difference_mismatched_mipmaps() {
+        let surface = |mipmaps| SurfaceRgba32Float {
+            width: 2,
+            height: 2,
+            depth: 1,
+            layers: 1,
+            mipmaps,
+            data: vec![0.0; if mipmaps == 1 { 16 } else { 20 }],
+        };
+        assert!(matches!(
+            surface(2).difference(&surface(1)),
+            Err(SurfaceError::IncompatibleMipmapCount {
+                mipmaps: 2,
+                other_mipmaps: 1
+            })
+        ));
+    }
 }